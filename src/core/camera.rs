@@ -2,6 +2,8 @@ use std::{cell::RefCell, f32::consts::PI};
 
 use poli_math::{Matrix4, Vector3};
 
+use crate::math::vector::{from_array, transform_point};
+
 /// Contains different kinds of cameras.
 pub enum CameraKind {
     /// A camera that uses perspective projection, similar to a pinhole camera.
@@ -20,6 +22,31 @@ pub enum CameraKind {
         /// Can be set to [`INFINITY`](std::f32::INFINITY).
         far: f32,
     },
+    /// A camera that uses orthographic projection, where the size of objects
+    /// does not depend on their distance to the camera. Useful for CAD and 2D
+    /// overlays.
+    ///
+    /// The planes are specified in view space, where the camera looks along
+    /// the -Z axis. For example, `left: -2.0, right: 2.0` shows 2 units to
+    /// each side of the camera.
+    OrthographicCamera {
+        /// The left plane of the camera frustum.
+        left: f32,
+        /// The right plane of the camera frustum.
+        right: f32,
+        /// The top plane of the camera frustum.
+        top: f32,
+        /// The bottom plane of the camera frustum.
+        bottom: f32,
+        /// The near plane of the camera frustum. Objects closer to the camera
+        /// than this amount in the view space's z axis will not get rendered.
+        /// Can be negative to also render objects behind the camera.
+        near: f32,
+        /// The far plane of the camera frustum. Objects further to the camera
+        /// than this amount in the view space's z axis will not get rendered.
+        /// Must be greater than [`near`][#near] and finite.
+        far: f32,
+    },
 }
 
 /// The view point from which 3D scenes are rendered.
@@ -39,6 +66,12 @@ pub struct Camera {
     pub projection_matrix_inverse: Matrix4,
     /// The kind of camera e.g. perspective, orthographic.
     pub kind: RefCell<CameraKind>,
+    /// The zoom factor. Values greater than `1.0` magnify the view: a
+    /// perspective camera narrows its field of view, and an orthographic
+    /// camera shrinks its frustum around its center. Default is `1.0`. Call
+    /// [`update_projection_matrix`](Self::update_projection_matrix) after
+    /// changing it.
+    pub zoom: f32,
     /// The position of the camera in world space, for writing into the camera
     /// position buffer. Do not modify this property directly, instead use
     /// transformation methods on the encapsulating [`Object3D`](super::Object3d).
@@ -48,14 +81,83 @@ pub struct Camera {
 impl Camera {
     /// Creates a new camera of the specified kind.
     pub fn new(kind: CameraKind) -> Self {
-        let projection_matrix = match kind {
+        let projection_matrix = Self::compute_projection_matrix(&kind, 1.0);
+
+        Self {
+            view_matrix: Matrix4::identity(),
+            projection_matrix,
+            projection_matrix_inverse: projection_matrix.inverse(),
+            kind: RefCell::new(kind),
+            zoom: 1.0,
+            position: Vector3::default(),
+        }
+    }
+
+    /// Updates the projection matrix based on the current camera parameters.
+    /// Use this method after you have manually change any of the camera's
+    /// parameters e.g. aspect ratio.
+    pub fn update_projection_matrix(&mut self) {
+        self.projection_matrix = Self::compute_projection_matrix(&self.kind.borrow(), self.zoom);
+        self.projection_matrix_inverse = self.projection_matrix.inverse();
+    }
+
+    /// Returns the near and far distances of the camera frustum along the
+    /// camera's view direction.
+    pub fn near_far(&self) -> (f32, f32) {
+        match *self.kind.borrow() {
+            CameraKind::PerspectiveCamera { near, far, .. }
+            | CameraKind::OrthographicCamera { near, far, .. } => (near, far),
+        }
+    }
+
+    /// Returns the corners of the slice of the camera frustum between the
+    /// specified distances along the camera's view direction, in world space.
+    /// The distances need not be within the camera's near and far planes, and
+    /// must be finite.
+    ///
+    /// The corners are found by unprojecting the corners of the near plane
+    /// with the [inverse projection matrix](Self::projection_matrix_inverse),
+    /// so they take the [zoom](Self::zoom) into account. They are ordered
+    /// left-bottom, right-bottom, left-top, right-top on the near slice, then
+    /// the same on the far slice.
+    pub fn frustum_corners(&self, near: f32, far: f32) -> [Vector3; 8] {
+        let camera_matrix = self.view_matrix.inverse();
+
+        let corners = [[-1.0, -1.0], [1.0, -1.0], [-1.0, 1.0], [1.0, 1.0]].map(|[x, y]| {
+            // Two points on the line through the corner, which converges at
+            // the camera for perspective projection and is parallel to the
+            // view direction for orthographic projection. The far plane may
+            // be at infinity, so the second point is halfway through the
+            // depth range.
+            let a = transform_point(&self.projection_matrix_inverse, [x, y, 0.0]);
+            let b = transform_point(&self.projection_matrix_inverse, [x, y, 0.5]);
+
+            // The camera looks along -Z, so a distance `d` is at `z = -d`.
+            move |distance: f32| {
+                let t = (-distance - a[2]) / (b[2] - a[2]);
+
+                transform_point(&camera_matrix, [0, 1, 2].map(|i| a[i] + t * (b[i] - a[i])))
+            }
+        });
+
+        [near, far]
+            .map(|distance| corners.map(|corner| from_array(corner(distance))))
+            .concat()
+            .try_into()
+            .unwrap()
+    }
+
+    /// Computes the projection matrix of the specified kind of camera, which
+    /// maps the view-space depth range to `0.0..=1.0` as in WebGPU.
+    pub(crate) fn compute_projection_matrix(kind: &CameraKind, zoom: f32) -> Matrix4 {
+        match *kind {
             CameraKind::PerspectiveCamera {
                 vfov_radians,
                 aspect,
                 near,
                 far,
             } => {
-                let f = (PI * 0.5 - 0.5 * vfov_radians).tan();
+                let f = (PI * 0.5 - 0.5 * vfov_radians).tan() * zoom;
 
                 let (n33, n34) = if far.is_finite() {
                     let range_inv = 1.0 / (near - far);
@@ -85,44 +187,116 @@ impl Camera {
                     ],
                 }
             }
-        };
-
-        Self {
-            view_matrix: Matrix4::identity(),
-            projection_matrix,
-            projection_matrix_inverse: projection_matrix.inverse(),
-            kind: RefCell::new(kind),
-            position: Vector3::default(),
-        }
-    }
-
-    /// Updates the projection matrix based on the current camera parameters.
-    /// Use this method after you have manually change any of the camera's
-    /// parameters e.g. aspect ratio.
-    pub fn update_projection_matrix(&mut self) {
-        match *self.kind.borrow() {
-            CameraKind::PerspectiveCamera {
-                vfov_radians,
-                aspect,
+            CameraKind::OrthographicCamera {
+                left,
+                right,
+                top,
+                bottom,
                 near,
                 far,
             } => {
-                let f = (PI * 0.5 - 0.5 * vfov_radians).tan();
-
-                let (n33, n34) = if far.is_finite() {
-                    let range_inv = 1.0 / (near - far);
-                    (far * range_inv, far * near * range_inv)
-                } else {
-                    (-1.0, -near)
-                };
+                // Zooming scales the frustum around its center.
+                let width = (right - left) / zoom;
+                let height = (top - bottom) / zoom;
+                let center_x = (left + right) * 0.5;
+                let center_y = (top + bottom) * 0.5;
+                let range_inv = 1.0 / (near - far);
 
-                self.projection_matrix.elements[0] = f / aspect;
-                self.projection_matrix.elements[5] = f;
-                self.projection_matrix.elements[10] = n33;
-                self.projection_matrix.elements[14] = n34;
+                Matrix4 {
+                    elements: [
+                        2.0 / width,
+                        0.0,
+                        0.0,
+                        0.0,
+                        0.0,
+                        2.0 / height,
+                        0.0,
+                        0.0,
+                        0.0,
+                        0.0,
+                        range_inv,
+                        0.0,
+                        -2.0 * center_x / width,
+                        -2.0 * center_y / height,
+                        near * range_inv,
+                        1.0,
+                    ],
+                }
             }
         }
+    }
+}
 
-        self.projection_matrix_inverse = self.projection_matrix.inverse();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the specified view-space point in normalized device
+    /// coordinates.
+    fn project(camera: &Camera, point: [f32; 3]) -> [f32; 3] {
+        transform_point(&camera.projection_matrix, point)
+    }
+
+    #[track_caller]
+    fn assert_near(actual: [f32; 3], expected: [f32; 3]) {
+        assert!(
+            actual
+                .iter()
+                .zip(expected)
+                .all(|(a, e)| (a - e).abs() < 1e-5),
+            "{actual:?} is not {expected:?}"
+        );
+    }
+
+    fn orthographic() -> Camera {
+        Camera::new(CameraKind::OrthographicCamera {
+            left: -2.0,
+            right: 6.0,
+            top: 3.0,
+            bottom: -1.0,
+            near: 1.0,
+            far: 11.0,
+        })
+    }
+
+    #[test]
+    fn orthographic_frustum_maps_to_webgpu_ndc() {
+        let camera = orthographic();
+
+        assert_near(project(&camera, [-2.0, -1.0, -1.0]), [-1.0, -1.0, 0.0]);
+        assert_near(project(&camera, [6.0, 3.0, -11.0]), [1.0, 1.0, 1.0]);
+        assert_near(project(&camera, [2.0, 1.0, -6.0]), [0.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn zoom_shrinks_orthographic_frustum_around_its_center() {
+        let mut camera = orthographic();
+
+        camera.zoom = 2.0;
+        camera.update_projection_matrix();
+
+        // The frustum is half as wide and high, and keeps its depth range.
+        assert_near(project(&camera, [0.0, 0.0, -1.0]), [-1.0, -1.0, 0.0]);
+        assert_near(project(&camera, [4.0, 2.0, -11.0]), [1.0, 1.0, 1.0]);
+        assert_near(project(&camera, [2.0, 1.0, -6.0]), [0.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn perspective_frustum_maps_to_webgpu_depth_range() {
+        let mut camera = Camera::new(CameraKind::PerspectiveCamera {
+            vfov_radians: PI / 2.0,
+            aspect: 2.0,
+            near: 1.0,
+            far: 10.0,
+        });
+
+        // A field of view of 90° spans as many units up as away.
+        assert_near(project(&camera, [2.0, 1.0, -1.0]), [1.0, 1.0, 0.0]);
+        assert_near(project(&camera, [0.0, 0.0, -10.0]), [0.0, 0.0, 1.0]);
+
+        camera.zoom = 2.0;
+        camera.update_projection_matrix();
+
+        assert_near(project(&camera, [1.0, 0.5, -1.0]), [1.0, 1.0, 0.0]);
     }
 }
@@ -0,0 +1,225 @@
+use std::{cell::RefCell, ops::Range, rc::Rc};
+
+use poli_math::{Color, Matrix4};
+
+use crate::{
+    geometries::BufferGeometry,
+    materials::Material,
+    math::{
+        color::to_linear,
+        linear_to_srgb,
+        vector::{length, max_scale, sub, to_array, transform_point},
+        Box3, Sphere,
+    },
+};
+
+/// Many copies of a mesh that share a geometry and a material, but have their
+/// own transformations and colors. Renderers draw all copies (instances) in a
+/// single draw call, which is much faster than drawing a [mesh](super::Mesh)
+/// per copy, e.g. for the trees of a forest.
+pub struct InstancedMesh {
+    /// The triangular polygon geometry of every instance.
+    pub geometry: Rc<BufferGeometry>,
+    /// The material of every instance. The color of each instance multiplies
+    /// the color of the material.
+    pub material: Rc<dyn Material>,
+    /// The per-instance data, laid out as in the vertex buffer of renderers.
+    instances: RefCell<Vec<Instance>>,
+    /// The range of instances that have been modified since renderers last
+    /// uploaded them.
+    updated_range: RefCell<Option<Range<usize>>>,
+    /// The cached result of [`compute_bounding_box`](Self::compute_bounding_box),
+    /// along with the [version](BufferGeometry::version) of the geometry it
+    /// was computed for.
+    bounding_box: RefCell<Option<(u64, Box3)>>,
+    /// The cached result of
+    /// [`compute_bounding_sphere`](Self::compute_bounding_sphere), along with
+    /// the version of the geometry it was computed for.
+    bounding_sphere: RefCell<Option<(u64, Sphere)>>,
+}
+
+/// Per-instance data of an [`InstancedMesh`].
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub(crate) struct Instance {
+    pub(crate) matrix: Matrix4,
+    /// The linear color of the instance.
+    pub(crate) color: [f32; 4],
+}
+
+impl Instance {
+    /// An instance with no transformation and a white color.
+    pub(crate) const IDENTITY: Instance = Instance {
+        matrix: Matrix4 {
+            elements: [
+                1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+            ],
+        },
+        color: [1.0; 4],
+    };
+}
+
+impl InstancedMesh {
+    /// Creates an instanced mesh with the specified geometry, material, and
+    /// number of instances. All instances are at the origin of the instanced
+    /// mesh and are white.
+    pub fn new(geometry: Rc<BufferGeometry>, material: Rc<dyn Material>, count: usize) -> Self {
+        Self {
+            geometry,
+            material,
+            instances: RefCell::new(vec![Instance::IDENTITY; count]),
+            updated_range: RefCell::new(None),
+            bounding_box: RefCell::new(None),
+            bounding_sphere: RefCell::new(None),
+        }
+    }
+
+    /// Returns the number of instances.
+    pub fn count(&self) -> usize {
+        self.instances.borrow().len()
+    }
+
+    /// Returns the transformation of the instance at the specified index,
+    /// relative to the instanced mesh.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn matrix_at(&self, index: usize) -> Matrix4 {
+        self.instances.borrow()[index].matrix
+    }
+
+    /// Sets the transformation of the instance at the specified index,
+    /// relative to the instanced mesh. Only the modified instances are
+    /// uploaded to the GPU again. Also discards the cached bounding volumes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn set_matrix_at(&self, index: usize, matrix: &Matrix4) {
+        self.instances.borrow_mut()[index].matrix = *matrix;
+        self.mark_updated(index);
+        *self.bounding_box.borrow_mut() = None;
+        *self.bounding_sphere.borrow_mut() = None;
+    }
+
+    /// Returns the color of the instance at the specified index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn color_at(&self, index: usize) -> Color {
+        let [r, g, b, _] = self.instances.borrow()[index].color;
+
+        linear_to_srgb(&Color {
+            r: r as f64,
+            g: g as f64,
+            b: b as f64,
+        })
+    }
+
+    /// Sets the color of the instance at the specified index, which multiplies
+    /// the color of the material. Only the modified instances are uploaded to
+    /// the GPU again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index is out of bounds.
+    pub fn set_color_at(&self, index: usize, color: &Color) {
+        let [r, g, b] = to_linear(color);
+
+        self.instances.borrow_mut()[index].color = [r, g, b, 1.0];
+        self.mark_updated(index);
+    }
+
+    /// Adds the instance at the specified index to the updated range.
+    fn mark_updated(&self, index: usize) {
+        let mut updated_range = self.updated_range.borrow_mut();
+
+        *updated_range = Some(match updated_range.take() {
+            Some(range) => range.start.min(index)..range.end.max(index + 1),
+            None => index..index + 1,
+        });
+    }
+
+    /// Returns the per-instance data.
+    pub(crate) fn instances(&self) -> std::cell::Ref<'_, Vec<Instance>> {
+        self.instances.borrow()
+    }
+
+    /// Returns the range of instances that have been modified since the last
+    /// call, and resets it.
+    pub(crate) fn take_updated_range(&self) -> Option<Range<usize>> {
+        self.updated_range.borrow_mut().take()
+    }
+
+    /// Returns the smallest axis-aligned box that contains the geometries of
+    /// all instances, relative to the instanced mesh. The box is empty if
+    /// there are no instances.
+    ///
+    /// The result is cached until the transformation of an instance is set or
+    /// the geometry is [updated](BufferGeometry::needs_update).
+    pub fn compute_bounding_box(&self) -> Box3 {
+        let version = self.geometry.version();
+
+        if let Some((cached_version, bounding_box)) = *self.bounding_box.borrow() {
+            if cached_version == version {
+                return bounding_box;
+            }
+        }
+
+        let geometry_box = self.geometry.compute_bounding_box();
+        let mut bounding_box = Box3::empty();
+
+        for instance in self.instances.borrow().iter() {
+            bounding_box.union(&geometry_box.apply_matrix4(&instance.matrix));
+        }
+
+        *self.bounding_box.borrow_mut() = Some((version, bounding_box));
+
+        bounding_box
+    }
+
+    /// Returns a sphere that contains the geometries of all instances,
+    /// relative to the instanced mesh. The sphere is centered at the center of
+    /// the [bounding box](Self::compute_bounding_box), and is empty if there
+    /// are no instances.
+    ///
+    /// The result is cached like the bounding box.
+    pub fn compute_bounding_sphere(&self) -> Sphere {
+        let version = self.geometry.version();
+
+        if let Some((cached_version, bounding_sphere)) = *self.bounding_sphere.borrow() {
+            if cached_version == version {
+                return bounding_sphere;
+            }
+        }
+
+        let bounding_box = self.compute_bounding_box();
+
+        let bounding_sphere = if bounding_box.is_empty() {
+            Sphere::empty()
+        } else {
+            let geometry_sphere = self.geometry.compute_bounding_sphere();
+            let center = to_array(&bounding_box.center());
+            let radius = self
+                .instances
+                .borrow()
+                .iter()
+                .map(|instance| {
+                    let instance_center =
+                        transform_point(&instance.matrix, to_array(&geometry_sphere.center));
+
+                    length(sub(instance_center, center))
+                        + geometry_sphere.radius * max_scale(&instance.matrix)
+                })
+                .fold(0.0, f32::max);
+
+            Sphere::new(bounding_box.center(), radius)
+        };
+
+        *self.bounding_sphere.borrow_mut() = Some((version, bounding_sphere));
+
+        bounding_sphere
+    }
+}
@@ -0,0 +1,159 @@
+use poli_math::Color;
+
+/// Contains different kinds of lights.
+///
+/// Lights take their position and direction from the world matrix of the
+/// encapsulating [`Object3d`](super::Object3d). Directional lights and spot
+/// lights shine along the object's local -Z axis, like a camera looks.
+pub enum LightKind {
+    /// A light that illuminates all objects equally from all directions.
+    AmbientLight,
+    /// A light whose rays are parallel and infinitely far away, similar to
+    /// sunlight. Only its direction matters, not its position.
+    DirectionalLight,
+    /// A light emitted from a single point in all directions, similar to a
+    /// light bulb.
+    PointLight {
+        /// The distance at which the light's intensity reaches zero. `0.0`
+        /// means the light has no range limit.
+        range: f32,
+        /// How fast the light dims with distance. `2.0` is physically correct.
+        decay: f32,
+    },
+    /// A light emitted from a single point in one direction, along a cone that
+    /// gets wider further from the light, similar to a flashlight.
+    SpotLight {
+        /// The distance at which the light's intensity reaches zero. `0.0`
+        /// means the light has no range limit.
+        range: f32,
+        /// The angle in radians between the direction of the light and the
+        /// edge of the cone. Should be less than `PI / 2.0`.
+        angle: f32,
+        /// The fraction of the cone, from `0.0` to `1.0`, along which the
+        /// light fades out towards the edge.
+        penumbra: f32,
+        /// How fast the light dims with distance. `2.0` is physically correct.
+        decay: f32,
+    },
+}
+
+/// A light source that illuminates 3D objects in a scene.
+pub struct Light {
+    /// The color of the light.
+    pub color: Color,
+    /// The strength of the light, multiplied with the color.
+    pub intensity: f32,
+    /// The kind of light e.g. ambient, directional, point, spot.
+    pub kind: LightKind,
+    /// Whether the light casts shadows. All lights except ambient lights cast
+    /// shadows, from the objects that [cast shadows](super::Object3d::cast_shadow)
+    /// onto the objects that [receive them](super::Object3d::receive_shadow).
+    /// Default is `false`.
+    pub cast_shadow: bool,
+    /// How the light's shadow is rendered.
+    pub shadow: LightShadow,
+}
+
+/// Parameters of the shadow of a [`Light`].
+///
+/// Renderers draw the depth of the shadow casters as seen from the light into
+/// a shadow map, and darken the fragments that are further from the light than
+/// the depth stored in the map. A directional light sees the scene through an
+/// orthographic box centered on its position, and a spot light through a
+/// perspective frustum that encloses its cone.
+///
+/// # Point light shadows
+///
+/// A point light shines in all directions, so its shadow map is a cube map,
+/// which is drawn through the six faces of a cube around the light and
+/// sampled in the direction from the light to the fragment. Instead of the
+/// depth of a perspective projection, which is not linear, the cube map
+/// stores the distance from the light divided by the range of the light, or
+/// by [`far`](Self::far) if it has no range. The [bias](Self::bias) is
+/// therefore the same fraction of the range at all distances. The cube maps
+/// of all point lights have the size of the largest one. GPUs without cube map
+/// arrays, e.g. some WebGL and OpenGL ES devices, only draw the shadow of one
+/// point light.
+///
+/// # Cascaded shadows
+///
+/// A single shadow map cannot cover a large scene with enough detail. With
+/// more than one [cascade](Self::cascades), the shadow of a directional light
+/// instead follows the camera: the camera frustum is split along the view
+/// direction into cascades up to [`cascade_distance`](Self::cascade_distance),
+/// and each cascade gets its own shadow map, which is fitted around it. Nearby
+/// cascades are shorter and therefore get more detailed shadows.
+pub struct LightShadow {
+    /// The width and height of the shadow map in texels. Larger maps give
+    /// sharper shadows, but use more memory. Default is `1024`.
+    pub map_size: u32,
+    /// The amount subtracted from the depth of fragments, in the `0.0..=1.0`
+    /// depth range of the shadow map, before they are compared with the map.
+    /// For point lights, it is a fraction of the range.
+    /// Increase it if lit surfaces show stripes of shadow ("shadow acne"), and
+    /// decrease it if shadows detach from their casters. Default is `0.0001`.
+    pub bias: f32,
+    /// Half of the width and height of the area covered by the shadow of a
+    /// directional light, around its position. Not used by cascaded shadows.
+    /// Default is `5.0`.
+    pub extent: f32,
+    /// The distance from the light at which shadow casters start to be drawn.
+    /// Not used by cascaded shadows. Default is `0.5`.
+    pub near: f32,
+    /// The distance from the light up to which shadow casters are drawn. Spot
+    /// and point lights with a range use the range instead, and cascaded
+    /// shadows the far side of each cascade. Default is `500.0`.
+    pub far: f32,
+    /// The number of cascades of the shadow of a directional light, each of
+    /// which uses a shadow map of [`map_size`](Self::map_size). Other lights
+    /// have a single shadow map. Default is `1`, i.e. not cascaded.
+    pub cascades: u32,
+    /// The distance from the camera up to which cascaded shadows are drawn,
+    /// which is clamped to the camera's far plane. Shadows fade out towards
+    /// this distance. Default is `100.0`.
+    pub cascade_distance: f32,
+    /// How the camera frustum is split into cascades, from `0.0` for cascades
+    /// of equal depth to `1.0` for cascades whose depth grows geometrically
+    /// with their distance from the camera. Default is `0.5`.
+    pub cascade_split_lambda: f32,
+    /// The fraction of the depth of each cascade, at its far side, over which
+    /// it is blended with the next cascade to hide the seam between their
+    /// shadow maps. Default is `0.1`.
+    pub cascade_blend: f32,
+    /// The distance in front of each cascade, towards the light, up to which
+    /// shadow casters outside the cascade are drawn, e.g. tall objects whose
+    /// shadows fall into it. Cascades do not depend on the position of the
+    /// light. Default is `100.0`.
+    pub cascade_caster_margin: f32,
+}
+
+impl Default for LightShadow {
+    fn default() -> Self {
+        Self {
+            map_size: 1024,
+            bias: 0.0001,
+            extent: 5.0,
+            near: 0.5,
+            far: 500.0,
+            cascades: 1,
+            cascade_distance: 100.0,
+            cascade_split_lambda: 0.5,
+            cascade_blend: 0.1,
+            cascade_caster_margin: 100.0,
+        }
+    }
+}
+
+impl Light {
+    /// Creates a new light of the specified kind, color, and intensity, which
+    /// does not cast shadows.
+    pub fn new(kind: LightKind, color: Color, intensity: f32) -> Self {
+        Self {
+            color,
+            intensity,
+            kind,
+            cast_shadow: false,
+            shadow: LightShadow::default(),
+        }
+    }
+}
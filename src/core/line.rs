@@ -0,0 +1,43 @@
+use std::rc::Rc;
+
+use crate::{geometries::BufferGeometry, materials::Material};
+
+/// Continuous line 3D object, which connects each vertex of its geometry to the
+/// next one.
+///
+/// If the geometry is indexed, the vertices are connected in the order of the
+/// indices. The normals of the geometry are not used, but the normal and UV
+/// attributes must have as many elements as there are vertices.
+pub struct Line {
+    /// The geometry whose vertices are the points of the line.
+    pub geometry: Rc<BufferGeometry>,
+    /// The material e.g. a [`LineBasicMaterial`](crate::LineBasicMaterial).
+    pub material: Rc<dyn Material>,
+}
+
+impl Line {
+    /// Creates a new line with the specified geometry and material.
+    pub fn new(geometry: Rc<BufferGeometry>, material: Rc<dyn Material>) -> Self {
+        Self { geometry, material }
+    }
+}
+
+/// Line segments 3D object, where every consecutive pair of vertices of its
+/// geometry defines a separate segment.
+///
+/// If the geometry is indexed, every consecutive pair of indices defines a
+/// segment. The normals of the geometry are not used, but the normal and UV
+/// attributes must have as many elements as there are vertices.
+pub struct LineSegments {
+    /// The geometry whose vertices are the end points of the segments.
+    pub geometry: Rc<BufferGeometry>,
+    /// The material e.g. a [`LineBasicMaterial`](crate::LineBasicMaterial).
+    pub material: Rc<dyn Material>,
+}
+
+impl LineSegments {
+    /// Creates new line segments with the specified geometry and material.
+    pub fn new(geometry: Rc<BufferGeometry>, material: Rc<dyn Material>) -> Self {
+        Self { geometry, material }
+    }
+}
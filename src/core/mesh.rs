@@ -1,6 +1,6 @@
-use std::{cell::RefCell, rc::Rc};
+use std::rc::Rc;
 
-use crate::geometries::BufferGeometry;
+use crate::{geometries::BufferGeometry, materials::Material};
 
 /// Triangular polygon mesh 3D object.
 ///
@@ -9,17 +9,14 @@ use crate::geometries::BufferGeometry;
 pub struct Mesh {
     /// The triangular polygon geometry.
     pub geometry: Rc<BufferGeometry>,
-    /// The associated GPU bind group, which contains information such as world
-    /// matrix, normal matrix, etc.
-    pub bind_group: RefCell<Option<wgpu::BindGroup>>,
+    /// The material, which determines how the surface of the geometry is
+    /// shaded. Can be shared between several meshes.
+    pub material: Rc<dyn Material>,
 }
 
 impl Mesh {
-    /// Creates a new 3D mesh with the specified geometry.
-    pub fn new(geometry: Rc<BufferGeometry>) -> Self {
-        Self {
-            geometry,
-            bind_group: RefCell::new(None),
-        }
+    /// Creates a new 3D mesh with the specified geometry and material.
+    pub fn new(geometry: Rc<BufferGeometry>, material: Rc<dyn Material>) -> Self {
+        Self { geometry, material }
     }
 }
@@ -1,9 +1,19 @@
 //! The core 3D graphics module, which consists of 3D objects and primitives.
 
 mod camera;
+mod instanced_mesh;
+mod light;
+mod line;
 mod mesh;
 mod object3d;
+mod points;
+mod raycaster;
 
 pub use camera::*;
+pub use instanced_mesh::*;
+pub use light::*;
+pub use line::*;
 pub use mesh::*;
 pub use object3d::*;
+pub use points::*;
+pub use raycaster::*;
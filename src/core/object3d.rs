@@ -7,13 +7,22 @@ use std::{
 
 use poli_math::{Matrix4, Quaternion, Vector3};
 
-use super::{Camera, Mesh};
+use super::{Camera, InstancedMesh, Light, Line, LineSegments, Mesh, Points};
+use crate::math::{
+    vector::{cross, normalize},
+    Box3,
+};
 
 /// Contains different kinds of 3D objects.
 pub enum Object3dKind {
     Mesh(Box<Mesh>),
+    InstancedMesh(Box<InstancedMesh>),
+    Line(Box<Line>),
+    LineSegments(Box<LineSegments>),
+    Points(Box<Points>),
     Group,
     Camera(Rc<RefCell<Camera>>),
+    Light(Rc<RefCell<Light>>),
 }
 
 /// 3D object, which is anything that can have a transformation in 3D space
@@ -35,6 +44,13 @@ pub struct Object3d {
     pub kind: Object3dKind,
     /// Whether or not this 3D object is visible.
     pub visible: RefCell<bool>,
+    /// Whether this 3D object casts shadows from the lights that
+    /// [cast shadows](super::Light::cast_shadow), if it is a mesh or an
+    /// instanced mesh. Default is `false`.
+    pub cast_shadow: RefCell<bool>,
+    /// Whether shadows are cast onto this 3D object, if it is a mesh or an
+    /// instanced mesh. Default is `false`.
+    pub receive_shadow: RefCell<bool>,
 }
 
 impl From<Mesh> for Object3d {
@@ -43,12 +59,42 @@ impl From<Mesh> for Object3d {
     }
 }
 
+impl From<InstancedMesh> for Object3d {
+    fn from(instanced_mesh: InstancedMesh) -> Self {
+        Self::new(Object3dKind::InstancedMesh(Box::new(instanced_mesh)))
+    }
+}
+
+impl From<Line> for Object3d {
+    fn from(line: Line) -> Self {
+        Self::new(Object3dKind::Line(Box::new(line)))
+    }
+}
+
+impl From<LineSegments> for Object3d {
+    fn from(line_segments: LineSegments) -> Self {
+        Self::new(Object3dKind::LineSegments(Box::new(line_segments)))
+    }
+}
+
+impl From<Points> for Object3d {
+    fn from(points: Points) -> Self {
+        Self::new(Object3dKind::Points(Box::new(points)))
+    }
+}
+
 impl From<Camera> for Object3d {
     fn from(camera: Camera) -> Self {
         Self::new(Object3dKind::Camera(Rc::new(RefCell::new(camera))))
     }
 }
 
+impl From<Light> for Object3d {
+    fn from(light: Light) -> Self {
+        Self::new(Object3dKind::Light(Rc::new(RefCell::new(light))))
+    }
+}
+
 /// [Breadth-first traversal][bfs] iterator for the scene graph.
 ///
 /// [bfs]: https://en.wikipedia.org/wiki/Breadth-first_search
@@ -106,6 +152,8 @@ impl Object3d {
             world_matrix: RefCell::new(Matrix4::identity()),
             kind,
             visible: RefCell::new(true),
+            cast_shadow: RefCell::new(false),
+            receive_shadow: RefCell::new(false),
         }
     }
 
@@ -166,15 +214,98 @@ impl Object3d {
         Self::update_world_matrix(object, true);
     }
 
+    /// Rotates the given 3D object so that its local -Z axis points towards
+    /// `target` and its local +Y axis points as closely as possible in the
+    /// direction of `up`, then updates its descendants' world matrices. Both
+    /// vectors are in world space. The position and world scale of the object
+    /// are kept.
+    ///
+    /// Cameras and lights look along their local -Z axis, so this aims them at
+    /// `target`. Does nothing if `target` is at the position of the object.
+    pub fn look_at(object: &Rc<Self>, target: &Vector3, up: &Vector3) {
+        let position = object.world_matrix.borrow().translation();
+        let [px, py, pz] = bytemuck::cast::<Vector3, [f32; 3]>(position);
+        let [tx, ty, tz] = bytemuck::cast::<Vector3, [f32; 3]>(*target);
+
+        let Some(z) = normalize([px - tx, py - ty, pz - tz]) else {
+            return;
+        };
+
+        let up = bytemuck::cast::<Vector3, [f32; 3]>(*up);
+
+        // If the up vector is parallel to the Z axis, any other axis works.
+        let x = normalize(cross(up, z))
+            .or_else(|| normalize(cross([0.0, 0.0, 1.0], z)))
+            .or_else(|| normalize(cross([1.0, 0.0, 0.0], z)))
+            .unwrap();
+        let y = cross(z, x);
+
+        // The scale is kept in world space, since the parent's scale is
+        // removed again when converting back to the local matrix.
+        let world_matrix = *object.world_matrix.borrow();
+        let e = &world_matrix.elements;
+        let scale =
+            [0, 4, 8].map(|i| (e[i] * e[i] + e[i + 1] * e[i + 1] + e[i + 2] * e[i + 2]).sqrt());
+
+        #[rustfmt::skip]
+        let world_matrix = Matrix4 {
+            elements: [
+                x[0] * scale[0], x[1] * scale[0], x[2] * scale[0], 0.0,
+                y[0] * scale[1], y[1] * scale[1], y[2] * scale[1], 0.0,
+                z[0] * scale[2], z[1] * scale[2], z[2] * scale[2], 0.0,
+                px, py, pz, 1.0,
+            ],
+        };
+
+        // The local matrix is the world matrix relative to the parent.
+        *object.local_matrix.borrow_mut() = match object.parent.borrow().upgrade() {
+            Some(parent) => parent.world_matrix.borrow().inverse() * world_matrix,
+            None => world_matrix,
+        };
+
+        Self::update_world_matrix(object, true);
+    }
+
+    /// Returns the smallest axis-aligned box in world space that contains the
+    /// geometries of the given 3D object and its descendants, regardless of
+    /// their visibility. The box is empty if there are no geometries.
+    ///
+    /// The world matrices must be up to date, which is the case unless the
+    /// local matrices have been modified directly.
+    pub fn bounding_box(object: &Rc<Self>) -> Box3 {
+        let mut bounding_box = Box3::empty();
+
+        for object in Self::bfs(object) {
+            let local_bounding_box = match &object.kind {
+                Object3dKind::Mesh(mesh) => mesh.geometry.compute_bounding_box(),
+                Object3dKind::InstancedMesh(instanced_mesh) => {
+                    instanced_mesh.compute_bounding_box()
+                }
+                Object3dKind::Line(line) => line.geometry.compute_bounding_box(),
+                Object3dKind::LineSegments(line_segments) => {
+                    line_segments.geometry.compute_bounding_box()
+                }
+                Object3dKind::Points(points) => points.geometry.compute_bounding_box(),
+                _ => continue,
+            };
+
+            bounding_box.union(&local_bounding_box.apply_matrix4(&object.world_matrix.borrow()));
+        }
+
+        bounding_box
+    }
+
     /// Updates the world matrix of the given object and (if specified) the
     /// world matrices of its descendants.
     pub fn update_world_matrix(object: &Rc<Self>, update_descendants: bool) {
         if !update_descendants {
-            if let Some(parent) = object.parent.borrow().upgrade() {
-                *object.world_matrix.borrow_mut() = parent
+            // The world matrix of a root object is its local matrix.
+            *object.world_matrix.borrow_mut() = match object.parent.borrow().upgrade() {
+                Some(parent) => parent
                     .world_matrix
                     .borrow()
-                    .mul(*object.local_matrix.borrow())
+                    .mul(*object.local_matrix.borrow()),
+                None => *object.local_matrix.borrow(),
             };
 
             if let Object3dKind::Camera(camera) = &object.kind {
@@ -189,3 +320,58 @@ impl Object3d {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::vector::{length, scale, sub};
+
+    /// Returns the X, Y, and Z axes of the specified matrix.
+    fn axes(m: &Matrix4) -> [[f32; 3]; 3] {
+        let e = &m.elements;
+
+        [0, 4, 8].map(|i| [e[i], e[i + 1], e[i + 2]])
+    }
+
+    #[track_caller]
+    fn assert_near(actual: [f32; 3], expected: [f32; 3]) {
+        assert!(
+            length(sub(actual, expected)) < 1e-4,
+            "{actual:?} is not {expected:?}"
+        );
+    }
+
+    #[test]
+    fn look_at_keeps_world_scale_under_scaled_parent() {
+        let parent = Rc::new(Object3d::new(Object3dKind::Group));
+        let child = Rc::new(Object3d::new(Object3dKind::Group));
+
+        Object3d::add(&parent, &child);
+        Object3d::scale(&parent, &(2.0, 3.0, 4.0).into());
+        Object3d::translate(&child, &(1.0, 0.0, 0.0).into());
+        Object3d::scale(&child, &(0.5, 0.5, 0.5).into());
+
+        let world_scale = axes(&child.world_matrix.borrow()).map(length);
+        let position: [f32; 3] = bytemuck::cast(child.world_matrix.borrow().translation());
+        let target = [5.0, 5.0, 0.0];
+
+        Object3d::look_at(&child, &bytemuck::cast(target), &(0.0, 1.0, 0.0).into());
+
+        // Looking at the target twice changes nothing.
+        Object3d::look_at(&child, &bytemuck::cast(target), &(0.0, 1.0, 0.0).into());
+
+        let world_matrix = *child.world_matrix.borrow();
+        let [x, y, z] = axes(&world_matrix);
+
+        assert_near([x, y, z].map(length), world_scale);
+        assert_near(bytemuck::cast(world_matrix.translation()), position);
+
+        // The -Z axis points at the target.
+        let forward = sub(target, position);
+
+        assert_near(
+            scale(z, -1.0 / length(z)),
+            scale(forward, 1.0 / length(forward)),
+        );
+    }
+}
@@ -0,0 +1,24 @@
+use std::rc::Rc;
+
+use crate::{geometries::BufferGeometry, materials::Material};
+
+/// Point cloud 3D object, which draws a point at each vertex of its geometry.
+///
+/// Each point is a square that faces the camera and whose size in pixels is
+/// given by the [material](Material::point_size), e.g. a
+/// [`PointsMaterial`](crate::PointsMaterial). The indices of the geometry are
+/// not used, but the normal and UV attributes must have as many elements as
+/// there are vertices.
+pub struct Points {
+    /// The geometry whose vertices are the positions of the points.
+    pub geometry: Rc<BufferGeometry>,
+    /// The material, which also determines the size of the points.
+    pub material: Rc<dyn Material>,
+}
+
+impl Points {
+    /// Creates a new point cloud with the specified geometry and material.
+    pub fn new(geometry: Rc<BufferGeometry>, material: Rc<dyn Material>) -> Self {
+        Self { geometry, material }
+    }
+}
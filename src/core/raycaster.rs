@@ -0,0 +1,243 @@
+use std::rc::Rc;
+
+use poli_math::{Matrix4, Vector3};
+
+use super::{Camera, CameraKind, Object3d, Object3dKind};
+use crate::{
+    geometries::BufferGeometry,
+    math::{
+        vector::{from_array, length, sub, to_array, transform_point},
+        Ray,
+    },
+};
+
+/// Intersection of a [`Raycaster`]'s ray with the triangle of a mesh.
+pub struct Intersection {
+    /// The distance from the origin of the ray to the intersection, in world
+    /// space.
+    pub distance: f32,
+    /// The intersection point in world space.
+    pub point: Vector3,
+    /// The index of the intersected triangle. Triangle `i` is formed by the
+    /// vertices at indices `3 * i`, `3 * i + 1` and `3 * i + 2` of the
+    /// geometry's [`indices`](BufferGeometry::indices), or of its
+    /// [`position`](BufferGeometry::position) attribute if it is not indexed.
+    pub face_index: usize,
+    /// The barycentric coordinates of the intersection point with respect to
+    /// the three vertices of the triangle.
+    pub barycentric: Vector3,
+    /// The UV coordinates at the intersection point, interpolated from the
+    /// geometry's [`uv`](BufferGeometry::uv) attribute with the
+    /// [barycentric coordinates](Self::barycentric). `None` if the geometry
+    /// has no UV coordinates.
+    pub uv: Option<[f32; 2]>,
+    /// The index of the intersected instance if the mesh is an
+    /// [instanced mesh](super::InstancedMesh), `None` otherwise.
+    pub instance_index: Option<usize>,
+    /// The intersected mesh.
+    pub object: Rc<Object3d>,
+}
+
+/// Casts a ray into a 3D scene to find the meshes it intersects, e.g. to pick
+/// the mesh under the mouse cursor.
+///
+/// Meshes are intersected on the CPU using their world matrices, which must be
+/// up to date. Only the front faces of triangles are intersected, since
+/// renderers do not draw back faces.
+pub struct Raycaster {
+    /// The ray in world space.
+    pub ray: Ray,
+    /// Intersections closer to the origin of the ray than this distance are
+    /// ignored. Default is `0.0`.
+    pub near: f32,
+    /// Intersections further from the origin of the ray than this distance
+    /// are ignored. Default is [`INFINITY`](std::f32::INFINITY).
+    pub far: f32,
+}
+
+impl Raycaster {
+    /// Creates a raycaster with the specified ray in world space.
+    pub fn new(ray: Ray) -> Self {
+        Self {
+            ray,
+            near: 0.0,
+            far: f32::INFINITY,
+        }
+    }
+
+    /// Creates a raycaster whose ray goes from the camera through the
+    /// specified point on the screen.
+    ///
+    /// * `ndc_x`, `ndc_y`: The point in normalized device coordinates, from
+    ///   `-1.0` at the left and bottom to `1.0` at the right and top of the
+    ///   screen. For a mouse position in pixels, use
+    ///   `2.0 * x / width - 1.0` and `1.0 - 2.0 * y / height`.
+    ///
+    /// For a perspective camera, the ray starts at the camera's position. For
+    /// other cameras, it starts on the near plane and is found by unprojecting
+    /// the point with the inverse projection matrix, which makes all rays of
+    /// an orthographic camera parallel.
+    pub fn from_camera(ndc_x: f32, ndc_y: f32, camera: &Camera) -> Self {
+        let camera_matrix = camera.view_matrix.inverse();
+        let unproject = |ndc_z: f32| {
+            let view_point =
+                transform_point(&camera.projection_matrix_inverse, [ndc_x, ndc_y, ndc_z]);
+
+            transform_point(&camera_matrix, view_point)
+        };
+
+        // The point halfway through the depth range is finite even if the far
+        // plane is at infinity.
+        let target = unproject(0.5);
+
+        let origin = match *camera.kind.borrow() {
+            CameraKind::PerspectiveCamera { .. } => to_array(&camera.position),
+            _ => unproject(0.0),
+        };
+
+        Self::new(Ray::new(
+            from_array(origin),
+            from_array(sub(target, origin)),
+        ))
+    }
+
+    /// Intersects the ray with the meshes in the subtree of the specified 3D
+    /// object, including the object itself. Invisible objects and their
+    /// descendants are skipped.
+    ///
+    /// # Returns
+    ///
+    /// The intersections sorted by distance, closest first.
+    pub fn intersect_object(&self, object: &Rc<Object3d>) -> Vec<Intersection> {
+        self.intersect_objects(std::slice::from_ref(object))
+    }
+
+    /// Intersects the ray with the meshes in the subtrees of the specified 3D
+    /// objects. See [`intersect_object`](Self::intersect_object).
+    pub fn intersect_objects(&self, objects: &[Rc<Object3d>]) -> Vec<Intersection> {
+        let mut intersections = Vec::new();
+        let mut stack = objects.to_vec();
+
+        while let Some(object) = stack.pop() {
+            if !*object.visible.borrow() {
+                continue;
+            }
+
+            let world_matrix = *object.world_matrix.borrow();
+
+            match &object.kind {
+                Object3dKind::Mesh(mesh) => self.intersect_mesh(
+                    &object,
+                    &mesh.geometry,
+                    &world_matrix,
+                    None,
+                    &mut intersections,
+                ),
+                Object3dKind::InstancedMesh(instanced_mesh) => {
+                    if !self.ray.intersects_sphere(
+                        &instanced_mesh
+                            .compute_bounding_sphere()
+                            .apply_matrix4(&world_matrix),
+                    ) {
+                        continue;
+                    }
+
+                    for instance_index in 0..instanced_mesh.count() {
+                        self.intersect_mesh(
+                            &object,
+                            &instanced_mesh.geometry,
+                            &(world_matrix * instanced_mesh.matrix_at(instance_index)),
+                            Some(instance_index),
+                            &mut intersections,
+                        );
+                    }
+                }
+                _ => {}
+            }
+
+            stack.extend(object.children.borrow().iter().map(Rc::clone));
+        }
+
+        intersections.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+
+        intersections
+    }
+
+    /// Appends the intersections of the ray with the triangles of the
+    /// specified mesh, or of one of its instances, to `intersections`.
+    ///
+    /// * `world_matrix`: The matrix that transforms the geometry into world
+    ///   space, which includes the transformation of the instance.
+    fn intersect_mesh(
+        &self,
+        object: &Rc<Object3d>,
+        geometry: &BufferGeometry,
+        world_matrix: &Matrix4,
+        instance_index: Option<usize>,
+        intersections: &mut Vec<Intersection>,
+    ) {
+        if !self.ray.intersects_sphere(
+            &geometry
+                .compute_bounding_sphere()
+                .apply_matrix4(world_matrix),
+        ) {
+            return;
+        }
+
+        // Triangles are intersected in local space, which is cheaper than
+        // transforming every vertex into world space.
+        let local_ray = self.ray.apply_matrix4(&world_matrix.inverse());
+
+        let position = geometry.position.borrow();
+        let uv = geometry.uv.borrow();
+        let indices = geometry.indices.borrow();
+
+        let vertex_count = position.len() / 3;
+        let has_uv = uv.len() >= vertex_count * 2;
+
+        let face_count = match &*indices {
+            Some(indices) => indices.len() / 3,
+            None => vertex_count / 3,
+        };
+
+        let vertex = |i: usize| [position[3 * i], position[3 * i + 1], position[3 * i + 2]];
+
+        for face_index in 0..face_count {
+            let [a, b, c] = match &*indices {
+                Some(indices) => [0, 1, 2].map(|i| indices[3 * face_index + i] as usize),
+                None => [0, 1, 2].map(|i| 3 * face_index + i),
+            };
+
+            let Some((local_distance, barycentric)) =
+                local_ray.intersect_triangle(vertex(a), vertex(b), vertex(c), true)
+            else {
+                continue;
+            };
+
+            let point = transform_point(world_matrix, to_array(&local_ray.at(local_distance)));
+            let distance = length(sub(point, to_array(&self.ray.origin)));
+
+            if distance < self.near || distance > self.far {
+                continue;
+            }
+
+            let uv = has_uv.then(|| {
+                [0, 1].map(|i| {
+                    barycentric[0] * uv[2 * a + i]
+                        + barycentric[1] * uv[2 * b + i]
+                        + barycentric[2] * uv[2 * c + i]
+                })
+            });
+
+            intersections.push(Intersection {
+                distance,
+                point: from_array(point),
+                face_index,
+                barycentric: from_array(barycentric),
+                uv,
+                instance_index,
+                object: Rc::clone(object),
+            });
+        }
+    }
+}
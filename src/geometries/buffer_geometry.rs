@@ -1,33 +1,127 @@
+use std::cell::RefCell;
+
 use super::Geometry;
+use crate::math::{
+    vector::{length, sub, to_array},
+    Box3, Sphere,
+};
 
 /// Shape of [triangular polygon 3D mesh](crate::Mesh) with compiled
 /// buffer data.
+///
+/// Renderers upload the buffers to the GPU once and reuse them in later frames,
+/// even if the geometry is shared by several meshes. After modifying the
+/// buffers, call [`needs_update`](Self::needs_update) so that the renderers
+/// upload them again.
 pub struct BufferGeometry {
     /// Position attribute buffer. Every consecutive triplet specifies the
     /// position of a vertex relative to the geometry's origin.
-    pub position: Vec<f32>,
+    pub position: RefCell<Vec<f32>>,
     /// Normal attribute buffer. Every consecutive triplet specifies the normal
     /// vector of the corresponding vertex in [`position`](Self::position).
-    pub normal: Vec<f32>,
+    pub normal: RefCell<Vec<f32>>,
     /// UV attribute buffer. Every consecutive pair of numbers specifies the
     /// UV coordinates of the corresponding vertex in [`position`](Self::position).
-    pub uv: Vec<f32>,
+    pub uv: RefCell<Vec<f32>>,
     /// Optional list of indices. Every consecutive triplet defines a triangle
     /// formed by the vertices at the specified indices. If the list is `None`,
     /// every consecutive triplet of vertices defines a triangle.
-    pub indices: Option<Vec<u32>>,
+    pub indices: RefCell<Option<Vec<u32>>>,
+    /// Incremented every time the buffers are marked as modified.
+    version: RefCell<u64>,
+    /// The cached result of [`compute_bounding_box`](Self::compute_bounding_box).
+    bounding_box: RefCell<Option<Box3>>,
+    /// The cached result of
+    /// [`compute_bounding_sphere`](Self::compute_bounding_sphere).
+    bounding_sphere: RefCell<Option<Sphere>>,
 }
 
 impl BufferGeometry {
+    /// Creates a 3D geometry from the given buffers. See the fields of
+    /// [`BufferGeometry`] for their layout.
+    pub fn new(
+        position: Vec<f32>,
+        normal: Vec<f32>,
+        uv: Vec<f32>,
+        indices: Option<Vec<u32>>,
+    ) -> Self {
+        Self {
+            position: RefCell::new(position),
+            normal: RefCell::new(normal),
+            uv: RefCell::new(uv),
+            indices: RefCell::new(indices),
+            version: RefCell::new(0),
+            bounding_box: RefCell::new(None),
+            bounding_sphere: RefCell::new(None),
+        }
+    }
+
     /// Creates a 3D geometry from the given geometry builder.
     pub fn from_geometry<G: Geometry>(geometry: &G) -> Self {
         let (position, normal, uv) = geometry.vertices();
 
-        Self {
-            position,
-            normal,
-            uv,
-            indices: geometry.indices(),
+        Self::new(position, normal, uv, geometry.indices())
+    }
+
+    /// Marks the buffers as modified, so that renderers upload them to the GPU
+    /// again before the next time the geometry is rendered. Also discards the
+    /// cached bounding volumes.
+    pub fn needs_update(&self) {
+        *self.version.borrow_mut() += 1;
+        *self.bounding_box.borrow_mut() = None;
+        *self.bounding_sphere.borrow_mut() = None;
+    }
+
+    /// Returns the number of times the buffers have been marked as modified
+    /// with [`needs_update`](Self::needs_update). Renderers compare it with
+    /// the version they last uploaded.
+    pub fn version(&self) -> u64 {
+        *self.version.borrow()
+    }
+
+    /// Returns the smallest axis-aligned box that contains all vertices of the
+    /// geometry, in the geometry's local space. The box is empty if the
+    /// geometry has no vertices.
+    ///
+    /// The result is cached until [`needs_update`](Self::needs_update) is
+    /// called.
+    pub fn compute_bounding_box(&self) -> Box3 {
+        *self
+            .bounding_box
+            .borrow_mut()
+            .get_or_insert_with(|| Box3::from_points(&self.position.borrow()))
+    }
+
+    /// Returns a sphere that contains all vertices of the geometry, in the
+    /// geometry's local space. The sphere is centered at the center of the
+    /// [bounding box](Self::compute_bounding_box), and is empty if the
+    /// geometry has no vertices.
+    ///
+    /// The result is cached until [`needs_update`](Self::needs_update) is
+    /// called.
+    pub fn compute_bounding_sphere(&self) -> Sphere {
+        if let Some(bounding_sphere) = *self.bounding_sphere.borrow() {
+            return bounding_sphere;
         }
+
+        let bounding_box = self.compute_bounding_box();
+
+        let bounding_sphere = if bounding_box.is_empty() {
+            Sphere::empty()
+        } else {
+            let center = to_array(&bounding_box.center());
+            let radius = self
+                .position
+                .borrow()
+                .chunks_exact(3)
+                .map(|point| length(sub([point[0], point[1], point[2]], center)))
+                .fold(0.0, f32::max);
+
+            Sphere::new(bounding_box.center(), radius)
+        };
+
+        *self.bounding_sphere.borrow_mut() = Some(bounding_sphere);
+
+        bounding_sphere
     }
 }
@@ -1,14 +1,25 @@
 mod core;
 mod geometries;
 mod loaders;
+mod materials;
+mod math;
+mod postprocessing;
 mod renderers;
+mod textures;
 mod wgpual;
 
 pub use core::*;
 pub use geometries::*;
 pub use loaders::*;
+pub use materials::*;
+pub use math::*;
+pub use postprocessing::*;
 pub use renderers::*;
+pub use textures::*;
 pub use wgpual::*;
 
 // wgpu re-exports
-pub use wgpu::PowerPreference;
+pub use wgpu::{AddressMode, FilterMode, PowerPreference};
+
+// image re-exports
+pub use image::ImageError;
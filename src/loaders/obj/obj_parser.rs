@@ -1,8 +1,9 @@
 use std::{cell::RefCell, collections::HashSet, ops::RangeInclusive, rc::Rc, str::Split};
 
 use crate::{
-    core::{Mesh, Object3d, Object3dKind::Group},
+    core::{LineSegments, Mesh, Object3d, Object3dKind::Group, Points},
     geometries::BufferGeometry,
+    materials::{LineBasicMaterial, Material, NormalMaterial, PointsMaterial},
 };
 
 /// Successful OBJ file parse result.
@@ -73,6 +74,11 @@ struct ObjObject {
     /// where this value is set to `false`.
     from_declaration: bool,
     geometry: ObjGeometry,
+    /// Positions of the end points of the line segments from the line
+    /// command (`l`), two vertices per segment.
+    lines: Vec<f32>,
+    /// Positions of the points from the point command (`p`).
+    points: Vec<f32>,
 }
 
 impl ObjObject {
@@ -81,6 +87,8 @@ impl ObjObject {
             name,
             from_declaration,
             geometry: ObjGeometry::new(),
+            lines: Vec::new(),
+            points: Vec::new(),
         }
     }
 
@@ -89,11 +97,7 @@ impl ObjObject {
 
 impl Default for ObjObject {
     fn default() -> Self {
-        Self {
-            name: None,
-            from_declaration: false,
-            geometry: ObjGeometry::new(),
-        }
+        Self::new(None, false)
     }
 }
 
@@ -125,8 +129,8 @@ pub struct ObjParseOptions {
 /// - [ ] Degree (`deg`)
 /// - [ ] Basis matrix (`bmat`)
 /// - [ ] Step size (`step`)
-/// - [ ] Point (`p`)
-/// - [ ] Line (`l`)
+/// - [x] Point (`p`)
+/// - [x] Line (`l`)
 /// - [x] Face (`f`)
 /// - [ ] Curve (`curv`)
 /// - [ ] 2D curve (`curv2`)
@@ -210,33 +214,24 @@ impl ObjParseState {
     }
 
     /// Converts the given vertex reference number to index in
-    /// [`vertices`](Self::vertices).
-    fn vertex_reference_to_index(&self, reference_number: i32) -> usize {
-        3 * if reference_number >= 0 {
-            reference_number as usize - 1
-        } else {
-            (reference_number + self.vertices.len() as i32 / 3) as usize
-        }
+    /// [`vertices`](Self::vertices), or `None` if it does not refer to a
+    /// vertex. See [`reference_to_index`].
+    fn vertex_reference_to_index(&self, reference_number: i32) -> Option<usize> {
+        reference_to_index(reference_number, self.vertices.len() / 3).map(|index| 3 * index)
     }
 
     /// Converts the given vertex normal reference number to index in
-    /// [`normals`](Self::normals).
-    fn normal_reference_to_index(&self, reference_number: i32) -> usize {
-        3 * if reference_number >= 0 {
-            reference_number as usize - 1
-        } else {
-            (reference_number + self.normals.len() as i32 / 3) as usize
-        }
+    /// [`normals`](Self::normals), or `None` if it does not refer to a vertex
+    /// normal. See [`reference_to_index`].
+    fn normal_reference_to_index(&self, reference_number: i32) -> Option<usize> {
+        reference_to_index(reference_number, self.normals.len() / 3).map(|index| 3 * index)
     }
 
     /// Converts the given texture vertex reference number to index in
-    /// [`uvs`](Self::uvs).
-    fn uv_reference_to_index(&self, reference_number: i32) -> usize {
-        2 * if reference_number >= 0 {
-            reference_number as usize - 1
-        } else {
-            (reference_number + self.uvs.len() as i32 / 2) as usize
-        }
+    /// [`uvs`](Self::uvs), or `None` if it does not refer to a texture vertex.
+    /// See [`reference_to_index`].
+    fn uv_reference_to_index(&self, reference_number: i32) -> Option<usize> {
+        reference_to_index(reference_number, self.uvs.len() / 2).map(|index| 2 * index)
     }
 
     /// Adds three vertices to the current object given their reference numbers.
@@ -247,34 +242,19 @@ impl ObjParseState {
     /// * `Err(i32)` if there is an invalid reference number, which is included
     ///   in the enum.
     fn add_vertex(&mut self, v1: i32, v2: i32, v3: i32) -> Result<(), i32> {
-        let v1_index = self.vertex_reference_to_index(v1);
-        let v2_index = self.vertex_reference_to_index(v2);
-        let v3_index = self.vertex_reference_to_index(v3);
-
-        let vertices = [
-            self.vertices.get(v1_index),
-            self.vertices.get(v1_index + 1),
-            self.vertices.get(v1_index + 2),
-            self.vertices.get(v2_index),
-            self.vertices.get(v2_index + 1),
-            self.vertices.get(v2_index + 2),
-            self.vertices.get(v3_index),
-            self.vertices.get(v3_index + 1),
-            self.vertices.get(v3_index + 2),
-        ];
+        let [v1, v2, v3] = [v1, v2, v3].map(|reference_number| {
+            self.vertex_reference_to_index(reference_number)
+                .ok_or(reference_number)
+        });
+        let indices = [v1?, v2?, v3?];
 
         let mut current_object = self.current_object.as_ref().borrow_mut();
 
-        for (v, vertex) in vertices.chunks(3).enumerate() {
-            if let [Some(x), Some(y), Some(z)] = vertex {
-                current_object.geometry.position.extend([*x, *y, *z]);
-            } else {
-                return match v {
-                    0 => Err(v1),
-                    1 => Err(v2),
-                    _ => Err(v3),
-                };
-            }
+        for index in indices {
+            current_object
+                .geometry
+                .position
+                .extend_from_slice(&self.vertices[index..index + 3]);
         }
 
         Ok(())
@@ -289,42 +269,20 @@ impl ObjParseState {
     /// * `Err(i32)` if there is an invalid reference number, which is included
     ///   in the enum.
     fn add_normal(&mut self, vn1: i32, vn2: i32, vn3: i32) -> Result<(), i32> {
-        let vn1_index = self.normal_reference_to_index(vn1);
-        let vn2_index = self.normal_reference_to_index(vn2);
-        let vn3_index = self.normal_reference_to_index(vn3);
-
-        let normals = [
-            self.normals.get(vn1_index),
-            self.normals.get(vn1_index + 1),
-            self.normals.get(vn1_index + 2),
-            self.normals.get(vn2_index),
-            self.normals.get(vn2_index + 1),
-            self.normals.get(vn2_index + 2),
-            self.normals.get(vn3_index),
-            self.normals.get(vn3_index + 1),
-            self.normals.get(vn3_index + 2),
-        ];
-
-        let mut new_normals: Vec<f32> = Vec::with_capacity(9);
-
-        for (vn, normal) in normals.chunks(3).enumerate() {
-            if let [Some(x), Some(y), Some(z)] = normal {
-                new_normals.extend([*x, *y, *z]);
-            } else {
-                return match vn {
-                    0 => Err(vn1),
-                    1 => Err(vn2),
-                    _ => Err(vn3),
-                };
-            }
-        }
+        let [vn1, vn2, vn3] = [vn1, vn2, vn3].map(|reference_number| {
+            self.normal_reference_to_index(reference_number)
+                .ok_or(reference_number)
+        });
+        let indices = [vn1?, vn2?, vn3?];
 
-        self.current_object
-            .as_ref()
-            .borrow_mut()
-            .geometry
-            .normal
-            .extend(new_normals);
+        let mut current_object = self.current_object.as_ref().borrow_mut();
+
+        for index in indices {
+            current_object
+                .geometry
+                .normal
+                .extend_from_slice(&self.normals[index..index + 3]);
+        }
 
         Ok(())
     }
@@ -338,39 +296,78 @@ impl ObjParseState {
     /// * `Err(i32)` if there is an invalid reference number, which is included
     ///   in the enum.
     fn add_uv(&mut self, vt1: i32, vt2: i32, vt3: i32) -> Result<(), i32> {
-        let vt1_index = self.uv_reference_to_index(vt1);
-        let vt2_index = self.uv_reference_to_index(vt2);
-        let vt3_index = self.uv_reference_to_index(vt3);
-
-        let uvs = [
-            self.uvs.get(vt1_index),
-            self.uvs.get(vt1_index + 1),
-            self.uvs.get(vt2_index),
-            self.uvs.get(vt2_index + 1),
-            self.uvs.get(vt3_index),
-            self.uvs.get(vt3_index + 1),
-        ];
-
-        let mut new_uvs: Vec<f32> = Vec::with_capacity(6);
-
-        for (vt, uv) in uvs.chunks(2).enumerate() {
-            if let [Some(u), Some(v)] = uv {
-                new_uvs.extend([*u, *v]);
-            } else {
-                return match vt {
-                    0 => Err(vt1),
-                    1 => Err(vt2),
-                    _ => Err(vt3),
-                };
-            }
+        let [vt1, vt2, vt3] = [vt1, vt2, vt3].map(|reference_number| {
+            self.uv_reference_to_index(reference_number)
+                .ok_or(reference_number)
+        });
+        let indices = [vt1?, vt2?, vt3?];
+
+        let mut current_object = self.current_object.as_ref().borrow_mut();
+
+        for index in indices {
+            current_object
+                .geometry
+                .uv
+                .extend_from_slice(&self.uvs[index..index + 2]);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the position of the vertex with the given reference number, or
+    /// `None` if the reference number is invalid.
+    fn vertex(&self, reference_number: i32) -> Option<[f32; 3]> {
+        let index = self.vertex_reference_to_index(reference_number)?;
+
+        Some([
+            self.vertices[index],
+            self.vertices[index + 1],
+            self.vertices[index + 2],
+        ])
+    }
+
+    /// Adds a polyline to the current object as line segments between every
+    /// consecutive pair of vertices, given their reference numbers.
+    ///
+    /// ## Returns
+    ///
+    /// * `Ok(())` if successful.
+    /// * `Err(i32)` if there is an invalid reference number, which is included
+    ///   in the enum.
+    fn add_line(&mut self, v: &[i32]) -> Result<(), i32> {
+        let vertices = v
+            .iter()
+            .map(|&reference_number| self.vertex(reference_number).ok_or(reference_number))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut current_object = self.current_object.as_ref().borrow_mut();
+
+        for segment in vertices.windows(2) {
+            current_object.lines.extend(segment.concat());
         }
 
+        Ok(())
+    }
+
+    /// Adds points to the current object given the reference numbers of their
+    /// vertices.
+    ///
+    /// ## Returns
+    ///
+    /// * `Ok(())` if successful.
+    /// * `Err(i32)` if there is an invalid reference number, which is included
+    ///   in the enum.
+    fn add_points(&mut self, v: &[i32]) -> Result<(), i32> {
+        let vertices = v
+            .iter()
+            .map(|&reference_number| self.vertex(reference_number).ok_or(reference_number))
+            .collect::<Result<Vec<_>, _>>()?;
+
         self.current_object
             .as_ref()
             .borrow_mut()
-            .geometry
-            .uv
-            .extend(new_uvs);
+            .points
+            .extend(vertices.concat());
 
         Ok(())
     }
@@ -437,7 +434,38 @@ impl ObjParseState {
     }
 }
 
+/// Converts the given reference number to the index of the element it refers
+/// to among `count` elements, or `None` if it does not refer to one.
+///
+/// Positive reference numbers count from the first element, starting at `1`,
+/// and negative ones count back from the last element, starting at `-1`. `0`
+/// never refers to an element.
+fn reference_to_index(reference_number: i32, count: usize) -> Option<usize> {
+    let offset = reference_number.unsigned_abs() as usize;
+
+    let index = match reference_number {
+        1.. => offset - 1,
+        0 => return None,
+        _ => count.checked_sub(offset)?,
+    };
+
+    (index < count).then_some(index)
+}
+
 impl ObjParser {
+    /// Creates the geometry of lines or points with the given positions, and
+    /// with zero normals and UVs.
+    fn geometry_from_positions(position: Vec<f32>) -> BufferGeometry {
+        let vertex_count = position.len() / 3;
+
+        BufferGeometry::new(
+            position,
+            vec![0.0; vertex_count * 3],
+            vec![0.0; vertex_count * 2],
+            None,
+        )
+    }
+
     /// Parses a string slice into an `f32`. Used for parsing vertex data.
     fn parse_f32(s: &str) -> Option<f32> {
         s.parse::<f32>().ok()
@@ -467,6 +495,13 @@ impl ObjParser {
     /// * If an `f` command does not have enough arguments, it will be ignored.
     ///   Note that the same does not apply for `v`, `vt`, or `vn`, because they
     ///   are needed for reference numbers to work.
+    /// * If one of the `v` values in `l v1/vt1 v2/vt2 ...` or `p v1 v2 ...`
+    ///   could not be parsed, the command will be ignored. The `vt` values of
+    ///   lines are not used.
+    ///
+    /// Faces, lines, and points of an object become separate children of the
+    /// group with the object's name: a [mesh](Mesh), [line segments](LineSegments),
+    /// and [points](Points) respectively.
     ///
     /// ## Parameters
     ///
@@ -603,6 +638,25 @@ impl ObjParser {
                         };
                     }
                 }
+                "l" | "p" => {
+                    let v: Option<Vec<i32>> = parts
+                        .map(|vertex| vertex.split('/').next().and_then(Self::parse_i32))
+                        .collect();
+
+                    let result = match (command, v) {
+                        ("l", Some(v)) if v.len() >= 2 => state.add_line(&v),
+                        ("p", Some(v)) => state.add_points(&v),
+                        _ => continue, // skip lines without enough vertices
+                    };
+
+                    if let Err(reference_number) = result {
+                        return Err(ObjParseError::InvalidReferenceNumber {
+                            line_num,
+                            data_type: String::from("v"),
+                            reference_number,
+                        });
+                    }
+                }
                 "o" | "g" => {
                     let name = parts.next().map(|s| s.to_string());
                     state.start_object(name, true);
@@ -621,27 +675,47 @@ impl ObjParser {
         state.finalize();
 
         let group = Rc::new(Object3d::new(Group));
+        let material: Rc<dyn Material> = Rc::new(NormalMaterial::new());
+        let line_material: Rc<dyn Material> = Rc::new(LineBasicMaterial::default());
+        let points_material: Rc<dyn Material> = Rc::new(PointsMaterial::default());
 
         state.objects.iter().for_each(|object| {
             let object = object.take();
+            let mut objects_3d: Vec<Object3d> = Vec::with_capacity(3);
+
+            // Skip groups/objects that do not have any faces, lines, or points.
+            if !object.geometry.position.is_empty() {
+                let buffer_geometry = BufferGeometry::new(
+                    object.geometry.position,
+                    object.geometry.normal,
+                    object.geometry.uv,
+                    None,
+                );
+
+                objects_3d.push(Mesh::new(Rc::new(buffer_geometry), Rc::clone(&material)).into());
+            }
 
-            // Skip groups/objects that do not have any faces.
-            if object.geometry.position.is_empty() {
-                return;
+            if !object.lines.is_empty() {
+                let buffer_geometry = Self::geometry_from_positions(object.lines);
+
+                objects_3d.push(
+                    LineSegments::new(Rc::new(buffer_geometry), Rc::clone(&line_material)).into(),
+                );
             }
 
-            let buffer_geometry = BufferGeometry {
-                position: object.geometry.position,
-                normal: object.geometry.normal,
-                uv: object.geometry.uv,
-                indices: None,
-            };
+            if !object.points.is_empty() {
+                let buffer_geometry = Self::geometry_from_positions(object.points);
+
+                objects_3d.push(
+                    Points::new(Rc::new(buffer_geometry), Rc::clone(&points_material)).into(),
+                );
+            }
 
-            let mut object_3d: Object3d = Mesh::new(Rc::new(buffer_geometry)).into();
-            object_3d.name = RefCell::new(object.name);
+            for object_3d in objects_3d {
+                object_3d.name.replace(object.name.clone());
 
-            let mesh = Rc::new(object_3d);
-            Object3d::add(&group, &mesh);
+                Object3d::add(&group, &Rc::new(object_3d));
+            }
         });
 
         Ok(ObjParseResult {
@@ -651,3 +725,66 @@ impl ObjParser {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Object3dKind;
+
+    const VERTICES: &str = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvn 0 0 1\n";
+
+    /// Parses the vertices followed by the specified command, and returns the
+    /// invalid reference number of the error, if any.
+    fn invalid_reference_number(command: &str) -> Option<i32> {
+        match ObjParser::parse(&format!("{VERTICES}{command}"), None) {
+            Err(ObjParseError::InvalidReferenceNumber {
+                reference_number, ..
+            }) => Some(reference_number),
+            _ => None,
+        }
+    }
+
+    /// Returns the positions of the first points in the subtree of the
+    /// specified object.
+    fn points_position(object: &Rc<Object3d>) -> Option<Vec<f32>> {
+        if let Object3dKind::Points(points) = &object.kind {
+            return Some(points.geometry.position.borrow().clone());
+        }
+
+        object.children.borrow().iter().find_map(points_position)
+    }
+
+    #[test]
+    fn zero_reference_number_is_invalid() {
+        assert_eq!(invalid_reference_number("l 0 1"), Some(0));
+        assert_eq!(invalid_reference_number("p 0"), Some(0));
+        assert_eq!(invalid_reference_number("f 0//1 1//1 2//1"), Some(0));
+    }
+
+    #[test]
+    fn out_of_range_reference_numbers_are_invalid() {
+        assert_eq!(invalid_reference_number("p -99"), Some(-99));
+        assert_eq!(invalid_reference_number("p -4"), Some(-4));
+        assert_eq!(invalid_reference_number("l 1 4"), Some(4));
+        assert_eq!(invalid_reference_number("p 2147483647"), Some(i32::MAX));
+        assert_eq!(invalid_reference_number("p -2147483648"), Some(i32::MIN));
+        assert_eq!(invalid_reference_number("f 1//1 2//1 4//1"), Some(4));
+        assert_eq!(invalid_reference_number("f -4//1 1//1 2//1"), Some(-4));
+        assert_eq!(
+            invalid_reference_number("f 1//1 2//1 -2147483648//1"),
+            Some(i32::MIN)
+        );
+    }
+
+    #[test]
+    fn negative_reference_numbers_count_from_last_vertex() {
+        let Ok(result) = ObjParser::parse(&format!("{VERTICES}p -1 -3 2"), None) else {
+            panic!("valid reference numbers");
+        };
+
+        assert_eq!(
+            points_position(&result.group),
+            Some(vec![0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0])
+        );
+    }
+}
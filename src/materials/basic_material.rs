@@ -0,0 +1,70 @@
+use std::{cell::RefCell, rc::Rc};
+
+use poli_math::Color;
+
+use super::{Material, MaterialSettings};
+use crate::{math::color::to_linear, textures::Texture};
+
+/// Material that shades the surface with a single flat color, regardless of
+/// lighting.
+pub struct BasicMaterial {
+    /// The color of the surface.
+    pub color: RefCell<Color>,
+    /// Texture whose color multiplies [`color`](Self::color). Default is
+    /// `None`.
+    pub map: RefCell<Option<Rc<Texture>>>,
+    /// The opacity, transparency and wireframe of the surface. Default is
+    /// [`MaterialSettings::default`].
+    pub settings: RefCell<MaterialSettings>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct BasicMaterialUniform {
+    color: [f32; 4],
+}
+
+impl BasicMaterial {
+    /// Creates a new basic material with the specified color.
+    pub fn new(color: Color) -> Self {
+        Self {
+            color: RefCell::new(color),
+            map: RefCell::new(None),
+            settings: RefCell::new(MaterialSettings::default()),
+        }
+    }
+}
+
+impl Default for BasicMaterial {
+    /// Returns a white basic material.
+    fn default() -> Self {
+        Self::new(Color {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+        })
+    }
+}
+
+impl Material for BasicMaterial {
+    fn fragment_shader(&self) -> &'static str {
+        include_str!("shaders/basic_material.wgsl")
+    }
+
+    fn uniform(&self) -> Vec<u8> {
+        let [r, g, b] = to_linear(&self.color.borrow());
+
+        bytemuck::bytes_of(&BasicMaterialUniform {
+            color: [r, g, b, self.settings.borrow().opacity],
+        })
+        .to_vec()
+    }
+
+    fn textures(&self) -> Vec<Option<Rc<Texture>>> {
+        vec![self.map.borrow().clone()]
+    }
+
+    fn settings(&self) -> MaterialSettings {
+        *self.settings.borrow()
+    }
+}
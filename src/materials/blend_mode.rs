@@ -0,0 +1,19 @@
+/// How the colors of a transparent material are combined with the colors
+/// behind it. See [`MaterialSettings::blend_mode`](super::MaterialSettings::blend_mode).
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+pub enum BlendMode {
+    /// Mixes the color with the color behind it according to its alpha,
+    /// like a sheet of tinted glass.
+    #[default]
+    Normal,
+    /// Adds the color, weighted by its alpha, to the color behind it, which
+    /// brightens it. Useful for glows, fire, and particles.
+    Additive,
+    /// Multiplies the color behind by the color, which darkens it. The alpha
+    /// is ignored.
+    Multiply,
+    /// Like [`Normal`](Self::Normal), but the color is expected to be already
+    /// multiplied by its alpha, e.g. when sampled from a premultiplied
+    /// texture.
+    Premultiplied,
+}
@@ -0,0 +1,83 @@
+use std::{cell::RefCell, rc::Rc};
+
+use poli_math::Color;
+
+use super::{Material, MaterialSettings};
+use crate::{math::color::to_linear, textures::Texture};
+
+/// Material for non-shiny surfaces, which reflect light equally in all
+/// directions according to [Lambert's cosine law][lambert].
+///
+/// [lambert]: https://en.wikipedia.org/wiki/Lambertian_reflectance
+pub struct LambertMaterial {
+    /// The diffuse color of the surface.
+    pub color: RefCell<Color>,
+    /// The color that the surface emits regardless of lighting. Default is
+    /// black.
+    pub emissive: RefCell<Color>,
+    /// Texture whose color multiplies [`color`](Self::color). Default is
+    /// `None`.
+    pub map: RefCell<Option<Rc<Texture>>>,
+    /// The opacity, transparency and wireframe of the surface. Default is
+    /// [`MaterialSettings::default`].
+    pub settings: RefCell<MaterialSettings>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct LambertMaterialUniform {
+    color: [f32; 4],
+    emissive: [f32; 4],
+}
+
+impl LambertMaterial {
+    /// Creates a new Lambert material with the specified diffuse color.
+    pub fn new(color: Color) -> Self {
+        Self {
+            color: RefCell::new(color),
+            emissive: RefCell::new(Color {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+            }),
+            map: RefCell::new(None),
+            settings: RefCell::new(MaterialSettings::default()),
+        }
+    }
+}
+
+impl Default for LambertMaterial {
+    /// Returns a white Lambert material.
+    fn default() -> Self {
+        Self::new(Color {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+        })
+    }
+}
+
+impl Material for LambertMaterial {
+    fn fragment_shader(&self) -> &'static str {
+        include_str!("shaders/lambert_material.wgsl")
+    }
+
+    fn uniform(&self) -> Vec<u8> {
+        let [r, g, b] = to_linear(&self.color.borrow());
+        let emissive = to_linear(&self.emissive.borrow());
+
+        bytemuck::bytes_of(&LambertMaterialUniform {
+            color: [r, g, b, self.settings.borrow().opacity],
+            emissive: [emissive[0], emissive[1], emissive[2], 0.0],
+        })
+        .to_vec()
+    }
+
+    fn textures(&self) -> Vec<Option<Rc<Texture>>> {
+        vec![self.map.borrow().clone()]
+    }
+
+    fn settings(&self) -> MaterialSettings {
+        *self.settings.borrow()
+    }
+}
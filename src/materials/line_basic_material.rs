@@ -0,0 +1,65 @@
+use std::cell::RefCell;
+
+use poli_math::Color;
+
+use super::{Material, MaterialSettings};
+use crate::math::color::to_linear;
+
+/// Material for [lines](crate::Line) and [line segments](crate::LineSegments)
+/// that draws them with a single flat color, regardless of lighting.
+///
+/// Lines are always 1 pixel wide, because WebGPU does not support other line
+/// widths.
+pub struct LineBasicMaterial {
+    /// The color of the lines.
+    pub color: RefCell<Color>,
+    /// The opacity and transparency of the lines. Default is
+    /// [`MaterialSettings::default`].
+    pub settings: RefCell<MaterialSettings>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct LineBasicMaterialUniform {
+    color: [f32; 4],
+}
+
+impl LineBasicMaterial {
+    /// Creates a new line material with the specified color.
+    pub fn new(color: Color) -> Self {
+        Self {
+            color: RefCell::new(color),
+            settings: RefCell::new(MaterialSettings::default()),
+        }
+    }
+}
+
+impl Default for LineBasicMaterial {
+    /// Returns a white line material.
+    fn default() -> Self {
+        Self::new(Color {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+        })
+    }
+}
+
+impl Material for LineBasicMaterial {
+    fn fragment_shader(&self) -> &'static str {
+        include_str!("shaders/line_basic_material.wgsl")
+    }
+
+    fn uniform(&self) -> Vec<u8> {
+        let [r, g, b] = to_linear(&self.color.borrow());
+
+        bytemuck::bytes_of(&LineBasicMaterialUniform {
+            color: [r, g, b, self.settings.borrow().opacity],
+        })
+        .to_vec()
+    }
+
+    fn settings(&self) -> MaterialSettings {
+        *self.settings.borrow()
+    }
+}
@@ -0,0 +1,71 @@
+use std::{any::TypeId, rc::Rc};
+
+use super::MaterialSettings;
+use crate::textures::Texture;
+
+/// The look of a [mesh](crate::Mesh), which determines how its surface is
+/// shaded, or of [lines](crate::Line) and [points](crate::Points). Should be
+/// implemented on all material parameter structs.
+///
+/// A material provides the fragment stage of the renderer's shader. The source
+/// is appended to the renderer's own shader code, which declares:
+///
+/// * The object and camera bindings in `@group(0)` e.g. `camera_position`,
+///   and the `view_direction` helper function, which also handles
+///   orthographic cameras.
+/// * The `VertexOutput` struct with the interpolated `world_position`,
+///   `normal`, `world_normal`, and `uv` of the fragment, and the `color` of
+///   its [instance](crate::InstancedMesh), which is white for other meshes.
+///   For points, `uv` goes across each point from `(0, 0)` at the top-left
+///   corner to `(1, 1)` at the bottom-right corner.
+/// * The scene's `lights` in `@group(2)`, along with the `ambient_light` and
+///   `incident_light` helper functions.
+pub trait Material: 'static {
+    /// Returns the WGSL source of the material's fragment stage, which must
+    /// define a `fragmentMain` entry point that takes a `VertexOutput` and
+    /// returns the color at `@location(0)`. The material's uniform buffer, if
+    /// used, is bound to `@group(1) @binding(0)`.
+    fn fragment_shader(&self) -> &'static str;
+
+    /// Returns the content of the material's uniform buffer, laid out as the
+    /// uniform struct is declared in the [fragment shader](Self::fragment_shader).
+    /// The renderer writes it to the GPU whenever it changes.
+    ///
+    /// Shaders work with linear colors, so sRGB-encoded [`Color`] parameters
+    /// should be converted with [`srgb_to_linear`](crate::srgb_to_linear).
+    ///
+    /// [`Color`]: poli_math::Color
+    fn uniform(&self) -> Vec<u8>;
+
+    /// Returns the textures that the material samples from. Texture `i` is
+    /// bound to `@group(1) @binding(1 + 2 * i)` as a `texture_2d<f32>`, and its
+    /// sampler to `@group(1) @binding(2 + 2 * i)`. Renderers upload textures
+    /// to the GPU the first time they are used.
+    ///
+    /// The number of textures must be the same for all materials of a type.
+    /// Unset textures are `None` and are replaced by a 1x1 white texture, so
+    /// shaders can sample them unconditionally. Default is no textures.
+    fn textures(&self) -> Vec<Option<Rc<Texture>>> {
+        Vec::new()
+    }
+
+    /// Returns the settings that renderers read to draw the material, such as
+    /// whether it is transparent or drawn as a wireframe. Default is [`MaterialSettings::default`],
+    /// an opaque material.
+    fn settings(&self) -> MaterialSettings {
+        MaterialSettings::default()
+    }
+
+    /// Returns the width and height in pixels of each point when the material
+    /// is used by [points](crate::Points). Default is `1.0`.
+    fn point_size(&self) -> f32 {
+        1.0
+    }
+
+    /// Returns the identifier of the material's type. Renderers build one
+    /// render pipeline per material type and share it between all materials
+    /// of that type.
+    fn material_type_id(&self) -> TypeId {
+        TypeId::of::<Self>()
+    }
+}
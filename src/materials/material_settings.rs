@@ -0,0 +1,40 @@
+use super::BlendMode;
+
+/// Settings that all built-in materials share regardless of how they shade,
+/// which control how renderers draw them. See [`Material::settings`].
+///
+/// [`Material::settings`]: super::Material::settings
+#[derive(Clone, Copy, Debug)]
+pub struct MaterialSettings {
+    /// The opacity of the material, from `0.0` (invisible) to `1.0` (opaque),
+    /// which multiplies the alpha of its color. The material is only blended
+    /// with the colors behind it if it is [`transparent`](Self::transparent).
+    /// Default is `1.0`.
+    pub opacity: f32,
+    /// Whether the material is transparent. Renderers draw transparent
+    /// objects after opaque ones, from back to front, and blend them with the
+    /// colors behind them according to the [blend mode](Self::blend_mode)
+    /// without writing their depth. Default is `false`.
+    pub transparent: bool,
+    /// How the colors of the material are combined with the colors behind it
+    /// if it is [transparent](Self::transparent). The alpha is the one
+    /// returned by the fragment shader. Default is [`BlendMode::Normal`].
+    pub blend_mode: BlendMode,
+    /// Whether meshes with the material are drawn as wireframes, i.e. only
+    /// the edges of their triangles are drawn as 1 pixel wide lines, e.g. to
+    /// inspect the topology of a mesh. Lines and points ignore it. Default is
+    /// `false`.
+    pub wireframe: bool,
+}
+
+impl Default for MaterialSettings {
+    /// Returns the settings of an opaque material that is not a wireframe.
+    fn default() -> Self {
+        Self {
+            opacity: 1.0,
+            transparent: false,
+            blend_mode: BlendMode::Normal,
+            wireframe: false,
+        }
+    }
+}
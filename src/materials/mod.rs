@@ -0,0 +1,23 @@
+//! Contains different kinds of materials, which define the look of 3D objects.
+
+mod basic_material;
+mod blend_mode;
+mod lambert_material;
+mod line_basic_material;
+mod material;
+mod material_settings;
+mod normal_material;
+mod phong_material;
+mod points_material;
+mod standard_material;
+
+pub use basic_material::*;
+pub use blend_mode::*;
+pub use lambert_material::*;
+pub use line_basic_material::*;
+pub use material::*;
+pub use material_settings::*;
+pub use normal_material::*;
+pub use phong_material::*;
+pub use points_material::*;
+pub use standard_material::*;
@@ -0,0 +1,46 @@
+use std::cell::RefCell;
+
+use super::{Material, MaterialSettings};
+
+/// Material that maps the vertex normals of the surface to RGB colors. Useful
+/// for debugging geometries.
+#[derive(Default)]
+pub struct NormalMaterial {
+    /// The opacity, transparency and wireframe of the surface. Default is
+    /// [`MaterialSettings::default`].
+    pub settings: RefCell<MaterialSettings>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct NormalMaterialUniform {
+    opacity: f32,
+    _padding: [f32; 3],
+}
+
+impl NormalMaterial {
+    /// Creates a new normal material.
+    pub fn new() -> Self {
+        Self {
+            settings: RefCell::new(MaterialSettings::default()),
+        }
+    }
+}
+
+impl Material for NormalMaterial {
+    fn fragment_shader(&self) -> &'static str {
+        include_str!("shaders/normal_material.wgsl")
+    }
+
+    fn uniform(&self) -> Vec<u8> {
+        bytemuck::bytes_of(&NormalMaterialUniform {
+            opacity: self.settings.borrow().opacity,
+            _padding: [0.0; 3],
+        })
+        .to_vec()
+    }
+
+    fn settings(&self) -> MaterialSettings {
+        *self.settings.borrow()
+    }
+}
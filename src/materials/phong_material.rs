@@ -0,0 +1,99 @@
+use std::{cell::RefCell, rc::Rc};
+
+use poli_math::Color;
+
+use super::{Material, MaterialSettings};
+use crate::{math::color::to_linear, textures::Texture};
+
+/// Material for shiny surfaces with specular highlights, using the
+/// [Blinn-Phong reflection model][blinn-phong].
+///
+/// [blinn-phong]: https://en.wikipedia.org/wiki/Blinn%E2%80%93Phong_reflection_model
+pub struct PhongMaterial {
+    /// The diffuse color of the surface.
+    pub color: RefCell<Color>,
+    /// The color that the surface emits regardless of lighting. Default is
+    /// black.
+    pub emissive: RefCell<Color>,
+    /// The color of the specular highlights. Default is a dark gray.
+    pub specular: RefCell<Color>,
+    /// How shiny the surface is. Higher values give smaller and sharper
+    /// specular highlights. Default is `30.0`.
+    pub shininess: RefCell<f32>,
+    /// Texture whose color multiplies [`color`](Self::color). Default is
+    /// `None`.
+    pub map: RefCell<Option<Rc<Texture>>>,
+    /// The opacity, transparency and wireframe of the surface. Default is
+    /// [`MaterialSettings::default`].
+    pub settings: RefCell<MaterialSettings>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct PhongMaterialUniform {
+    color: [f32; 4],
+    emissive: [f32; 4],
+    specular: [f32; 3],
+    shininess: f32,
+}
+
+impl PhongMaterial {
+    /// Creates a new Phong material with the specified diffuse color.
+    pub fn new(color: Color) -> Self {
+        Self {
+            color: RefCell::new(color),
+            emissive: RefCell::new(Color {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+            }),
+            specular: RefCell::new(Color {
+                r: 0.067,
+                g: 0.067,
+                b: 0.067,
+            }),
+            shininess: RefCell::new(30.0),
+            map: RefCell::new(None),
+            settings: RefCell::new(MaterialSettings::default()),
+        }
+    }
+}
+
+impl Default for PhongMaterial {
+    /// Returns a white Phong material.
+    fn default() -> Self {
+        Self::new(Color {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+        })
+    }
+}
+
+impl Material for PhongMaterial {
+    fn fragment_shader(&self) -> &'static str {
+        include_str!("shaders/phong_material.wgsl")
+    }
+
+    fn uniform(&self) -> Vec<u8> {
+        let [r, g, b] = to_linear(&self.color.borrow());
+        let emissive = to_linear(&self.emissive.borrow());
+        let specular = to_linear(&self.specular.borrow());
+
+        bytemuck::bytes_of(&PhongMaterialUniform {
+            color: [r, g, b, self.settings.borrow().opacity],
+            emissive: [emissive[0], emissive[1], emissive[2], 0.0],
+            specular,
+            shininess: *self.shininess.borrow(),
+        })
+        .to_vec()
+    }
+
+    fn textures(&self) -> Vec<Option<Rc<Texture>>> {
+        vec![self.map.borrow().clone()]
+    }
+
+    fn settings(&self) -> MaterialSettings {
+        *self.settings.borrow()
+    }
+}
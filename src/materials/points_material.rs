@@ -0,0 +1,81 @@
+use std::{cell::RefCell, rc::Rc};
+
+use poli_math::Color;
+
+use super::{Material, MaterialSettings};
+use crate::{math::color::to_linear, textures::Texture};
+
+/// Material for [points](crate::Points) that draws each point as a square of
+/// a fixed size on the screen, with a single flat color regardless of
+/// lighting.
+pub struct PointsMaterial {
+    /// The color of the points.
+    pub color: RefCell<Color>,
+    /// The width and height of each point in pixels. Default is `1.0`.
+    pub size: RefCell<f32>,
+    /// Texture whose color multiplies [`color`](Self::color). The whole
+    /// texture is mapped onto each point, e.g. to draw round points with a
+    /// texture of a disc and a [transparent](MaterialSettings::transparent) material.
+    /// Default is `None`.
+    pub map: RefCell<Option<Rc<Texture>>>,
+    /// The opacity and transparency of the points. Default is
+    /// [`MaterialSettings::default`].
+    pub settings: RefCell<MaterialSettings>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct PointsMaterialUniform {
+    color: [f32; 4],
+}
+
+impl PointsMaterial {
+    /// Creates a new points material with the specified color and a size of
+    /// 1 pixel.
+    pub fn new(color: Color) -> Self {
+        Self {
+            color: RefCell::new(color),
+            size: RefCell::new(1.0),
+            map: RefCell::new(None),
+            settings: RefCell::new(MaterialSettings::default()),
+        }
+    }
+}
+
+impl Default for PointsMaterial {
+    /// Returns a white points material.
+    fn default() -> Self {
+        Self::new(Color {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+        })
+    }
+}
+
+impl Material for PointsMaterial {
+    fn fragment_shader(&self) -> &'static str {
+        include_str!("shaders/points_material.wgsl")
+    }
+
+    fn uniform(&self) -> Vec<u8> {
+        let [r, g, b] = to_linear(&self.color.borrow());
+
+        bytemuck::bytes_of(&PointsMaterialUniform {
+            color: [r, g, b, self.settings.borrow().opacity],
+        })
+        .to_vec()
+    }
+
+    fn textures(&self) -> Vec<Option<Rc<Texture>>> {
+        vec![self.map.borrow().clone()]
+    }
+
+    fn settings(&self) -> MaterialSettings {
+        *self.settings.borrow()
+    }
+
+    fn point_size(&self) -> f32 {
+        *self.size.borrow()
+    }
+}
@@ -0,0 +1,12 @@
+struct BasicMaterial {
+  color: vec4f,
+}
+
+@group(1) @binding(0) var<uniform> material: BasicMaterial;
+@group(1) @binding(1) var map: texture_2d<f32>;
+@group(1) @binding(2) var map_sampler: sampler;
+
+@fragment
+fn fragmentMain(input: VertexOutput) -> @location(0) vec4f {
+  return material.color * textureSample(map, map_sampler, input.uv) * input.color;
+}
@@ -0,0 +1,23 @@
+struct LambertMaterial {
+  color: vec4f,
+  emissive: vec4f,
+}
+
+@group(1) @binding(0) var<uniform> material: LambertMaterial;
+@group(1) @binding(1) var map: texture_2d<f32>;
+@group(1) @binding(2) var map_sampler: sampler;
+
+@fragment
+fn fragmentMain(input: VertexOutput) -> @location(0) vec4f {
+  let base_color = material.color * textureSample(map, map_sampler, input.uv) * input.color;
+  let normal = normalize(input.world_normal);
+
+  var irradiance = ambient_light();
+
+  for (var i = 0u; i < lights.count; i++) {
+    let light = incident_light(lights.lights[i], input.world_position);
+    irradiance += light.color * max(dot(normal, light.direction), 0.0);
+  }
+
+  return vec4f(base_color.rgb * irradiance + material.emissive.rgb, base_color.a);
+}
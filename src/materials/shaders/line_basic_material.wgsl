@@ -0,0 +1,10 @@
+struct LineBasicMaterial {
+  color: vec4f,
+}
+
+@group(1) @binding(0) var<uniform> material: LineBasicMaterial;
+
+@fragment
+fn fragmentMain(input: VertexOutput) -> @location(0) vec4f {
+  return material.color * input.color;
+}
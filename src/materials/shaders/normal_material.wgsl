@@ -0,0 +1,10 @@
+struct NormalMaterial {
+  opacity: f32,
+}
+
+@group(1) @binding(0) var<uniform> material: NormalMaterial;
+
+@fragment
+fn fragmentMain(input: VertexOutput) -> @location(0) vec4f {
+  return vec4f((input.normal + 1) / 2, material.opacity);
+}
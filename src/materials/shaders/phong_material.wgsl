@@ -0,0 +1,36 @@
+struct PhongMaterial {
+  color: vec4f,
+  emissive: vec4f,
+  specular: vec3f,
+  shininess: f32,
+}
+
+@group(1) @binding(0) var<uniform> material: PhongMaterial;
+@group(1) @binding(1) var map: texture_2d<f32>;
+@group(1) @binding(2) var map_sampler: sampler;
+
+@fragment
+fn fragmentMain(input: VertexOutput) -> @location(0) vec4f {
+  let base_color = material.color * textureSample(map, map_sampler, input.uv) * input.color;
+  let normal = normalize(input.world_normal);
+  let view_direction = view_direction(input.world_position);
+
+  var diffuse = ambient_light();
+  var specular = vec3f(0.0);
+
+  for (var i = 0u; i < lights.count; i++) {
+    let light = incident_light(lights.lights[i], input.world_position);
+    let n_dot_l = max(dot(normal, light.direction), 0.0);
+
+    if n_dot_l > 0.0 {
+      let half_direction = normalize(light.direction + view_direction);
+
+      diffuse += light.color * n_dot_l;
+      specular += light.color * pow(max(dot(normal, half_direction), 0.0), material.shininess);
+    }
+  }
+
+  let color = base_color.rgb * diffuse + material.specular * specular + material.emissive.rgb;
+
+  return vec4f(color, base_color.a);
+}
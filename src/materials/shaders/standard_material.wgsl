@@ -0,0 +1,87 @@
+const PI: f32 = 3.141592653589793;
+
+struct StandardMaterial {
+  color: vec4f,
+  emissive: vec3f,
+  metallic: f32,
+  roughness: f32,
+  occlusion: f32,
+}
+
+@group(1) @binding(0) var<uniform> material: StandardMaterial;
+@group(1) @binding(1) var map: texture_2d<f32>;
+@group(1) @binding(2) var map_sampler: sampler;
+@group(1) @binding(3) var metallic_roughness_map: texture_2d<f32>;
+@group(1) @binding(4) var metallic_roughness_map_sampler: sampler;
+@group(1) @binding(5) var emissive_map: texture_2d<f32>;
+@group(1) @binding(6) var emissive_map_sampler: sampler;
+@group(1) @binding(7) var occlusion_map: texture_2d<f32>;
+@group(1) @binding(8) var occlusion_map_sampler: sampler;
+
+// GGX (Trowbridge-Reitz) normal distribution function.
+fn distribution_ggx(n_dot_h: f32, alpha: f32) -> f32 {
+  let alpha_2 = alpha * alpha;
+  let d = n_dot_h * n_dot_h * (alpha_2 - 1.0) + 1.0;
+
+  return alpha_2 / (PI * d * d);
+}
+
+// Height-correlated Smith-GGX visibility function, which is the geometric
+// shadowing-masking term divided by the BRDF's denominator.
+fn visibility_smith_ggx(n_dot_l: f32, n_dot_v: f32, alpha: f32) -> f32 {
+  let alpha_2 = alpha * alpha;
+  let ggx_v = n_dot_l * sqrt(n_dot_v * n_dot_v * (1.0 - alpha_2) + alpha_2);
+  let ggx_l = n_dot_v * sqrt(n_dot_l * n_dot_l * (1.0 - alpha_2) + alpha_2);
+
+  return 0.5 / max(ggx_v + ggx_l, 1e-5);
+}
+
+// Schlick's approximation of the Fresnel term.
+fn fresnel_schlick(f0: vec3f, v_dot_h: f32) -> vec3f {
+  return f0 + (1.0 - f0) * pow(1.0 - v_dot_h, 5.0);
+}
+
+@fragment
+fn fragmentMain(input: VertexOutput) -> @location(0) vec4f {
+  // Textures are sampled before any branching, as required for sampling with
+  // implicit derivatives.
+  let base_color = material.color * textureSample(map, map_sampler, input.uv) * input.color;
+  let metallic_roughness = textureSample(metallic_roughness_map, metallic_roughness_map_sampler, input.uv);
+  let emissive = material.emissive * textureSample(emissive_map, emissive_map_sampler, input.uv).rgb;
+  // The occlusion strength scales the occlusion of the map, as in glTF.
+  let occlusion = 1.0 + material.occlusion * (textureSample(occlusion_map, occlusion_map_sampler, input.uv).r - 1.0);
+
+  let metallic = saturate(material.metallic * metallic_roughness.b);
+  let roughness = clamp(material.roughness * metallic_roughness.g, 0.04, 1.0);
+  let alpha = roughness * roughness;
+
+  let diffuse_color = base_color.rgb * (1.0 - metallic);
+  let f0 = mix(vec3f(0.04), base_color.rgb, metallic);
+
+  let normal = normalize(input.world_normal);
+  let view_direction = view_direction(input.world_position);
+  let n_dot_v = max(dot(normal, view_direction), 1e-4);
+
+  var color = ambient_light() * diffuse_color / PI * occlusion;
+
+  for (var i = 0u; i < lights.count; i++) {
+    let light = incident_light(lights.lights[i], input.world_position);
+    let n_dot_l = dot(normal, light.direction);
+
+    if n_dot_l > 0.0 {
+      let half_direction = normalize(light.direction + view_direction);
+      let n_dot_h = max(dot(normal, half_direction), 0.0);
+      let v_dot_h = max(dot(view_direction, half_direction), 0.0);
+
+      let fresnel = fresnel_schlick(f0, v_dot_h);
+      let specular = fresnel * distribution_ggx(n_dot_h, alpha) * visibility_smith_ggx(n_dot_l, n_dot_v, alpha);
+      let diffuse = (1.0 - fresnel) * diffuse_color / PI;
+
+      color += (diffuse + specular) * light.color * n_dot_l;
+    }
+  }
+
+  color += emissive;
+
+  return vec4f(color, base_color.a);
+}
@@ -0,0 +1,135 @@
+use std::{cell::RefCell, rc::Rc};
+
+use poli_math::Color;
+
+use super::{Material, MaterialSettings};
+use crate::{math::color::to_linear, textures::Texture};
+
+/// Physically based material that follows the metallic-roughness model of
+/// [glTF 2.0][gltf], so that scenes look the same as in other glTF viewers.
+///
+/// Direct lighting uses the Cook-Torrance microfacet BRDF with the GGX normal
+/// distribution. As in glTF, light intensities are in physical units: lux for
+/// directional lights and candela for point and spot lights.
+///
+/// [gltf]: https://registry.khronos.org/glTF/specs/2.0/glTF-2.0.html#materials
+pub struct StandardMaterial {
+    /// The base color of the surface. For metals, this is the color of the
+    /// specular reflection. Default is white.
+    pub color: RefCell<Color>,
+    /// How metallic the surface is, from `0.0` (dielectric) to `1.0` (metal).
+    /// Default is `1.0`, as in glTF.
+    pub metallic: RefCell<f32>,
+    /// How rough the surface is, from `0.0` (smooth, mirror-like) to `1.0`
+    /// (rough, diffuse-like). Default is `1.0`, as in glTF.
+    pub roughness: RefCell<f32>,
+    /// The color that the surface emits regardless of lighting. Default is
+    /// black.
+    pub emissive: RefCell<Color>,
+    /// The strength of the emitted color, as in the
+    /// `KHR_materials_emissive_strength` glTF extension. Default is `1.0`.
+    pub emissive_intensity: RefCell<f32>,
+    /// The strength of the [occlusion map](Self::occlusion_map), from `0.0`
+    /// (the map has no effect) to `1.0` (the map applies fully), as the
+    /// `strength` of glTF occlusion textures. Default is `1.0`.
+    pub occlusion: RefCell<f32>,
+    /// Texture whose color multiplies [`color`](Self::color). Default is
+    /// `None`.
+    pub map: RefCell<Option<Rc<Texture>>>,
+    /// Texture whose green channel multiplies
+    /// [`roughness`](Self::roughness) and whose blue channel multiplies
+    /// [`metallic`](Self::metallic), as in glTF. Should not be sRGB encoded.
+    /// Default is `None`.
+    pub metallic_roughness_map: RefCell<Option<Rc<Texture>>>,
+    /// Texture whose color multiplies [`emissive`](Self::emissive). Default is
+    /// `None`.
+    pub emissive_map: RefCell<Option<Rc<Texture>>>,
+    /// Texture whose red channel is how much ambient light reaches the
+    /// surface, from `0.0` (fully occluded) to `1.0` (not occluded), scaled
+    /// by the [`occlusion`](Self::occlusion) strength as in glTF. Only affects
+    /// ambient lights. Should not be sRGB encoded. Default is `None`.
+    pub occlusion_map: RefCell<Option<Rc<Texture>>>,
+    /// The opacity, transparency and wireframe of the surface. Default is
+    /// [`MaterialSettings::default`].
+    pub settings: RefCell<MaterialSettings>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct StandardMaterialUniform {
+    color: [f32; 4],
+    emissive: [f32; 3],
+    metallic: f32,
+    roughness: f32,
+    occlusion: f32,
+    _padding: [f32; 2],
+}
+
+impl StandardMaterial {
+    /// Creates a new standard material with the specified base color.
+    pub fn new(color: Color) -> Self {
+        Self {
+            color: RefCell::new(color),
+            metallic: RefCell::new(1.0),
+            roughness: RefCell::new(1.0),
+            emissive: RefCell::new(Color {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+            }),
+            emissive_intensity: RefCell::new(1.0),
+            occlusion: RefCell::new(1.0),
+            map: RefCell::new(None),
+            metallic_roughness_map: RefCell::new(None),
+            emissive_map: RefCell::new(None),
+            occlusion_map: RefCell::new(None),
+            settings: RefCell::new(MaterialSettings::default()),
+        }
+    }
+}
+
+impl Default for StandardMaterial {
+    /// Returns a white standard material.
+    fn default() -> Self {
+        Self::new(Color {
+            r: 1.0,
+            g: 1.0,
+            b: 1.0,
+        })
+    }
+}
+
+impl Material for StandardMaterial {
+    fn fragment_shader(&self) -> &'static str {
+        include_str!("shaders/standard_material.wgsl")
+    }
+
+    fn uniform(&self) -> Vec<u8> {
+        let [r, g, b] = to_linear(&self.color.borrow());
+        let emissive = to_linear(&self.emissive.borrow());
+        let emissive_intensity = *self.emissive_intensity.borrow();
+
+        bytemuck::bytes_of(&StandardMaterialUniform {
+            color: [r, g, b, self.settings.borrow().opacity],
+            emissive: emissive.map(|c| c * emissive_intensity),
+            metallic: *self.metallic.borrow(),
+            roughness: *self.roughness.borrow(),
+            occlusion: *self.occlusion.borrow(),
+            _padding: [0.0; 2],
+        })
+        .to_vec()
+    }
+
+    fn textures(&self) -> Vec<Option<Rc<Texture>>> {
+        vec![
+            self.map.borrow().clone(),
+            self.metallic_roughness_map.borrow().clone(),
+            self.emissive_map.borrow().clone(),
+            self.occlusion_map.borrow().clone(),
+        ]
+    }
+
+    fn settings(&self) -> MaterialSettings {
+        *self.settings.borrow()
+    }
+}
@@ -0,0 +1,224 @@
+use poli_math::{Matrix4, Vector3};
+
+use super::vector::{from_array, to_array, transform_point};
+
+/// Axis-aligned bounding box, which is the space between two corners.
+#[derive(Clone, Copy)]
+pub struct Box3 {
+    /// The corner with the smallest coordinates.
+    pub min: Vector3,
+    /// The corner with the largest coordinates.
+    pub max: Vector3,
+}
+
+impl Default for Box3 {
+    /// Returns an empty box.
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl Box3 {
+    /// Creates a box with the specified corners.
+    pub fn new(min: Vector3, max: Vector3) -> Self {
+        Self { min, max }
+    }
+
+    /// Returns an empty box, which contains no points. Expanding it by a point
+    /// results in a box that only contains that point.
+    pub fn empty() -> Self {
+        Self {
+            min: from_array([f32::INFINITY; 3]),
+            max: from_array([f32::NEG_INFINITY; 3]),
+        }
+    }
+
+    /// Returns the smallest box that contains the specified points.
+    ///
+    /// * `points`: Every consecutive triplet specifies a point, as in
+    ///   [`BufferGeometry::position`](crate::BufferGeometry::position).
+    pub fn from_points(points: &[f32]) -> Self {
+        let mut box3 = Self::empty();
+
+        for point in points.chunks_exact(3) {
+            box3.expand_by_point(&from_array([point[0], point[1], point[2]]));
+        }
+
+        box3
+    }
+
+    /// Returns whether the box contains no points.
+    pub fn is_empty(&self) -> bool {
+        let (min, max) = (to_array(&self.min), to_array(&self.max));
+
+        (0..3).any(|i| max[i] < min[i])
+    }
+
+    /// Returns the center of the box.
+    pub fn center(&self) -> Vector3 {
+        let (min, max) = (to_array(&self.min), to_array(&self.max));
+
+        from_array([0, 1, 2].map(|i| (min[i] + max[i]) * 0.5))
+    }
+
+    /// Returns the width, height and depth of the box.
+    pub fn size(&self) -> Vector3 {
+        if self.is_empty() {
+            return Vector3::default();
+        }
+
+        let (min, max) = (to_array(&self.min), to_array(&self.max));
+
+        from_array([0, 1, 2].map(|i| max[i] - min[i]))
+    }
+
+    /// Returns the 8 corners of the box.
+    pub(crate) fn corners(&self) -> [[f32; 3]; 8] {
+        let (min, max) = (to_array(&self.min), to_array(&self.max));
+
+        [0, 1, 2, 3, 4, 5, 6, 7].map(|i| {
+            [
+                if i & 1 == 0 { min[0] } else { max[0] },
+                if i & 2 == 0 { min[1] } else { max[1] },
+                if i & 4 == 0 { min[2] } else { max[2] },
+            ]
+        })
+    }
+
+    /// Grows the box to contain the specified point.
+    pub fn expand_by_point(&mut self, point: &Vector3) {
+        let (mut min, mut max, point) = (to_array(&self.min), to_array(&self.max), to_array(point));
+
+        for i in 0..3 {
+            min[i] = min[i].min(point[i]);
+            max[i] = max[i].max(point[i]);
+        }
+
+        self.min = from_array(min);
+        self.max = from_array(max);
+    }
+
+    /// Grows the box to contain the specified box.
+    pub fn union(&mut self, other: &Box3) {
+        if !other.is_empty() {
+            self.expand_by_point(&other.min);
+            self.expand_by_point(&other.max);
+        }
+    }
+
+    /// Returns whether the specified point is inside the box or on its
+    /// surface.
+    pub fn contains_point(&self, point: &Vector3) -> bool {
+        let (min, max, point) = (to_array(&self.min), to_array(&self.max), to_array(point));
+
+        (0..3).all(|i| min[i] <= point[i] && point[i] <= max[i])
+    }
+
+    /// Returns whether the box and the specified box overlap.
+    pub fn intersects_box(&self, other: &Box3) -> bool {
+        let (min, max) = (to_array(&self.min), to_array(&self.max));
+        let (other_min, other_max) = (to_array(&other.min), to_array(&other.max));
+
+        (0..3).all(|i| min[i] <= other_max[i] && other_min[i] <= max[i])
+    }
+
+    /// Returns the smallest axis-aligned box that contains this box after it
+    /// has been transformed by the specified matrix e.g. a world matrix.
+    pub fn apply_matrix4(&self, matrix: &Matrix4) -> Box3 {
+        let mut box3 = Box3::empty();
+
+        if !self.is_empty() {
+            for corner in self.corners() {
+                box3.expand_by_point(&from_array(transform_point(matrix, corner)));
+            }
+        }
+
+        box3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a matrix that scales by `s` and then translates by `t`.
+    fn scale_translate(s: [f32; 3], t: [f32; 3]) -> Matrix4 {
+        Matrix4 {
+            elements: [
+                s[0], 0.0, 0.0, 0.0, //
+                0.0, s[1], 0.0, 0.0, //
+                0.0, 0.0, s[2], 0.0, //
+                t[0], t[1], t[2], 1.0,
+            ],
+        }
+    }
+
+    fn corners(box3: &Box3) -> ([f32; 3], [f32; 3]) {
+        (to_array(&box3.min), to_array(&box3.max))
+    }
+
+    #[test]
+    fn union_contains_both_boxes() {
+        let mut box3 = Box3::new(from_array([0.0, 0.0, 0.0]), from_array([1.0, 1.0, 1.0]));
+
+        box3.union(&Box3::new(
+            from_array([-2.0, 0.5, 0.5]),
+            from_array([0.5, 3.0, 0.5]),
+        ));
+
+        assert_eq!(corners(&box3), ([-2.0, 0.0, 0.0], [1.0, 3.0, 1.0]));
+    }
+
+    #[test]
+    fn union_with_empty_box() {
+        let unit = Box3::new(from_array([0.0, 0.0, 0.0]), from_array([1.0, 1.0, 1.0]));
+
+        let mut box3 = unit;
+        box3.union(&Box3::empty());
+        assert_eq!(corners(&box3), corners(&unit));
+
+        let mut box3 = Box3::empty();
+        box3.union(&unit);
+        assert_eq!(corners(&box3), corners(&unit));
+    }
+
+    #[test]
+    fn apply_matrix4_contains_transformed_corners() {
+        let box3 = Box3::new(from_array([-1.0, -1.0, -1.0]), from_array([1.0, 1.0, 1.0]));
+
+        // A negative scale swaps the corners.
+        let transformed = box3.apply_matrix4(&scale_translate([2.0, -1.0, 1.0], [5.0, 0.0, -3.0]));
+
+        assert_eq!(corners(&transformed), ([3.0, -1.0, -4.0], [7.0, 1.0, -2.0]));
+    }
+
+    #[test]
+    fn apply_matrix4_bounds_rotated_box() {
+        let box3 = Box3::new(from_array([0.0, 0.0, 0.0]), from_array([1.0, 1.0, 0.0]));
+        let (sin, cos) = std::f32::consts::FRAC_PI_4.sin_cos();
+
+        // A rotation of 45 degrees about the Z axis.
+        let rotation = Matrix4 {
+            elements: [
+                cos, sin, 0.0, 0.0, //
+                -sin, cos, 0.0, 0.0, //
+                0.0, 0.0, 1.0, 0.0, //
+                0.0, 0.0, 0.0, 1.0,
+            ],
+        };
+        let (min, max) = corners(&box3.apply_matrix4(&rotation));
+        let diagonal = std::f32::consts::SQRT_2;
+
+        assert!((min[0] + diagonal / 2.0).abs() < 1e-6);
+        assert!((max[0] - diagonal / 2.0).abs() < 1e-6);
+        assert!(min[1].abs() < 1e-6);
+        assert!((max[1] - diagonal).abs() < 1e-6);
+    }
+
+    #[test]
+    fn apply_matrix4_keeps_empty_box_empty() {
+        let transformed = Box3::empty().apply_matrix4(&scale_translate([1.0; 3], [1.0, 2.0, 3.0]));
+
+        assert!(transformed.is_empty());
+    }
+}
@@ -0,0 +1,54 @@
+//! Conversions of [`Color`] values between the sRGB and linear color spaces.
+//!
+//! `Color` values given to this crate, e.g. the colors of materials and
+//! lights, are sRGB encoded, as picked in color pickers or written in CSS.
+//! Shaders compute lighting with linear values, so renderers convert colors
+//! with [`srgb_to_linear`] before uploading them.
+
+use poli_math::Color;
+
+/// Returns the linear value of the specified sRGB-encoded color channel.
+fn srgb_channel_to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Returns the sRGB encoding of the specified linear color channel.
+fn linear_channel_to_srgb(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Converts the specified sRGB-encoded color to the linear color space, e.g.
+/// to pass it to a custom [material](crate::Material)'s shader.
+pub fn srgb_to_linear(color: &Color) -> Color {
+    Color {
+        r: srgb_channel_to_linear(color.r),
+        g: srgb_channel_to_linear(color.g),
+        b: srgb_channel_to_linear(color.b),
+    }
+}
+
+/// Converts the specified linear color to the sRGB color space, e.g. to use
+/// a color computed in linear space as the input of a material.
+pub fn linear_to_srgb(color: &Color) -> Color {
+    Color {
+        r: linear_channel_to_srgb(color.r),
+        g: linear_channel_to_srgb(color.g),
+        b: linear_channel_to_srgb(color.b),
+    }
+}
+
+/// Returns the linear components of the specified sRGB-encoded color, as laid
+/// out in uniform buffers.
+pub(crate) fn to_linear(color: &Color) -> [f32; 3] {
+    let Color { r, g, b } = srgb_to_linear(color);
+
+    [r as f32, g as f32, b as f32]
+}
@@ -0,0 +1,144 @@
+use poli_math::{Matrix4, Vector3};
+
+use super::{
+    vector::{from_array, to_array},
+    Box3, Plane, Sphere,
+};
+use crate::core::Camera;
+
+/// Volume enclosed by 6 planes, usually the view volume of a
+/// [camera](Camera). Used for culling objects that are out of view.
+#[derive(Clone, Copy)]
+pub struct Frustum {
+    /// The left, right, bottom, top, near, and far planes in that order, with
+    /// normals that point into the frustum.
+    pub planes: [Plane; 6],
+}
+
+impl Frustum {
+    /// Extracts the frustum from the specified projection matrix, or from the
+    /// product of a projection matrix and a view matrix to get the frustum in
+    /// world space.
+    ///
+    /// The clip-space depth range is `0.0..=1.0`, as in WebGPU and the
+    /// projection matrices of [cameras](Camera).
+    pub fn from_matrix(matrix: &Matrix4) -> Self {
+        let e = &matrix.elements;
+        let row = |i: usize| [e[i], e[i + 4], e[i + 8], e[i + 12]];
+        let (r0, r1, r2, r3) = (row(0), row(1), row(2), row(3));
+        let add = |u: [f32; 4], v: [f32; 4]| [0, 1, 2, 3].map(|i| u[i] + v[i]);
+        let sub = |u: [f32; 4], v: [f32; 4]| [0, 1, 2, 3].map(|i| u[i] - v[i]);
+
+        Self {
+            planes: [
+                Plane::from_coefficients(add(r3, r0)),
+                Plane::from_coefficients(sub(r3, r0)),
+                Plane::from_coefficients(add(r3, r1)),
+                Plane::from_coefficients(sub(r3, r1)),
+                Plane::from_coefficients(r2),
+                Plane::from_coefficients(sub(r3, r2)),
+            ],
+        }
+    }
+
+    /// Returns the frustum of the specified camera in world space.
+    pub fn from_camera(camera: &Camera) -> Self {
+        Self::from_matrix(&(camera.projection_matrix * camera.view_matrix.as_ref()))
+    }
+
+    /// Returns whether the specified point is inside the frustum or on its
+    /// surface.
+    pub fn contains_point(&self, point: &Vector3) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.distance_to_point(point) >= 0.0)
+    }
+
+    /// Returns whether the specified sphere is at least partially inside the
+    /// frustum. May return `true` for some spheres that are just outside the
+    /// corners of the frustum.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        !sphere.is_empty()
+            && self
+                .planes
+                .iter()
+                .all(|plane| plane.distance_to_point(&sphere.center) >= -sphere.radius)
+    }
+
+    /// Returns whether the specified box is at least partially inside the
+    /// frustum. May return `true` for some boxes that are just outside the
+    /// corners of the frustum.
+    pub fn intersects_box(&self, box3: &Box3) -> bool {
+        if box3.is_empty() {
+            return false;
+        }
+
+        let (min, max) = (to_array(&box3.min), to_array(&box3.max));
+
+        self.planes.iter().all(|plane| {
+            // The corner that is furthest along the normal.
+            let normal = to_array(&plane.normal);
+            let corner = [0, 1, 2].map(|i| if normal[i] >= 0.0 { max[i] } else { min[i] });
+
+            plane.distance_to_point(&from_array(corner)) >= 0.0
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the frustum of an orthographic projection that maps the box from
+    /// `(-1, -1, -1)` to `(1, 1, 1)` to clip space.
+    fn unit_frustum() -> Frustum {
+        Frustum::from_matrix(&Matrix4 {
+            elements: [
+                1.0, 0.0, 0.0, 0.0, //
+                0.0, 1.0, 0.0, 0.0, //
+                0.0, 0.0, -0.5, 0.0, //
+                0.0, 0.0, 0.5, 1.0,
+            ],
+        })
+    }
+
+    fn sphere(center: [f32; 3], radius: f32) -> Sphere {
+        Sphere::new(from_array(center), radius)
+    }
+
+    #[test]
+    fn intersects_sphere_inside() {
+        let frustum = unit_frustum();
+
+        assert!(frustum.intersects_sphere(&sphere([0.0, 0.0, 0.0], 0.5)));
+        assert!(frustum.intersects_sphere(&sphere([0.0, 0.0, 0.0], 10.0)));
+    }
+
+    #[test]
+    fn intersects_sphere_outside() {
+        let frustum = unit_frustum();
+
+        for center in [
+            [3.0, 0.0, 0.0],
+            [0.0, -3.0, 0.0],
+            [0.0, 0.0, 3.0],
+            [0.0, 0.0, -3.0],
+        ] {
+            assert!(!frustum.intersects_sphere(&sphere(center, 1.0)));
+        }
+    }
+
+    #[test]
+    fn intersects_sphere_straddling_plane() {
+        let frustum = unit_frustum();
+
+        assert!(frustum.intersects_sphere(&sphere([1.5, 0.0, 0.0], 1.0)));
+        assert!(frustum.intersects_sphere(&sphere([0.0, 0.0, -1.5], 1.0)));
+        assert!(!frustum.intersects_sphere(&sphere([1.5, 0.0, 0.0], 0.4)));
+    }
+
+    #[test]
+    fn intersects_sphere_rejects_empty_sphere() {
+        assert!(!unit_frustum().intersects_sphere(&Sphere::empty()));
+    }
+}
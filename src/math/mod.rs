@@ -0,0 +1,17 @@
+//! Contains geometric primitives used for spatial queries, such as bounding
+//! volumes and rays, and color space conversions.
+
+mod box3;
+pub(crate) mod color;
+mod frustum;
+mod plane;
+mod ray;
+mod sphere;
+pub(crate) mod vector;
+
+pub use box3::*;
+pub use color::{linear_to_srgb, srgb_to_linear};
+pub use frustum::*;
+pub use plane::*;
+pub use ray::*;
+pub use sphere::*;
@@ -0,0 +1,45 @@
+use poli_math::Vector3;
+
+use super::vector::{dot, from_array, length, to_array};
+
+/// Infinite plane, which is the set of points whose signed distance
+/// `normal · point + constant` is zero.
+#[derive(Clone, Copy)]
+pub struct Plane {
+    /// The unit normal of the plane, which points towards the side with
+    /// positive distances.
+    pub normal: Vector3,
+    /// The signed distance from the origin to the plane, along the opposite of
+    /// the normal.
+    pub constant: f32,
+}
+
+impl Plane {
+    /// Creates a plane with the specified unit normal and constant.
+    pub fn new(normal: Vector3, constant: f32) -> Self {
+        Self { normal, constant }
+    }
+
+    /// Creates a plane from the coefficients `(a, b, c, d)` of the equation
+    /// `ax + by + cz + d = 0`, which do not need to be normalized.
+    ///
+    /// If the coefficients `a`, `b` and `c` are all zero, e.g. for the far
+    /// plane of an infinite perspective frustum, the plane is degenerate: every
+    /// point is at the distance `d` to it.
+    pub(crate) fn from_coefficients(coefficients: [f32; 4]) -> Self {
+        let [a, b, c, d] = coefficients;
+        let length = length([a, b, c]);
+
+        if length > f32::EPSILON {
+            Self::new(from_array([a / length, b / length, c / length]), d / length)
+        } else {
+            Self::new(Vector3::default(), d)
+        }
+    }
+
+    /// Returns the signed distance from the plane to the specified point,
+    /// which is positive on the side the normal points to.
+    pub fn distance_to_point(&self, point: &Vector3) -> f32 {
+        dot(to_array(&self.normal), to_array(point)) + self.constant
+    }
+}
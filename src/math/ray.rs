@@ -0,0 +1,178 @@
+use poli_math::{Matrix4, Vector3};
+
+use super::{
+    vector::{cross, dot, from_array, normalize, sub, to_array, transform_point},
+    Sphere,
+};
+
+/// Half-line that starts at an origin and extends infinitely in a direction.
+#[derive(Clone, Copy)]
+pub struct Ray {
+    /// The point where the ray starts.
+    pub origin: Vector3,
+    /// The unit direction of the ray.
+    pub direction: Vector3,
+}
+
+impl Default for Ray {
+    /// Returns a ray that starts at the origin and points along the -Z axis.
+    fn default() -> Self {
+        Self {
+            origin: Vector3::default(),
+            direction: from_array([0.0, 0.0, -1.0]),
+        }
+    }
+}
+
+impl Ray {
+    /// Creates a ray with the specified origin and direction. The direction
+    /// is normalized.
+    pub fn new(origin: Vector3, direction: Vector3) -> Self {
+        Self {
+            origin,
+            direction: from_array(normalize(to_array(&direction)).unwrap_or([0.0, 0.0, -1.0])),
+        }
+    }
+
+    /// Returns the point at the specified distance along the ray.
+    pub fn at(&self, distance: f32) -> Vector3 {
+        let origin = to_array(&self.origin);
+        let direction = to_array(&self.direction);
+
+        from_array([0, 1, 2].map(|i| origin[i] + direction[i] * distance))
+    }
+
+    /// Returns the ray transformed by the specified matrix e.g. the inverse of
+    /// a world matrix. The direction is normalized again, so distances along
+    /// the transformed ray are in the transformed space.
+    pub fn apply_matrix4(&self, matrix: &Matrix4) -> Ray {
+        let origin = transform_point(matrix, to_array(&self.origin));
+        let target = transform_point(matrix, to_array(&self.at(1.0)));
+
+        Ray::new(from_array(origin), from_array(sub(target, origin)))
+    }
+
+    /// Returns whether the ray passes through the specified sphere.
+    pub fn intersects_sphere(&self, sphere: &Sphere) -> bool {
+        if sphere.is_empty() {
+            return false;
+        }
+
+        let to_center = sub(to_array(&sphere.center), to_array(&self.origin));
+        let radius_squared = sphere.radius * sphere.radius;
+
+        // The distance along the ray to the point closest to the center.
+        let closest = dot(to_center, to_array(&self.direction));
+
+        if closest < 0.0 {
+            return dot(to_center, to_center) <= radius_squared;
+        }
+
+        dot(to_center, to_center) - closest * closest <= radius_squared
+    }
+
+    /// Intersects the ray with the triangle `abc` using the
+    /// [Möller–Trumbore algorithm][mt].
+    ///
+    /// # Returns
+    ///
+    /// The distance along the ray to the intersection, and the barycentric
+    /// coordinates of the intersection with respect to `a`, `b` and `c`. `None`
+    /// if the ray misses the triangle, or if `cull_back_face` is `true` and
+    /// the ray hits the back of the triangle, which faces away when its
+    /// vertices appear clockwise.
+    ///
+    /// [mt]: https://en.wikipedia.org/wiki/M%C3%B6ller%E2%80%93Trumbore_intersection_algorithm
+    pub(crate) fn intersect_triangle(
+        &self,
+        a: [f32; 3],
+        b: [f32; 3],
+        c: [f32; 3],
+        cull_back_face: bool,
+    ) -> Option<(f32, [f32; 3])> {
+        let direction = to_array(&self.direction);
+        let edge1 = sub(b, a);
+        let edge2 = sub(c, a);
+        let p = cross(direction, edge2);
+        let determinant = dot(edge1, p);
+
+        // The determinant is zero if the ray is parallel to the triangle, and
+        // positive if the ray hits the front face.
+        if determinant == 0.0 || (cull_back_face && determinant < 0.0) {
+            return None;
+        }
+
+        let inverse_determinant = 1.0 / determinant;
+        let s = sub(to_array(&self.origin), a);
+        let u = dot(s, p) * inverse_determinant;
+
+        if !(0.0..=1.0).contains(&u) {
+            return None;
+        }
+
+        let q = cross(s, edge1);
+        let v = dot(direction, q) * inverse_determinant;
+
+        if v < 0.0 || u + v > 1.0 {
+            return None;
+        }
+
+        let distance = dot(edge2, q) * inverse_determinant;
+
+        (distance >= 0.0).then_some((distance, [1.0 - u - v, u, v]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A triangle in the plane `z = -2` that faces +Z, i.e. towards a ray
+    /// from the origin along -Z.
+    const TRIANGLE: [[f32; 3]; 3] = [[-1.0, -1.0, -2.0], [1.0, -1.0, -2.0], [0.0, 1.0, -2.0]];
+
+    fn intersect(
+        ray: &Ray,
+        [a, b, c]: [[f32; 3]; 3],
+        cull_back_face: bool,
+    ) -> Option<(f32, [f32; 3])> {
+        ray.intersect_triangle(a, b, c, cull_back_face)
+    }
+
+    #[test]
+    fn intersect_triangle_front_face() {
+        let (distance, barycentric) = intersect(&Ray::default(), TRIANGLE, true).unwrap();
+
+        assert!((distance - 2.0).abs() < 1e-6);
+        assert!((barycentric.iter().sum::<f32>() - 1.0).abs() < 1e-6);
+        assert!((barycentric[2] - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn intersect_triangle_back_face() {
+        let [a, b, c] = TRIANGLE;
+        let back = [a, c, b];
+
+        assert!(intersect(&Ray::default(), back, true).is_none());
+
+        let (distance, _) = intersect(&Ray::default(), back, false).unwrap();
+        assert!((distance - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn intersect_triangle_parallel_ray() {
+        let ray = Ray::new(from_array([-2.0, 0.0, -2.0]), from_array([1.0, 0.0, 0.0]));
+
+        assert!(intersect(&ray, TRIANGLE, false).is_none());
+    }
+
+    #[test]
+    fn intersect_triangle_misses() {
+        // Beside the triangle, and pointing away from it.
+        let beside = Ray::new(from_array([2.0, 0.0, 0.0]), from_array([0.0, 0.0, -1.0]));
+        let away = Ray::new(Vector3::default(), from_array([0.0, 0.0, 1.0]));
+
+        assert!(intersect(&beside, TRIANGLE, false).is_none());
+        assert!(intersect(&away, TRIANGLE, false).is_none());
+    }
+}
@@ -0,0 +1,102 @@
+use poli_math::{Matrix4, Vector3};
+
+use super::vector::{from_array, length, max_scale, sub, to_array, transform_point};
+
+/// Bounding sphere, which is the space within a distance of a center point.
+#[derive(Clone, Copy)]
+pub struct Sphere {
+    /// The center of the sphere.
+    pub center: Vector3,
+    /// The radius of the sphere. Negative for an empty sphere.
+    pub radius: f32,
+}
+
+impl Default for Sphere {
+    /// Returns an empty sphere.
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl Sphere {
+    /// Creates a sphere with the specified center and radius.
+    pub fn new(center: Vector3, radius: f32) -> Self {
+        Self { center, radius }
+    }
+
+    /// Returns an empty sphere, which contains no points.
+    pub fn empty() -> Self {
+        Self {
+            center: Vector3::default(),
+            radius: -1.0,
+        }
+    }
+
+    /// Returns whether the sphere contains no points.
+    pub fn is_empty(&self) -> bool {
+        self.radius < 0.0
+    }
+
+    /// Returns whether the specified point is inside the sphere or on its
+    /// surface.
+    pub fn contains_point(&self, point: &Vector3) -> bool {
+        length(sub(to_array(point), to_array(&self.center))) <= self.radius
+    }
+
+    /// Returns a sphere that contains this sphere after it has been
+    /// transformed by the specified matrix e.g. a world matrix. The radius is
+    /// scaled by the largest scale factor of the matrix.
+    pub fn apply_matrix4(&self, matrix: &Matrix4) -> Sphere {
+        if self.is_empty() {
+            return *self;
+        }
+
+        Sphere {
+            center: from_array(transform_point(matrix, to_array(&self.center))),
+            radius: self.radius * max_scale(matrix),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a matrix that scales by `s` and then translates by `t`.
+    fn scale_translate(s: [f32; 3], t: [f32; 3]) -> Matrix4 {
+        Matrix4 {
+            elements: [
+                s[0], 0.0, 0.0, 0.0, //
+                0.0, s[1], 0.0, 0.0, //
+                0.0, 0.0, s[2], 0.0, //
+                t[0], t[1], t[2], 1.0,
+            ],
+        }
+    }
+
+    #[test]
+    fn apply_matrix4_moves_center_and_scales_radius() {
+        let sphere = Sphere::new(from_array([1.0, 0.0, 0.0]), 2.0);
+
+        let transformed = sphere.apply_matrix4(&scale_translate([1.0, 3.0, -0.5], [0.0, 4.0, 0.0]));
+
+        assert_eq!(to_array(&transformed.center), [1.0, 4.0, 0.0]);
+        assert_eq!(transformed.radius, 6.0);
+    }
+
+    #[test]
+    fn apply_matrix4_keeps_empty_sphere_empty() {
+        let transformed = Sphere::empty().apply_matrix4(&scale_translate([2.0; 3], [1.0; 3]));
+
+        assert!(transformed.is_empty());
+    }
+
+    #[test]
+    fn contains_point_on_surface() {
+        let sphere = Sphere::new(from_array([0.0, 1.0, 0.0]), 1.0);
+
+        assert!(sphere.contains_point(&from_array([0.0, 2.0, 0.0])));
+        assert!(!sphere.contains_point(&from_array([0.0, 2.1, 0.0])));
+        assert!(!Sphere::empty().contains_point(&Vector3::default()));
+    }
+}
@@ -0,0 +1,75 @@
+//! Helpers for 3D vectors stored as arrays, used internally for arithmetic on
+//! [`Vector3`] values.
+
+use poli_math::{Matrix4, Vector3};
+
+/// Returns the components of the specified vector.
+pub(crate) fn to_array(v: &Vector3) -> [f32; 3] {
+    bytemuck::cast(*v)
+}
+
+/// Returns the vector with the specified components.
+pub(crate) fn from_array(v: [f32; 3]) -> Vector3 {
+    bytemuck::cast(v)
+}
+
+/// Returns the difference of the specified vectors.
+pub(crate) fn sub(u: [f32; 3], v: [f32; 3]) -> [f32; 3] {
+    [u[0] - v[0], u[1] - v[1], u[2] - v[2]]
+}
+
+/// Returns the specified vector multiplied by a scalar.
+pub(crate) fn scale(v: [f32; 3], s: f32) -> [f32; 3] {
+    [v[0] * s, v[1] * s, v[2] * s]
+}
+
+/// Returns the dot product of the specified vectors.
+pub(crate) fn dot(u: [f32; 3], v: [f32; 3]) -> f32 {
+    u[0] * v[0] + u[1] * v[1] + u[2] * v[2]
+}
+
+/// Returns the cross product of the specified vectors.
+pub(crate) fn cross(u: [f32; 3], v: [f32; 3]) -> [f32; 3] {
+    [
+        u[1] * v[2] - u[2] * v[1],
+        u[2] * v[0] - u[0] * v[2],
+        u[0] * v[1] - u[1] * v[0],
+    ]
+}
+
+/// Returns the length of the specified vector.
+pub(crate) fn length(v: [f32; 3]) -> f32 {
+    dot(v, v).sqrt()
+}
+
+/// Returns the specified vector scaled to a length of `1.0`, or `None` if its
+/// length is (close to) zero.
+pub(crate) fn normalize(v: [f32; 3]) -> Option<[f32; 3]> {
+    let length = length(v);
+
+    (length > f32::EPSILON).then(|| scale(v, 1.0 / length))
+}
+
+/// Transforms the specified point by the specified matrix, including the
+/// translation and the perspective divide.
+pub(crate) fn transform_point(m: &Matrix4, p: [f32; 3]) -> [f32; 3] {
+    let e = &m.elements;
+    let w = e[3] * p[0] + e[7] * p[1] + e[11] * p[2] + e[15];
+    let w = if w != 0.0 { 1.0 / w } else { 1.0 };
+
+    [
+        (e[0] * p[0] + e[4] * p[1] + e[8] * p[2] + e[12]) * w,
+        (e[1] * p[0] + e[5] * p[1] + e[9] * p[2] + e[13]) * w,
+        (e[2] * p[0] + e[6] * p[1] + e[10] * p[2] + e[14]) * w,
+    ]
+}
+
+/// Returns the largest scale factor of the specified matrix along its axes.
+pub(crate) fn max_scale(m: &Matrix4) -> f32 {
+    let e = &m.elements;
+
+    [0, 4, 8]
+        .map(|i| length([e[i], e[i + 1], e[i + 2]]))
+        .into_iter()
+        .fold(0.0, f32::max)
+}
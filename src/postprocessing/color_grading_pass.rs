@@ -0,0 +1,85 @@
+use std::cell::RefCell;
+
+use poli_math::Color;
+
+use super::Pass;
+use crate::math::color::to_linear;
+
+/// Pass that adjusts the colors of the image, e.g. to give a scene a warmer or
+/// more washed-out look.
+///
+/// The adjustments are applied in order: the tint, the brightness, the
+/// contrast, and then the saturation. The resulting colors are clamped to the
+/// `[0, 1]` range.
+pub struct ColorGradingPass {
+    /// Whether the pass is run. Default is `true`.
+    pub enabled: RefCell<bool>,
+    /// The color that multiplies the colors of the image. Default is white,
+    /// which leaves them unchanged.
+    pub tint: RefCell<Color>,
+    /// The amount added to all color channels, from `-1.0` (black) to `1.0`
+    /// (white). Default is `0.0`.
+    pub brightness: RefCell<f32>,
+    /// How much colors are pushed away from middle gray. `0.0` makes the image
+    /// uniformly gray, and values above `1.0` increase the contrast. Default
+    /// is `1.0`.
+    pub contrast: RefCell<f32>,
+    /// How much colors are pushed away from their luminance. `0.0` makes the
+    /// image grayscale, and values above `1.0` make colors more vivid. Default
+    /// is `1.0`.
+    pub saturation: RefCell<f32>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ColorGradingPassUniform {
+    tint: [f32; 3],
+    brightness: f32,
+    contrast: f32,
+    saturation: f32,
+    _padding: [f32; 2],
+}
+
+impl ColorGradingPass {
+    /// Creates a new color grading pass that leaves the colors unchanged.
+    pub fn new() -> Self {
+        Self {
+            enabled: RefCell::new(true),
+            tint: RefCell::new(Color {
+                r: 1.0,
+                g: 1.0,
+                b: 1.0,
+            }),
+            brightness: RefCell::new(0.0),
+            contrast: RefCell::new(1.0),
+            saturation: RefCell::new(1.0),
+        }
+    }
+}
+
+impl Default for ColorGradingPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pass for ColorGradingPass {
+    fn fragment_shader(&self) -> &'static str {
+        include_str!("shaders/color_grading_pass.wgsl")
+    }
+
+    fn uniform(&self) -> Vec<u8> {
+        bytemuck::bytes_of(&ColorGradingPassUniform {
+            tint: to_linear(&self.tint.borrow()),
+            brightness: *self.brightness.borrow(),
+            contrast: *self.contrast.borrow(),
+            saturation: *self.saturation.borrow(),
+            _padding: [0.0; 2],
+        })
+        .to_vec()
+    }
+
+    fn enabled(&self) -> bool {
+        *self.enabled.borrow()
+    }
+}
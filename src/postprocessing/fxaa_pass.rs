@@ -0,0 +1,39 @@
+use std::cell::RefCell;
+
+use super::Pass;
+
+/// Pass that smooths jagged edges with fast approximate anti-aliasing (FXAA).
+///
+/// FXAA blurs pixels along the edges it finds in the luminance of the image,
+/// which is much cheaper than [multisampling](crate::ForwardRendererOptions::sample_count),
+/// but also softens fine details such as text. It should run after the passes
+/// that change colors, so that it sees the final edges.
+pub struct FxaaPass {
+    /// Whether the pass is run. Default is `true`.
+    pub enabled: RefCell<bool>,
+}
+
+impl FxaaPass {
+    /// Creates a new FXAA pass.
+    pub fn new() -> Self {
+        Self {
+            enabled: RefCell::new(true),
+        }
+    }
+}
+
+impl Default for FxaaPass {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pass for FxaaPass {
+    fn fragment_shader(&self) -> &'static str {
+        include_str!("shaders/fxaa_pass.wgsl")
+    }
+
+    fn enabled(&self) -> bool {
+        *self.enabled.borrow()
+    }
+}
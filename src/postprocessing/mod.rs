@@ -0,0 +1,12 @@
+//! Contains post-processing passes, which apply screen-space effects to
+//! rendered images.
+
+mod color_grading_pass;
+mod fxaa_pass;
+mod pass;
+mod vignette_pass;
+
+pub use color_grading_pass::*;
+pub use fxaa_pass::*;
+pub use pass::*;
+pub use vignette_pass::*;
@@ -0,0 +1,50 @@
+use std::any::TypeId;
+
+/// A full-screen effect that a [`PostProcessor`](crate::PostProcessor) applies
+/// to the rendered image, such as anti-aliasing or a vignette. Should be
+/// implemented on all pass parameter structs.
+///
+/// A pass provides the fragment stage of a shader that is run once for every
+/// pixel of the image. The source is appended to the post-processor's own
+/// shader code, which declares:
+///
+/// * The `input_texture` in `@group(0) @binding(0)`, which holds the image
+///   produced by the previous pass, and its linear, clamp-to-edge
+///   `input_sampler` in `@group(0) @binding(1)`.
+/// * The renderer's `depth_texture` in `@group(0) @binding(2)`, and the
+///   `scene_depth` helper function, which returns the depth of the scene at
+///   the specified `uv`, from `0.0` at the near plane to `1.0` at the far
+///   plane of the camera. Pixels where nothing was drawn have a depth of
+///   `1.0`. On the OpenGL backend, the depth of multisampled renderers
+///   cannot be read and is always `1.0`.
+/// * The `VertexOutput` struct with the `uv` of the pixel, from `(0, 0)` at
+///   the top-left corner to `(1, 1)` at the bottom-right corner of the image.
+pub trait Pass: 'static {
+    /// Returns the WGSL source of the pass's fragment stage, which must define
+    /// a `fragmentMain` entry point that takes a `VertexOutput` and returns
+    /// the color at `@location(0)`. The pass's uniform buffer, if used, is
+    /// bound to `@group(1) @binding(0)`.
+    fn fragment_shader(&self) -> &'static str;
+
+    /// Returns the content of the pass's uniform buffer, laid out as the
+    /// uniform struct is declared in the [fragment shader](Self::fragment_shader).
+    /// The post-processor writes it to the GPU whenever it changes. Default is
+    /// no uniform.
+    fn uniform(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    /// Returns whether the pass is run. Disabled passes are skipped, so that
+    /// effects can be toggled without rebuilding the list of passes. Default
+    /// is `true`.
+    fn enabled(&self) -> bool {
+        true
+    }
+
+    /// Returns the identifier of the pass's type. Post-processors build one
+    /// render pipeline per pass type and share it between all passes of that
+    /// type.
+    fn pass_type_id(&self) -> TypeId {
+        TypeId::of::<Self>()
+    }
+}
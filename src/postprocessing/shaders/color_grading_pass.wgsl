@@ -0,0 +1,21 @@
+struct ColorGradingPass {
+  tint: vec3f,
+  brightness: f32,
+  contrast: f32,
+  saturation: f32,
+}
+
+@group(1) @binding(0) var<uniform> grading: ColorGradingPass;
+
+@fragment
+fn fragmentMain(input: VertexOutput) -> @location(0) vec4f {
+  let color = textureSample(input_texture, input_sampler, input.uv);
+
+  var rgb = color.rgb * grading.tint + grading.brightness;
+  rgb = (rgb - 0.5) * grading.contrast + 0.5;
+
+  let luminance = dot(rgb, vec3f(0.2126, 0.7152, 0.0722));
+  rgb = mix(vec3f(luminance), rgb, grading.saturation);
+
+  return vec4f(saturate(rgb), color.a);
+}
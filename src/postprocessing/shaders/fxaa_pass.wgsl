@@ -0,0 +1,55 @@
+// Based on the FXAA algorithm by Timothy Lottes: the direction of the edge
+// through each pixel is estimated from the luminance of its corners, and the
+// pixel is blurred along that direction.
+
+const FXAA_REDUCE_MIN = 1.0 / 128.0;
+const FXAA_REDUCE_MUL = 1.0 / 8.0;
+const FXAA_SPAN_MAX = 8.0;
+
+fn luminance(color: vec3f) -> f32 {
+  return dot(color, vec3f(0.299, 0.587, 0.114));
+}
+
+@fragment
+fn fragmentMain(input: VertexOutput) -> @location(0) vec4f {
+  let texel_size = 1 / vec2f(textureDimensions(input_texture));
+
+  let color = textureSample(input_texture, input_sampler, input.uv);
+  let luminance_nw = luminance(textureSample(input_texture, input_sampler, input.uv + vec2f(-1, -1) * texel_size).rgb);
+  let luminance_ne = luminance(textureSample(input_texture, input_sampler, input.uv + vec2f(1, -1) * texel_size).rgb);
+  let luminance_sw = luminance(textureSample(input_texture, input_sampler, input.uv + vec2f(-1, 1) * texel_size).rgb);
+  let luminance_se = luminance(textureSample(input_texture, input_sampler, input.uv + vec2f(1, 1) * texel_size).rgb);
+  let luminance_m = luminance(color.rgb);
+
+  let luminance_min = min(luminance_m, min(min(luminance_nw, luminance_ne), min(luminance_sw, luminance_se)));
+  let luminance_max = max(luminance_m, max(max(luminance_nw, luminance_ne), max(luminance_sw, luminance_se)));
+
+  var direction = vec2f(
+    (luminance_sw + luminance_se) - (luminance_nw + luminance_ne),
+    (luminance_nw + luminance_sw) - (luminance_ne + luminance_se),
+  );
+
+  let direction_reduce = max(
+    (luminance_nw + luminance_ne + luminance_sw + luminance_se) * 0.25 * FXAA_REDUCE_MUL,
+    FXAA_REDUCE_MIN,
+  );
+  let inverse_direction_min = 1 / (min(abs(direction.x), abs(direction.y)) + direction_reduce);
+
+  direction = clamp(direction * inverse_direction_min, vec2f(-FXAA_SPAN_MAX), vec2f(FXAA_SPAN_MAX)) * texel_size;
+
+  let color_a = 0.5 * (
+    textureSample(input_texture, input_sampler, input.uv + direction * (1.0 / 3.0 - 0.5)).rgb +
+    textureSample(input_texture, input_sampler, input.uv + direction * (2.0 / 3.0 - 0.5)).rgb
+  );
+  let color_b = color_a * 0.5 + 0.25 * (
+    textureSample(input_texture, input_sampler, input.uv - direction * 0.5).rgb +
+    textureSample(input_texture, input_sampler, input.uv + direction * 0.5).rgb
+  );
+  let luminance_b = luminance(color_b);
+
+  if luminance_b < luminance_min || luminance_b > luminance_max {
+    return vec4f(color_a, color.a);
+  }
+
+  return vec4f(color_b, color.a);
+}
@@ -0,0 +1,15 @@
+struct VignettePass {
+  offset: f32,
+  darkness: f32,
+}
+
+@group(1) @binding(0) var<uniform> vignette: VignettePass;
+
+@fragment
+fn fragmentMain(input: VertexOutput) -> @location(0) vec4f {
+  let color = textureSample(input_texture, input_sampler, input.uv);
+  let position = (input.uv - 0.5) * vignette.offset;
+  let amount = saturate(dot(position, position));
+
+  return vec4f(mix(color.rgb, color.rgb * (1 - vignette.darkness), amount), color.a);
+}
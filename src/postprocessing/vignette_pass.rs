@@ -0,0 +1,62 @@
+use std::cell::RefCell;
+
+use super::Pass;
+
+/// Pass that darkens the image towards its corners, which draws attention to
+/// its center.
+pub struct VignettePass {
+    /// Whether the pass is run. Default is `true`.
+    pub enabled: RefCell<bool>,
+    /// How far the darkening reaches towards the center of the image. The
+    /// darkening grows with the squared distance from the center, multiplied
+    /// by the square of the offset, so at `1.0` the corners are darkened
+    /// halfway. Larger values darken more of the image. Default is `1.0`.
+    pub offset: RefCell<f32>,
+    /// How dark the darkened parts of the image are, from `0.0` (not darkened)
+    /// to `1.0` (black). Default is `1.0`.
+    pub darkness: RefCell<f32>,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct VignettePassUniform {
+    offset: f32,
+    darkness: f32,
+    _padding: [f32; 2],
+}
+
+impl VignettePass {
+    /// Creates a new vignette pass with the specified offset and darkness.
+    pub fn new(offset: f32, darkness: f32) -> Self {
+        Self {
+            enabled: RefCell::new(true),
+            offset: RefCell::new(offset),
+            darkness: RefCell::new(darkness),
+        }
+    }
+}
+
+impl Default for VignettePass {
+    fn default() -> Self {
+        Self::new(1.0, 1.0)
+    }
+}
+
+impl Pass for VignettePass {
+    fn fragment_shader(&self) -> &'static str {
+        include_str!("shaders/vignette_pass.wgsl")
+    }
+
+    fn uniform(&self) -> Vec<u8> {
+        bytemuck::bytes_of(&VignettePassUniform {
+            offset: *self.offset.borrow(),
+            darkness: *self.darkness.borrow(),
+            _padding: [0.0; 2],
+        })
+        .to_vec()
+    }
+
+    fn enabled(&self) -> bool {
+        *self.enabled.borrow()
+    }
+}
@@ -0,0 +1,38 @@
+use std::rc::Rc;
+
+use poli_math::Color;
+
+use crate::textures::{CubeTexture, Texture};
+
+/// What a renderer draws behind the scene. See
+/// [`ForwardRenderer::background`](super::ForwardRenderer::background).
+///
+/// Like the rest of the scene, the background is exposed and tone mapped.
+/// Textures and gradients only depend on the direction in which the camera
+/// looks, so they appear infinitely far away.
+#[derive(Default)]
+pub enum Background {
+    /// The renderer's [clear color](super::ForwardRenderer::clear_color) and
+    /// [clear alpha](super::ForwardRenderer::clear_alpha).
+    #[default]
+    ClearColor,
+    /// A solid color in sRGB, which is opaque.
+    Color(Color),
+    /// A 2D texture with an equirectangular projection, e.g. a panorama, that
+    /// surrounds the scene. The horizontal axis of the texture goes around the
+    /// Y axis, and the vertical axis goes from straight down at the bottom to
+    /// straight up at the top, which with the default
+    /// [`flip_y`](Texture::flip_y) is the top row of the image. The center of
+    /// the texture is towards -Z.
+    Equirectangular(Rc<Texture>),
+    /// A cube texture that surrounds the scene.
+    CubeMap(Rc<CubeTexture>),
+    /// A vertical gradient between two colors in sRGB, from `bottom` when
+    /// looking straight down to `top` when looking straight up.
+    Gradient {
+        /// The color straight up.
+        top: Color,
+        /// The color straight down.
+        bottom: Color,
+    },
+}
@@ -0,0 +1,194 @@
+use std::ops::Range;
+
+/// GPU buffer that is divided into allocations of various sizes, such as the
+/// vertex data of several geometries.
+///
+/// The buffer starts small and grows on demand. Growing replaces the buffer
+/// with a larger one and copies the content over, so allocations keep their
+/// offsets but bind groups that refer to the old [buffer](Self::buffer) must be
+/// recreated.
+pub(crate) struct BufferArena {
+    label: &'static str,
+    usage: wgpu::BufferUsages,
+    /// The alignment of the offsets and sizes of allocations.
+    alignment: u64,
+    buffer: wgpu::Buffer,
+    /// The unallocated ranges of the buffer, sorted by offset and never
+    /// adjacent to each other.
+    free_ranges: Vec<Range<u64>>,
+}
+
+/// Range of a [`BufferArena`] that has been allocated.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub(crate) struct Allocation {
+    /// The offset of the allocation in the buffer in bytes.
+    pub(crate) offset: u64,
+    /// The size of the allocation in bytes, which may be larger than the
+    /// requested size because of alignment.
+    pub(crate) size: u64,
+}
+
+impl Allocation {
+    /// Returns the range of the allocation in the buffer.
+    pub(crate) fn range(&self) -> Range<u64> {
+        self.offset..self.offset + self.size
+    }
+}
+
+impl BufferArena {
+    /// The size of a new arena's buffer in bytes.
+    const INITIAL_CAPACITY: u64 = 4096;
+
+    /// Creates an empty arena whose buffer has the specified usage, in
+    /// addition to the copy usages required to write and grow the buffer.
+    /// Allocations are aligned to `alignment` bytes, which must be a power of
+    /// two and at least [`COPY_BUFFER_ALIGNMENT`](wgpu::COPY_BUFFER_ALIGNMENT).
+    pub(crate) fn new(
+        device: &wgpu::Device,
+        label: &'static str,
+        usage: wgpu::BufferUsages,
+        alignment: u64,
+    ) -> Self {
+        let usage = usage | wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::COPY_SRC;
+        let capacity = Self::INITIAL_CAPACITY.max(alignment);
+
+        Self {
+            label,
+            usage,
+            alignment,
+            buffer: Self::create_buffer(device, label, usage, capacity),
+            free_ranges: vec![Range {
+                start: 0,
+                end: capacity,
+            }],
+        }
+    }
+
+    fn create_buffer(
+        device: &wgpu::Device,
+        label: &'static str,
+        usage: wgpu::BufferUsages,
+        size: u64,
+    ) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(label),
+            size,
+            usage,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Returns the current buffer of the arena.
+    pub(crate) fn buffer(&self) -> &wgpu::Buffer {
+        &self.buffer
+    }
+
+    /// Returns the slice of the buffer that belongs to the specified
+    /// allocation.
+    pub(crate) fn slice(&self, allocation: &Allocation) -> wgpu::BufferSlice<'_> {
+        self.buffer.slice(allocation.range())
+    }
+
+    /// Allocates a range of at least `size` bytes, growing the buffer if no
+    /// free range is large enough. Allocations are never empty.
+    ///
+    /// # Returns
+    ///
+    /// The allocation, and whether the buffer was replaced by a larger one.
+    pub(crate) fn allocate(
+        &mut self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        size: u64,
+    ) -> (Allocation, bool) {
+        let size = size.max(1).next_multiple_of(self.alignment);
+
+        if let Some(allocation) = self.allocate_from_free_ranges(size) {
+            return (allocation, false);
+        }
+
+        self.grow(device, queue, size);
+
+        let allocation = self
+            .allocate_from_free_ranges(size)
+            .expect("the grown buffer has room for the allocation");
+
+        (allocation, true)
+    }
+
+    /// Takes an aligned range of the specified size from the first free range
+    /// that is large enough.
+    fn allocate_from_free_ranges(&mut self, size: u64) -> Option<Allocation> {
+        // Free ranges always start and end at aligned offsets.
+        let index = self
+            .free_ranges
+            .iter()
+            .position(|range| range.end - range.start >= size)?;
+
+        let range = &mut self.free_ranges[index];
+        let allocation = Allocation {
+            offset: range.start,
+            size,
+        };
+
+        range.start += size;
+
+        if range.is_empty() {
+            self.free_ranges.remove(index);
+        }
+
+        Some(allocation)
+    }
+
+    /// Replaces the buffer with one that is large enough for an additional
+    /// allocation of the specified size, and copies the content of the old
+    /// buffer into it.
+    fn grow(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, size: u64) {
+        let old_capacity = self.buffer.size();
+        let trailing_free = match self.free_ranges.last() {
+            Some(range) if range.end == old_capacity => range.end - range.start,
+            _ => 0,
+        };
+        let capacity = (old_capacity + size - trailing_free)
+            .next_power_of_two()
+            .max(2 * old_capacity);
+
+        let buffer = Self::create_buffer(device, self.label, self.usage, capacity);
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+        encoder.copy_buffer_to_buffer(&self.buffer, 0, &buffer, 0, old_capacity);
+
+        // Writes to the old buffer that are still pending happen before the
+        // copy, because they are flushed at the beginning of the submission.
+        queue.submit(std::iter::once(encoder.finish()));
+
+        self.buffer = buffer;
+        self.free(Allocation {
+            offset: old_capacity,
+            size: capacity - old_capacity,
+        });
+    }
+
+    /// Returns the range of the specified allocation to the arena, so that it
+    /// can be reused by later allocations.
+    pub(crate) fn free(&mut self, allocation: Allocation) {
+        let range = allocation.range();
+        let index = self
+            .free_ranges
+            .partition_point(|free_range| free_range.start < range.start);
+
+        let merges_previous = index > 0 && self.free_ranges[index - 1].end == range.start;
+        let merges_next =
+            index < self.free_ranges.len() && self.free_ranges[index].start == range.end;
+
+        match (merges_previous, merges_next) {
+            (true, true) => {
+                self.free_ranges[index - 1].end = self.free_ranges[index].end;
+                self.free_ranges.remove(index);
+            }
+            (true, false) => self.free_ranges[index - 1].end = range.end,
+            (false, true) => self.free_ranges[index].start = range.start,
+            (false, false) => self.free_ranges.insert(index, range),
+        }
+    }
+}
@@ -1,10 +1,33 @@
-use std::{mem::size_of, num::NonZero, rc::Rc};
+use std::{
+    any::TypeId,
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    mem::size_of,
+    num::NonZero,
+    ops::Range,
+    rc::{Rc, Weak},
+};
 
-use poli_math::{Color, Matrix3, Matrix4, Vector3};
-use wgpu::{include_wgsl, VertexAttribute};
+use poli_math::{Color, Matrix4, Vector3};
+use wgpu::VertexAttribute;
 
+use super::{
+    buffer_arena::{Allocation, BufferArena},
+    full_screen_pass,
+    resource_cache::ResourceCache,
+    Background, ToneMapping,
+};
 use crate::{
-    core::{Camera, Object3d, Object3dKind},
+    core::{Camera, CameraKind, Instance, Light, LightKind, Object3d, Object3dKind},
+    geometries::BufferGeometry,
+    materials::{BlendMode, Material},
+    math::{
+        color::to_linear,
+        srgb_to_linear,
+        vector::{dot, length, sub, to_array, transform_point},
+        Frustum, Sphere,
+    },
+    textures::{CubeTexture, Texture},
     wgpual::{Gpu, GpuOptions},
     PowerPreference,
 };
@@ -12,36 +35,441 @@ use crate::{
 /// Forward renderer.
 ///
 /// The forward renderer renders each object in a separate draw call.
+///
+/// # Color management
+///
+/// All [`Color`] values, e.g. the [clear color](Self::clear_color) and the
+/// colors of materials and lights, are sRGB encoded, as are
+/// [textures](Texture::srgb) with colors. The renderer converts them to linear
+/// values, in which lighting is computed, and draws the scene into a
+/// high dynamic range (HDR) texture whose colors can exceed `1.0`. The HDR
+/// image is then multiplied by the [exposure](Self::exposure),
+/// [tone mapped](Self::tone_mapping) into the displayable range, and sRGB
+/// encoded into the frame.
+///
+/// # Background
+///
+/// Behind the scene, the renderer draws its [background](Self::background),
+/// which is the clear color by default. Textured and gradient backgrounds are
+/// drawn in a separate pass before the scene, as seen from the camera with its
+/// translation removed, so that they stay infinitely far away as the camera
+/// moves.
+///
+/// # Shadows
+///
+/// Before drawing the scene, the renderer draws the depth of the meshes that
+/// [cast shadows](Object3d::cast_shadow) into a shadow map per light that
+/// [casts shadows](Light::cast_shadow), as seen from the light. Meshes that
+/// [receive shadows](Object3d::receive_shadow) then compare their depth from
+/// the light with the map, filtering several samples to soften the edges of
+/// shadows. The shadow of a directional light can be split into cascades that
+/// cover the camera frustum, and are blended where they meet, and point lights
+/// draw their shadow into a cube map. See [`LightShadow`](crate::LightShadow)
+/// for the parameters of shadow maps.
 pub struct ForwardRenderer<'window> {
     /// Contains various GPU objects used by this renderer.
     pub gpu: Gpu<'window>,
-    /// The clear color to use for the clear operation.
+    /// The clear color to use for the clear operation, in sRGB. Like the
+    /// scene, it is exposed and tone mapped. Only used if the
+    /// [background](Self::background) is [`Background::ClearColor`].
     pub clear_color: Color,
-    /// The clear alpha to use for the clear operation.
+    /// The clear alpha to use for the clear operation. Only used if the
+    /// [background](Self::background) is [`Background::ClearColor`].
     pub clear_alpha: f64,
+    /// What is drawn behind the scene. Default is [`Background::ClearColor`].
+    pub background: Background,
+    /// How the HDR colors of the scene are mapped into the displayable range.
+    /// Default is [`ToneMapping::None`].
+    pub tone_mapping: ToneMapping,
+    /// The factor by which the HDR colors of the scene are multiplied before
+    /// they are tone mapped, like the exposure of a camera. Default is `1.0`.
+    pub exposure: f32,
 
-    position_buffer: wgpu::Buffer,
-    normal_buffer: wgpu::Buffer,
-    uv_buffer: wgpu::Buffer,
-    index_buffer: wgpu::Buffer,
+    /// The number of samples per pixel of the color and depth attachments.
+    sample_count: u32,
+    /// The depth attachment, which post-processors also bind for their passes
+    /// if it is [bindable](Self::depth_texture_bindable).
     depth_texture: wgpu::Texture,
-    model_matrix_buffer: wgpu::Buffer,
-    model_view_matrix_buffer: wgpu::Buffer,
+    /// The multisampled color attachment, which is resolved into the HDR
+    /// texture. `None` if multisampling is disabled.
+    multisampled_texture: Option<wgpu::Texture>,
+    /// The texture that the scene is drawn into, in linear HDR colors.
+    hdr_texture: wgpu::Texture,
+    /// The pass that tone maps the HDR texture into the frame.
+    output_pass: OutputPass,
+    /// The pass that draws textured and gradient backgrounds, which is
+    /// created the first time such a background is drawn.
+    background_pass: RefCell<Option<BackgroundPass>>,
     projection_matrix_buffer: wgpu::Buffer,
     view_matrix_buffer: wgpu::Buffer,
-    normal_matrix_buffer: wgpu::Buffer,
     camera_position_buffer: wgpu::Buffer,
     bind_group_layout: wgpu::BindGroupLayout,
+    lights_bind_group_layout: wgpu::BindGroupLayout,
+    lights: RefCell<LightsResources>,
+    /// The layout of the bind group of the shadow camera of the shadow pass.
+    shadow_bind_group_layout: wgpu::BindGroupLayout,
+    /// The render pipeline of the shadow pass, which is created the first
+    /// time a shadow is drawn.
+    shadow_pipeline: RefCell<Option<wgpu::RenderPipeline>>,
+    /// The render pipeline of the shadow pass of point lights, which is
+    /// created the first time a point light's shadow is drawn.
+    point_shadow_pipeline: RefCell<Option<wgpu::RenderPipeline>>,
+    /// Render pipelines, grouped by material type.
+    pipelines: RefCell<HashMap<TypeId, MaterialPipeline>>,
+    materials: RefCell<ResourceCache<dyn Material, MaterialResources>>,
+    textures: RefCell<ResourceCache<Texture, TextureResources>>,
+    cube_textures: RefCell<ResourceCache<CubeTexture, TextureResources>>,
+    geometries: RefCell<ResourceCache<BufferGeometry, GeometryResources>>,
+    /// The slot of each mesh in the object arena, which stays the same for as
+    /// long as the mesh exists.
+    objects: RefCell<ResourceCache<Object3d, Allocation>>,
+    /// Per-mesh uniforms of all meshes, laid out as the `Object` struct of the
+    /// shader.
+    object_arena: RefCell<BufferArena>,
+    /// The bind group of the object and camera uniforms, which is created
+    /// lazily and recreated whenever the object arena grows. Each mesh selects
+    /// its slot with a dynamic offset.
+    object_bind_group: RefCell<Option<wgpu::BindGroup>>,
+    /// Vertex data of all geometries.
+    vertex_arena: RefCell<BufferArena>,
+    /// Index data of all indexed geometries.
+    index_arena: RefCell<BufferArena>,
+    /// The per-instance data of each instanced mesh in the vertex arena.
+    instances: RefCell<ResourceCache<Object3d, InstanceResources>>,
+    /// A single instance with no transformation in the vertex arena, which is
+    /// bound for meshes that are not instanced.
+    default_instance: Allocation,
+    /// 1x1 white texture that replaces unset material textures.
+    default_texture: Rc<Texture>,
+    /// 1x1 white cube texture that is bound when the background is not a cube
+    /// map.
+    default_cube_texture: Rc<CubeTexture>,
+    info: RefCell<RenderInfo>,
+    /// The render pipeline of the picking pass, which is created the first
+    /// time an object is picked.
+    picking_pipeline: RefCell<Option<wgpu::RenderPipeline>>,
+    /// The render targets of the picking pass, which are created the first
+    /// time an object is picked and recreated when the size changes.
+    picking_targets: RefCell<Option<PickingTargets>>,
+}
+
+/// Statistics about the last frame rendered by a [`ForwardRenderer`].
+#[derive(Clone, Copy, Default, Debug)]
+pub struct RenderInfo {
+    /// The number of meshes, lines, and points that were drawn.
+    pub rendered: usize,
+    /// The number of visible meshes that were skipped because they were
+    /// outside of the camera's view.
+    pub culled: usize,
+}
+
+/// Mesh found by [`ForwardRenderer::pick`].
+pub struct PickResult {
+    /// The picked mesh.
+    pub object: Rc<Object3d>,
+    /// The index of the picked triangle, as in
+    /// [`Intersection::face_index`](crate::Intersection::face_index). `None`
+    /// if the GPU does not support
+    /// [`SHADER_PRIMITIVE_INDEX`](wgpu::Features::SHADER_PRIMITIVE_INDEX).
+    pub face_index: Option<usize>,
+}
+
+/// Resources of the pass that tone maps the HDR texture into the frame.
+struct OutputPass {
+    pipeline: wgpu::RenderPipeline,
+    input_bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    /// The bind group of the HDR texture, which is recreated when the size
+    /// changes.
+    input_bind_group: wgpu::BindGroup,
+    uniform_buffer: wgpu::Buffer,
+    uniform_bind_group: wgpu::BindGroup,
+}
+
+/// Uniform of the output pass, laid out as the `OutputSettings` struct of the
+/// shader.
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct OutputUniform {
+    exposure: f32,
+    tone_mapping: u32,
+    encode_srgb: u32,
+    _padding: u32,
+}
+
+/// Resources of the pass that draws the background.
+struct BackgroundPass {
     pipeline: wgpu::RenderPipeline,
+    bind_group_layout: wgpu::BindGroupLayout,
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    /// The equirectangular texture and cube texture of the bind group, which
+    /// is recreated when they change.
+    textures: (Weak<Texture>, Weak<CubeTexture>),
+}
+
+/// Uniform of the background pass, laid out as the `Background` struct of the
+/// shader.
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct BackgroundUniform {
+    inverse_view_projection_matrix: [f32; 16],
+    top_color: [f32; 3],
+    kind: u32,
+    bottom_color: [f32; 3],
+    _padding: u32,
+}
+
+// Values of the `kind` of [`BackgroundUniform`], as the constants of the
+// background shader.
+const EQUIRECTANGULAR_BACKGROUND: u32 = 0;
+const CUBE_MAP_BACKGROUND: u32 = 1;
+const GRADIENT_BACKGROUND: u32 = 2;
+
+/// Render targets of the picking pass, which are never multisampled because
+/// integer formats cannot be resolved.
+struct PickingTargets {
+    /// The pick ID of the mesh and the index of the triangle of each pixel.
+    color: wgpu::Texture,
+    depth: wgpu::Texture,
+}
+
+/// Pixels read back from the picking pass.
+struct PickIds {
+    /// The drawn meshes, indexed by pick ID minus one.
+    meshes: Vec<Rc<Object3d>>,
+    /// The pick ID and triangle index of each pixel, row by row.
+    pixels: Vec<[u32; 2]>,
+}
+
+/// GPU resources of the lights in the scene and of their shadows.
+struct LightsResources {
+    /// Storage buffer with the number of lights followed by the lights.
+    buffer: wgpu::Buffer,
+    /// The number of lights that fit into the buffer.
+    capacity: usize,
+    /// Storage buffer with the shadows, laid out as the `Shadow` struct of the
+    /// shader.
+    shadow_buffer: wgpu::Buffer,
+    /// The number and size of the layers of the shadow map.
+    shadow_maps: ShadowMapCapacity,
+    /// Depth texture array with the shadow map of each directional and spot
+    /// light shadow in a layer.
+    shadow_map: wgpu::Texture,
+    /// The number and size of the cube maps of the point shadow map.
+    point_shadow_maps: ShadowMapCapacity,
+    /// Depth cube map array with the shadow of each point light in a cube map,
+    /// which stores the linear distance from the light. A single cube map if
+    /// the GPU does not support cube map arrays.
+    point_shadow_map: wgpu::Texture,
+    /// Uniform buffer with the camera of each shadow pass, which the shadow
+    /// pass selects with a dynamic offset.
+    shadow_camera_buffer: wgpu::Buffer,
+    /// The distance between consecutive cameras in the shadow camera buffer,
+    /// which is aligned to the minimum uniform buffer offset alignment.
+    shadow_camera_stride: u64,
+    shadow_camera_bind_group: wgpu::BindGroup,
+    /// The bind group of the lights, the shadows, and the shadow maps.
+    bind_group: wgpu::BindGroup,
+}
+
+/// The number of shadow maps that fit into a shadow map texture of
+/// [`LightsResources`], and their width and height in texels.
+#[derive(Clone, Copy, PartialEq)]
+struct ShadowMapCapacity {
+    count: usize,
+    size: u32,
+}
+
+/// Per-mesh data as laid out in the `Object` struct of the shader.
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ObjectUniform {
+    model_matrix: Matrix4,
+    model_view_matrix: Matrix4,
+    /// See [`ForwardRenderer::normal_matrix`].
+    normal_matrix: [f32; 12],
+    /// The index of the mesh in the list of drawn meshes plus one, written by
+    /// the picking pass. Zero is the background.
+    pick_id: u32,
+    /// Whether shadows are cast onto the mesh.
+    receive_shadow: u32,
+    /// Half of the width and height of points in normalized device
+    /// coordinates, if the object is a point cloud.
+    point_size: [f32; 2],
+}
+
+/// Light data as laid out in the `Light` struct of the shader.
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct LightUniform {
+    /// The color of the light multiplied by its intensity.
+    color: [f32; 3],
+    kind: u32,
+    position: [f32; 3],
+    range: f32,
+    direction: [f32; 3],
+    decay: f32,
+    cos_angle: f32,
+    cos_penumbra: f32,
+    /// The index of the light's shadow in the shadow buffer, or `-1` if the
+    /// light does not cast shadows.
+    shadow: i32,
+    /// The number of cascades of the light's shadow, which are consecutive in
+    /// the shadow buffer.
+    shadow_count: u32,
+}
+
+/// The shadow of a light as laid out in the `Shadow` struct of the shader.
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ShadowUniform {
+    view_projection_matrix: Matrix4,
+    /// The layer of the shadow map.
+    layer: u32,
+    bias: f32,
+    /// The fraction of the width and height of the layer covered by the
+    /// shadow map.
+    scale: f32,
+    /// The size of a texel of the layer in UV coordinates.
+    texel_size: f32,
+    /// The distance along the camera's view direction up to which the shadow
+    /// map is used.
+    far: f32,
+    /// The distance along the camera's view direction from which the shadow
+    /// map is blended with the next cascade.
+    blend_start: f32,
+    /// The distance by which the point shadow map's distances are divided.
+    range: f32,
+    _padding: f32,
+}
+
+/// The view of a shadow pass as laid out in the `ShadowCamera` struct of the
+/// shader.
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct ShadowCameraUniform {
+    view_projection_matrix: Matrix4,
+    /// The position of a point light.
+    position: [f32; 3],
+    /// The distance by which a point light's distances are divided.
+    range: f32,
+}
+
+/// A view of a light from which its shadow casters are drawn into a layer of
+/// the shadow map.
+struct ShadowView {
+    /// The view-projection matrix of the view, or of each face of the cube
+    /// map of a point light.
+    view_projection_matrices: Vec<Matrix4>,
+    bias: f32,
+    map_size: u32,
+    /// The distance along the camera's view direction up to which the shadow
+    /// map is used, which is only finite for cascades.
+    far: f32,
+    /// The distance along the camera's view direction from which the shadow
+    /// map is blended with the next cascade.
+    blend_start: f32,
+    /// The position of a point light and the distance up to which its shadow
+    /// casters are drawn, or `None` if the shadow is not a cube map.
+    point: Option<([f32; 3], f32)>,
+}
+
+/// A render of shadow casters into a layer of one of the shadow maps.
+struct ShadowPass {
+    /// Whether the layer is in the point shadow map.
+    point: bool,
+    layer: u32,
+    map_size: u32,
+}
+
+/// How the vertices of a geometry are assembled into primitives.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Primitive {
+    /// Triangles of [meshes](crate::Mesh).
+    Triangles,
+    /// The edges of the triangles of meshes whose material is drawn as a
+    /// [wireframe](crate::MaterialSettings::wireframe).
+    Wireframe,
+    /// Separate [line segments](crate::LineSegments).
+    Lines,
+    /// A continuous [line](crate::Line).
+    LineStrip,
+    /// [Points](crate::Points), each drawn as a quad.
+    Points,
+}
+
+/// Render pipelines of a material type.
+struct MaterialPipeline {
+    /// The layout of the bind group of each material of the type.
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline_layout: wgpu::PipelineLayout,
+    module: wgpu::ShaderModule,
+    /// The render pipelines for each primitive and
+    /// [blending](ForwardRenderer::blending) used by materials of the type,
+    /// which are created on first use.
+    pipelines: HashMap<(Primitive, Option<BlendMode>), wgpu::RenderPipeline>,
+}
+
+/// GPU resources of a material.
+struct MaterialResources {
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    /// The uniform content that was last written to the uniform buffer.
+    uniform: Vec<u8>,
+    /// The textures in the bind group.
+    textures: Vec<Weak<Texture>>,
+}
+
+/// GPU resources of a geometry.
+struct GeometryResources {
+    /// The position data in the vertex arena.
+    position: Allocation,
+    /// The normal data in the vertex arena.
+    normal: Allocation,
+    /// The UV data in the vertex arena.
+    uv: Allocation,
+    /// The indices in the index arena, if the geometry is indexed.
+    indices: Option<Allocation>,
+    /// The indices of the edges of the triangles in the index arena and their
+    /// number, which are created the first time the geometry is drawn as a
+    /// wireframe.
+    edges: Option<(Allocation, u32)>,
+    /// The number of vertices, or of indices if the geometry is indexed.
+    count: u32,
+    /// The [version](BufferGeometry::version) of the geometry that was last
+    /// uploaded.
+    version: u64,
+}
+
+/// GPU resources of an instanced mesh.
+struct InstanceResources {
+    /// The per-instance data in the vertex arena.
+    allocation: Allocation,
+    /// The number of instances.
+    count: u32,
+}
+
+/// GPU resources of a texture.
+struct TextureResources {
+    view: wgpu::TextureView,
+    sampler: wgpu::Sampler,
 }
 
 /// Defines the [`ForwardRenderer`]'s behavior.
 pub struct ForwardRendererOptions {
     /// See [`GpuOptions#power_preference`].
     pub power_preference: PowerPreference,
+    /// See [`GpuOptions#force_fallback_adapter`].
+    pub force_fallback_adapter: bool,
     /// Controls the default clear alpha value. When set to `true`, the value is
     /// `0.0`. Otherwise it's `1.0`. Default is `false`.
     pub alpha: bool,
+    /// The number of samples per pixel for multisample anti-aliasing (MSAA).
+    /// `1` disables multisampling, and `4` is supported by all devices. Other
+    /// values depend on the device, and unsupported values are lowered to the
+    /// largest supported sample count below them. Default is `1`.
+    pub sample_count: u32,
 }
 
 impl Default for ForwardRendererOptions {
@@ -49,22 +477,105 @@ impl Default for ForwardRendererOptions {
     fn default() -> Self {
         Self {
             power_preference: PowerPreference::None,
+            force_fallback_adapter: false,
             alpha: false,
+            sample_count: 1,
         }
     }
 }
 
 impl<'window> ForwardRenderer<'window> {
-    /// The maximum number of meshes that this renderer can render.
-    pub const MESH_CAPACITY: u64 = 1024;
-    /// The maximum number of vertices that a mesh can have to be rendered properly.
-    pub const VERTEX_CAPACITY: u64 = 1 << 19; // 524,288
-    /// The maximum number of triangular polygons an indexed mesh can have to be rendered properly.
-    pub const POLYGON_CAPACITY: u64 = 1 << 19; // 524,288
-    /// The minimum buffer offset alignment as defined in [the WebGPU spec](
-    /// https://www.w3.org/TR/webgpu/#dom-supported-limits-minuniformbufferoffsetalignment).
-    const OFFSET: u64 = 256;
+    /// The layouts of the position, normal, and UV vertex buffers, and of the
+    /// per-instance buffer.
+    const VERTEX_BUFFER_LAYOUTS: [wgpu::VertexBufferLayout<'static>; 4] = [
+        wgpu::VertexBufferLayout {
+            array_stride: 3 * 4,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[VertexAttribute {
+                format: wgpu::VertexFormat::Float32x3,
+                offset: 0,
+                shader_location: 1,
+            }],
+        },
+        wgpu::VertexBufferLayout {
+            array_stride: 3 * 4,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[VertexAttribute {
+                format: wgpu::VertexFormat::Float32x3,
+                offset: 0,
+                shader_location: 2,
+            }],
+        },
+        wgpu::VertexBufferLayout {
+            array_stride: 2 * 4,
+            step_mode: wgpu::VertexStepMode::Vertex,
+            attributes: &[VertexAttribute {
+                format: wgpu::VertexFormat::Float32x2,
+                offset: 0,
+                shader_location: 3,
+            }],
+        },
+        // The columns of the instance matrix, followed by the instance color.
+        wgpu::VertexBufferLayout {
+            array_stride: size_of::<Instance>() as u64,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &wgpu::vertex_attr_array![
+                4 => Float32x4,
+                5 => Float32x4,
+                6 => Float32x4,
+                7 => Float32x4,
+                8 => Float32x4,
+            ],
+        },
+    ];
+
+    /// The layouts of the position, normal, and UV vertex buffers of points,
+    /// which are read per instance because each point is drawn as an instance
+    /// of a quad.
+    const POINTS_VERTEX_BUFFER_LAYOUTS: [wgpu::VertexBufferLayout<'static>; 3] = [
+        wgpu::VertexBufferLayout {
+            array_stride: 3 * 4,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[VertexAttribute {
+                format: wgpu::VertexFormat::Float32x3,
+                offset: 0,
+                shader_location: 1,
+            }],
+        },
+        wgpu::VertexBufferLayout {
+            array_stride: 3 * 4,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[VertexAttribute {
+                format: wgpu::VertexFormat::Float32x3,
+                offset: 0,
+                shader_location: 2,
+            }],
+        },
+        wgpu::VertexBufferLayout {
+            array_stride: 2 * 4,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &[VertexAttribute {
+                format: wgpu::VertexFormat::Float32x2,
+                offset: 0,
+                shader_location: 3,
+            }],
+        },
+    ];
+
+    /// The format of the HDR texture, whose linear colors can exceed `1.0`.
+    const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+
+    /// The format of the depth attachment.
+    const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth24Plus;
+
+    /// The format of the shadow map.
+    const SHADOW_MAP_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
 
+    /// The format of the picking pass's render target. Each pixel holds the
+    /// pick ID of the mesh and the index of the triangle.
+    const PICKING_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rg32Uint;
+
+    /// Creates a new forward renderer that renders to the specified window.
     pub async fn new(
         window: impl Into<wgpu::SurfaceTarget<'window>>,
         options: ForwardRendererOptions,
@@ -73,58 +584,60 @@ impl<'window> ForwardRenderer<'window> {
             window,
             GpuOptions {
                 power_preference: options.power_preference,
+                force_fallback_adapter: options.force_fallback_adapter,
             },
         )
         .await
         .unwrap();
 
-        let depth_texture = gpu.device.create_texture(&wgpu::TextureDescriptor {
-            label: None,
-            size: wgpu::Extent3d {
-                width: gpu.size.0,
-                height: gpu.size.1,
-                depth_or_array_layers: 1,
+        Self::from_gpu(gpu, options)
+    }
+
+    /// Creates a new forward renderer that renders offscreen into a texture of
+    /// the specified size, without a window. Use
+    /// [`render_to_image`](Self::render_to_image) to read the rendered pixels.
+    pub async fn new_headless(width: u32, height: u32, options: ForwardRendererOptions) -> Self {
+        let gpu = Gpu::new_headless(
+            width,
+            height,
+            GpuOptions {
+                power_preference: options.power_preference,
+                force_fallback_adapter: options.force_fallback_adapter,
             },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Depth24Plus,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
-                .union(wgpu::TextureUsages::TEXTURE_BINDING),
-            view_formats: &[],
-        });
+        )
+        .await
+        .unwrap();
+
+        Self::from_gpu(gpu, options)
+    }
+
+    fn from_gpu(gpu: Gpu<'window>, options: ForwardRendererOptions) -> Self {
+        let sample_count = Self::supported_sample_count(&gpu, options.sample_count);
+        let depth_texture = Self::create_depth_texture(&gpu, sample_count);
+        let multisampled_texture = Self::create_multisampled_texture(&gpu, sample_count);
+        let hdr_texture = Self::create_hdr_texture(&gpu);
+        let output_pass = Self::create_output_pass(&gpu, &hdr_texture);
 
         let bind_group_layout =
             gpu.device
                 .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-                    label: None,
+                    label: Some("Object bind group layout"),
                     entries: &[
-                        // Model matrix
+                        // Object uniform
                         wgpu::BindGroupLayoutEntry {
                             binding: 0,
-                            visibility: wgpu::ShaderStages::VERTEX,
-                            ty: wgpu::BindingType::Buffer {
-                                ty: wgpu::BufferBindingType::Uniform,
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
-                        // Model-view matrix
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 1,
-                            visibility: wgpu::ShaderStages::VERTEX,
+                            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
                             ty: wgpu::BindingType::Buffer {
                                 ty: wgpu::BufferBindingType::Uniform,
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
+                                has_dynamic_offset: true,
+                                min_binding_size: NonZero::new(size_of::<ObjectUniform>() as u64),
                             },
                             count: None,
                         },
                         // Projection matrix
                         wgpu::BindGroupLayoutEntry {
-                            binding: 2,
-                            visibility: wgpu::ShaderStages::VERTEX,
+                            binding: 1,
+                            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
                             ty: wgpu::BindingType::Buffer {
                                 ty: wgpu::BufferBindingType::Uniform,
                                 has_dynamic_offset: false,
@@ -134,7 +647,7 @@ impl<'window> ForwardRenderer<'window> {
                         },
                         // View matrix
                         wgpu::BindGroupLayoutEntry {
-                            binding: 3,
+                            binding: 2,
                             visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
                             ty: wgpu::BindingType::Buffer {
                                 ty: wgpu::BufferBindingType::Uniform,
@@ -143,20 +656,9 @@ impl<'window> ForwardRenderer<'window> {
                             },
                             count: None,
                         },
-                        // Normal matrix
-                        wgpu::BindGroupLayoutEntry {
-                            binding: 4,
-                            visibility: wgpu::ShaderStages::VERTEX,
-                            ty: wgpu::BindingType::Buffer {
-                                ty: wgpu::BufferBindingType::Uniform,
-                                has_dynamic_offset: false,
-                                min_binding_size: None,
-                            },
-                            count: None,
-                        },
                         // Camera position
                         wgpu::BindGroupLayoutEntry {
-                            binding: 5,
+                            binding: 3,
                             visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
                             ty: wgpu::BindingType::Buffer {
                                 ty: wgpu::BufferBindingType::Uniform,
@@ -168,78 +670,6 @@ impl<'window> ForwardRenderer<'window> {
                     ],
                 });
 
-        let position_buffer_layout = wgpu::VertexBufferLayout {
-            array_stride: 3 * 4,
-            step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &[VertexAttribute {
-                format: wgpu::VertexFormat::Float32x3,
-                offset: 0,
-                shader_location: 1,
-            }],
-        };
-
-        let position_buffer = gpu.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Position buffer"),
-            size: Self::OFFSET * (Self::VERTEX_CAPACITY - 1) + (3 * 4),
-            usage: wgpu::BufferUsages::VERTEX.union(wgpu::BufferUsages::COPY_DST),
-            mapped_at_creation: false,
-        });
-
-        let normal_buffer_layout = wgpu::VertexBufferLayout {
-            array_stride: 3 * 4,
-            step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &[VertexAttribute {
-                format: wgpu::VertexFormat::Float32x3,
-                offset: 0,
-                shader_location: 2,
-            }],
-        };
-
-        let normal_buffer = gpu.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Normal buffer"),
-            size: Self::OFFSET * (Self::VERTEX_CAPACITY - 1) + (2 * 4),
-            usage: wgpu::BufferUsages::VERTEX.union(wgpu::BufferUsages::COPY_DST),
-            mapped_at_creation: false,
-        });
-
-        let uv_buffer_layout = wgpu::VertexBufferLayout {
-            array_stride: 2 * 4,
-            step_mode: wgpu::VertexStepMode::Vertex,
-            attributes: &[VertexAttribute {
-                format: wgpu::VertexFormat::Float32x2,
-                offset: 0,
-                shader_location: 3,
-            }],
-        };
-
-        let uv_buffer = gpu.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("UV buffer"),
-            size: Self::OFFSET * (Self::VERTEX_CAPACITY - 1) + (2 * 4),
-            usage: wgpu::BufferUsages::VERTEX.union(wgpu::BufferUsages::COPY_DST),
-            mapped_at_creation: false,
-        });
-
-        let index_buffer = gpu.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Index buffer"),
-            size: Self::OFFSET * (Self::POLYGON_CAPACITY - 1) + 4 * 4,
-            usage: wgpu::BufferUsages::INDEX.union(wgpu::BufferUsages::COPY_DST),
-            mapped_at_creation: false,
-        });
-
-        let model_matrix_buffer = gpu.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Model matrix buffer"),
-            size: Self::OFFSET * (Self::MESH_CAPACITY - 1) + size_of::<Matrix4>() as u64,
-            usage: wgpu::BufferUsages::UNIFORM.union(wgpu::BufferUsages::COPY_DST),
-            mapped_at_creation: false,
-        });
-
-        let model_view_matrix_buffer = gpu.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Model view matrix buffer"),
-            size: Self::OFFSET * (Self::MESH_CAPACITY - 1) + size_of::<Matrix4>() as u64,
-            usage: wgpu::BufferUsages::UNIFORM.union(wgpu::BufferUsages::COPY_DST),
-            mapped_at_creation: false,
-        });
-
         let projection_matrix_buffer = gpu.device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Projection matrix buffer"),
             size: size_of::<Matrix4>() as u64,
@@ -254,13 +684,6 @@ impl<'window> ForwardRenderer<'window> {
             mapped_at_creation: false,
         });
 
-        let normal_matrix_buffer = gpu.device.create_buffer(&wgpu::BufferDescriptor {
-            label: Some("Normal matrix buffer"),
-            size: Self::OFFSET * (Self::MESH_CAPACITY - 1) + size_of::<Matrix3>() as u64,
-            usage: wgpu::BufferUsages::UNIFORM.union(wgpu::BufferUsages::COPY_DST),
-            mapped_at_creation: false,
-        });
-
         let camera_position_buffer = gpu.device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Camera position buffer"),
             size: size_of::<Vector3>() as u64,
@@ -268,40 +691,482 @@ impl<'window> ForwardRenderer<'window> {
             mapped_at_creation: false,
         });
 
-        let pipeline_layout = gpu
-            .device
-            .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: None,
-                bind_group_layouts: &[&bind_group_layout],
-                push_constant_ranges: &[],
-            });
+        let lights_bind_group_layout =
+            gpu.device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("Lights bind group layout"),
+                    entries: &[
+                        // Lights
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Storage { read_only: true },
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                        // Shadows
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 1,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Storage { read_only: true },
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                        // Shadow map
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 2,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                sample_type: wgpu::TextureSampleType::Depth,
+                                view_dimension: wgpu::TextureViewDimension::D2Array,
+                                multisampled: false,
+                            },
+                            count: None,
+                        },
+                        // Shadow sampler
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 3,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Comparison),
+                            count: None,
+                        },
+                        // Point shadow map
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 4,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Texture {
+                                sample_type: wgpu::TextureSampleType::Depth,
+                                view_dimension: Self::point_shadow_map_dimension(&gpu),
+                                multisampled: false,
+                            },
+                            count: None,
+                        },
+                    ],
+                });
 
-        let module = gpu
-            .device
-            .create_shader_module(include_wgsl!("shaders/forward_renderer.wgsl"));
+        let shadow_bind_group_layout =
+            gpu.device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("Shadow bind group layout"),
+                    entries: &[
+                        // Shadow camera
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: wgpu::ShaderStages::VERTEX_FRAGMENT,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Uniform,
+                                has_dynamic_offset: true,
+                                min_binding_size: NonZero::new(
+                                    size_of::<ShadowCameraUniform>() as u64
+                                ),
+                            },
+                            count: None,
+                        },
+                    ],
+                });
 
-        let pipeline = gpu
-            .device
-            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        let empty_shadow_maps = ShadowMapCapacity { count: 1, size: 1 };
+        let lights = Self::create_lights_resources(
+            &gpu,
+            &lights_bind_group_layout,
+            &shadow_bind_group_layout,
+            1,
+            empty_shadow_maps,
+            empty_shadow_maps,
+        );
+
+        let object_arena = BufferArena::new(
+            &gpu.device,
+            "Object arena",
+            wgpu::BufferUsages::UNIFORM,
+            gpu.device.limits().min_uniform_buffer_offset_alignment as u64,
+        );
+
+        let mut vertex_arena = BufferArena::new(
+            &gpu.device,
+            "Vertex arena",
+            wgpu::BufferUsages::VERTEX,
+            wgpu::COPY_BUFFER_ALIGNMENT,
+        );
+
+        let (default_instance, _) =
+            vertex_arena.allocate(&gpu.device, &gpu.queue, size_of::<Instance>() as u64);
+
+        gpu.queue.write_buffer(
+            vertex_arena.buffer(),
+            default_instance.offset,
+            bytemuck::bytes_of(&Instance::IDENTITY),
+        );
+
+        let index_arena = BufferArena::new(
+            &gpu.device,
+            "Index arena",
+            wgpu::BufferUsages::INDEX,
+            wgpu::COPY_BUFFER_ALIGNMENT,
+        );
+
+        Self {
+            sample_count,
+            depth_texture,
+            multisampled_texture,
+            hdr_texture,
+            output_pass,
+            background_pass: RefCell::new(None),
+            gpu,
+            clear_color: Color {
+                r: 0.0,
+                g: 0.0,
+                b: 0.0,
+            },
+            clear_alpha: options.alpha.into(),
+            background: Background::ClearColor,
+            tone_mapping: ToneMapping::None,
+            exposure: 1.0,
+            projection_matrix_buffer,
+            view_matrix_buffer,
+            camera_position_buffer,
+            bind_group_layout,
+            lights_bind_group_layout,
+            lights: RefCell::new(lights),
+            shadow_bind_group_layout,
+            shadow_pipeline: RefCell::new(None),
+            point_shadow_pipeline: RefCell::new(None),
+            pipelines: RefCell::new(HashMap::new()),
+            materials: RefCell::new(ResourceCache::new()),
+            textures: RefCell::new(ResourceCache::new()),
+            cube_textures: RefCell::new(ResourceCache::new()),
+            geometries: RefCell::new(ResourceCache::new()),
+            objects: RefCell::new(ResourceCache::new()),
+            object_arena: RefCell::new(object_arena),
+            object_bind_group: RefCell::new(None),
+            vertex_arena: RefCell::new(vertex_arena),
+            index_arena: RefCell::new(index_arena),
+            instances: RefCell::new(ResourceCache::new()),
+            default_instance,
+            default_texture: Rc::new(Texture {
+                generate_mipmaps: false,
+                ..Texture::from_rgba(1, 1, vec![255; 4])
+            }),
+            default_cube_texture: Rc::new(CubeTexture::new(std::array::from_fn(|_| Texture {
+                generate_mipmaps: false,
+                ..Texture::from_rgba(1, 1, vec![255; 4])
+            }))),
+            info: RefCell::new(RenderInfo::default()),
+            picking_pipeline: RefCell::new(None),
+            picking_targets: RefCell::new(None),
+        }
+    }
+
+    /// Creates the shader and layouts of the render pipelines for the type of
+    /// the specified material.
+    fn create_pipeline(&self, material: &dyn Material) -> MaterialPipeline {
+        let bind_group_layout =
+            self.gpu
+                .device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("Material bind group layout"),
+                    entries: &Self::material_bind_group_layout_entries(material),
+                });
+
+        let pipeline_layout =
+            self.gpu
+                .device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: None,
+                    bind_group_layouts: &[
+                        &self.bind_group_layout,
+                        &bind_group_layout,
+                        &self.lights_bind_group_layout,
+                    ],
+                    push_constant_ranges: &[],
+                });
+
+        let point_shadow_map = match Self::point_shadow_map_dimension(&self.gpu) {
+            wgpu::TextureViewDimension::CubeArray => {
+                include_str!("shaders/point_shadow_map_array.wgsl")
+            }
+            _ => include_str!("shaders/point_shadow_map.wgsl"),
+        };
+
+        let source = format!(
+            "{}\n{}\n{}\n{}",
+            include_str!("shaders/forward_renderer.wgsl"),
+            include_str!("shaders/lights.wgsl"),
+            point_shadow_map,
+            material.fragment_shader()
+        );
+
+        let module = self
+            .gpu
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: None,
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+
+        MaterialPipeline {
+            bind_group_layout,
+            pipeline_layout,
+            module,
+            pipelines: HashMap::new(),
+        }
+    }
+
+    /// Creates the render pipeline of a material type for the specified
+    /// primitive and [blending](Self::blending). Transparent meshes do not
+    /// write depth, so that the meshes behind them are still drawn.
+    fn create_render_pipeline(
+        &self,
+        material_pipeline: &MaterialPipeline,
+        primitive: Primitive,
+        blending: Option<BlendMode>,
+    ) -> wgpu::RenderPipeline {
+        let module = &material_pipeline.module;
+
+        let (entry_point, buffers): (_, &[_]) = match primitive {
+            Primitive::Points => ("pointsVertexMain", &Self::POINTS_VERTEX_BUFFER_LAYOUTS),
+            _ => ("vertexMain", &Self::VERTEX_BUFFER_LAYOUTS),
+        };
+
+        let topology = match primitive {
+            Primitive::Triangles | Primitive::Points => wgpu::PrimitiveTopology::TriangleList,
+            Primitive::Lines | Primitive::Wireframe => wgpu::PrimitiveTopology::LineList,
+            Primitive::LineStrip => wgpu::PrimitiveTopology::LineStrip,
+        };
+
+        self.gpu
+            .device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
                 label: None,
+                layout: Some(&material_pipeline.pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module,
+                    entry_point,
+                    compilation_options: Default::default(),
+                    buffers,
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module,
+                    entry_point: "fragmentMain",
+                    compilation_options: Default::default(),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: Self::HDR_FORMAT,
+                        blend: blending.map(Self::blend_state),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology,
+                    front_face: wgpu::FrontFace::Ccw,
+                    // Lines and points have no back faces.
+                    cull_mode: (primitive == Primitive::Triangles).then_some(wgpu::Face::Back),
+                    strip_index_format: (primitive == Primitive::LineStrip)
+                        .then_some(wgpu::IndexFormat::Uint32),
+                    unclipped_depth: false,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    conservative: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: Self::DEPTH_FORMAT,
+                    depth_write_enabled: blending.is_none(),
+                    depth_compare: wgpu::CompareFunction::Less,
+                    stencil: wgpu::StencilState::default(),
+                    bias: wgpu::DepthBiasState::default(),
+                }),
+                multisample: wgpu::MultisampleState {
+                    count: self.sample_count,
+                    ..Default::default()
+                },
+                multiview: None,
+                cache: None,
+            })
+    }
+
+    /// Creates the render pipeline of the shadow pass, which only writes the
+    /// depth of the shadow casters. For point lights, the depth is the linear
+    /// distance from the light divided by its range, which is written by a
+    /// fragment stage.
+    fn create_shadow_pipeline(&self, point: bool) -> wgpu::RenderPipeline {
+        let pipeline_layout =
+            self.gpu
+                .device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("Shadow pipeline layout"),
+                    bind_group_layouts: &[&self.bind_group_layout, &self.shadow_bind_group_layout],
+                    push_constant_ranges: &[],
+                });
+
+        let source = format!(
+            "{}\n{}",
+            include_str!("shaders/forward_renderer.wgsl"),
+            include_str!("shaders/shadow.wgsl")
+        );
+
+        let module = self
+            .gpu
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Shadow shader"),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+
+        self.gpu
+            .device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Shadow pipeline"),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &module,
+                    entry_point: "shadowVertexMain",
+                    compilation_options: Default::default(),
+                    buffers: &Self::VERTEX_BUFFER_LAYOUTS,
+                },
+                fragment: point.then(|| wgpu::FragmentState {
+                    module: &module,
+                    entry_point: "pointShadowFragmentMain",
+                    compilation_options: Default::default(),
+                    targets: &[],
+                }),
+                primitive: wgpu::PrimitiveState {
+                    topology: wgpu::PrimitiveTopology::TriangleList,
+                    // The views of the faces of cube maps are mirrored, which
+                    // reverses the winding of triangles.
+                    front_face: if point {
+                        wgpu::FrontFace::Cw
+                    } else {
+                        wgpu::FrontFace::Ccw
+                    },
+                    cull_mode: Some(wgpu::Face::Back),
+                    strip_index_format: None,
+                    unclipped_depth: false,
+                    polygon_mode: wgpu::PolygonMode::Fill,
+                    conservative: false,
+                },
+                depth_stencil: Some(wgpu::DepthStencilState {
+                    format: Self::SHADOW_MAP_FORMAT,
+                    depth_write_enabled: true,
+                    depth_compare: wgpu::CompareFunction::Less,
+                    stencil: wgpu::StencilState::default(),
+                    // Surfaces at a grazing angle to the light span a large
+                    // depth range per texel, so their depth is pushed back
+                    // further to avoid shadow acne. The bias does not apply
+                    // to depths written by the fragment stage.
+                    bias: wgpu::DepthBiasState {
+                        constant: 0,
+                        slope_scale: if point { 0.0 } else { 2.0 },
+                        clamp: 0.0,
+                    },
+                }),
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+                cache: None,
+            })
+    }
+
+    /// Returns how the specified material is blended: `None` if it is opaque
+    /// and replaces the colors behind it, otherwise its blend mode.
+    fn blending(material: &dyn Material) -> Option<BlendMode> {
+        let settings = material.settings();
+
+        settings.transparent.then_some(settings.blend_mode)
+    }
+
+    /// Returns the blend state of the specified blend mode. Fragment shaders
+    /// return colors that are not premultiplied by alpha, except for
+    /// [`BlendMode::Premultiplied`].
+    fn blend_state(blend_mode: BlendMode) -> wgpu::BlendState {
+        // The alpha of the target only changes for blend modes that cover
+        // the colors behind.
+        let keep_alpha = wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::Zero,
+            dst_factor: wgpu::BlendFactor::One,
+            operation: wgpu::BlendOperation::Add,
+        };
+
+        match blend_mode {
+            BlendMode::Normal => wgpu::BlendState::ALPHA_BLENDING,
+            BlendMode::Additive => wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: keep_alpha,
+            },
+            BlendMode::Multiply => wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::Zero,
+                    dst_factor: wgpu::BlendFactor::Src,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: keep_alpha,
+            },
+            BlendMode::Premultiplied => wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING,
+        }
+    }
+
+    /// Creates the render pipeline of the picking pass, which writes the pick
+    /// ID of each mesh and the index of each triangle into a
+    /// [`PICKING_FORMAT`](Self::PICKING_FORMAT) render target.
+    fn create_picking_pipeline(&self) -> wgpu::RenderPipeline {
+        let pipeline_layout =
+            self.gpu
+                .device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("Picking pipeline layout"),
+                    bind_group_layouts: &[&self.bind_group_layout],
+                    push_constant_ranges: &[],
+                });
+
+        let fragment_shader = if self
+            .gpu
+            .device
+            .features()
+            .contains(wgpu::Features::SHADER_PRIMITIVE_INDEX)
+        {
+            include_str!("shaders/picking_primitive.wgsl")
+        } else {
+            include_str!("shaders/picking.wgsl")
+        };
+
+        let source = format!(
+            "{}\n{}",
+            include_str!("shaders/forward_renderer.wgsl"),
+            fragment_shader
+        );
+
+        let module = self
+            .gpu
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Picking shader"),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+
+        self.gpu
+            .device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Picking pipeline"),
                 layout: Some(&pipeline_layout),
                 vertex: wgpu::VertexState {
                     module: &module,
                     entry_point: "vertexMain",
                     compilation_options: Default::default(),
-                    buffers: &[
-                        position_buffer_layout,
-                        normal_buffer_layout,
-                        uv_buffer_layout,
-                    ],
+                    buffers: &Self::VERTEX_BUFFER_LAYOUTS,
                 },
                 fragment: Some(wgpu::FragmentState {
                     module: &module,
                     entry_point: "fragmentMain",
                     compilation_options: Default::default(),
                     targets: &[Some(wgpu::ColorTargetState {
-                        format: gpu.surface_configuration.format,
-                        blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                        format: Self::PICKING_FORMAT,
+                        blend: None,
                         write_mask: wgpu::ColorWrites::ALL,
                     })],
                 }),
@@ -315,7 +1180,7 @@ impl<'window> ForwardRenderer<'window> {
                     conservative: false,
                 },
                 depth_stencil: Some(wgpu::DepthStencilState {
-                    format: wgpu::TextureFormat::Depth24Plus,
+                    format: Self::DEPTH_FORMAT,
                     depth_write_enabled: true,
                     depth_compare: wgpu::CompareFunction::Less,
                     stencil: wgpu::StencilState::default(),
@@ -324,75 +1189,1875 @@ impl<'window> ForwardRenderer<'window> {
                 multisample: wgpu::MultisampleState::default(),
                 multiview: None,
                 cache: None,
-            });
+            })
+    }
 
-        Self {
-            depth_texture,
-            gpu,
-            clear_color: Color {
-                r: 0.0,
-                g: 0.0,
-                b: 0.0,
+    /// Creates the render targets of the picking pass, with the size of the
+    /// renderer.
+    fn create_picking_targets(&self) -> PickingTargets {
+        let color = self.gpu.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Picking texture"),
+            size: wgpu::Extent3d {
+                width: self.gpu.size.0,
+                height: self.gpu.size.1,
+                depth_or_array_layers: 1,
             },
-            clear_alpha: options.alpha.into(),
-            position_buffer,
-            normal_buffer,
-            uv_buffer,
-            index_buffer,
-            model_matrix_buffer,
-            model_view_matrix_buffer,
-            projection_matrix_buffer,
-            view_matrix_buffer,
-            normal_matrix_buffer,
-            camera_position_buffer,
-            bind_group_layout,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::PICKING_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT.union(wgpu::TextureUsages::COPY_SRC),
+            view_formats: &[],
+        });
+
+        PickingTargets {
+            color,
+            depth: Self::create_depth_texture(&self.gpu, 1),
+        }
+    }
+
+    /// Returns the largest sample count that is at most the specified one and
+    /// that the device supports for both the HDR and the depth attachments,
+    /// including resolving the multisampled HDR attachment.
+    fn supported_sample_count(gpu: &Gpu, sample_count: u32) -> u32 {
+        let hdr_features = gpu.texture_format_features(Self::HDR_FORMAT);
+        let depth_features = gpu.texture_format_features(Self::DEPTH_FORMAT);
+
+        [16, 8, 4, 2]
+            .into_iter()
+            .find(|&count| {
+                count <= sample_count
+                    && hdr_features.flags.sample_count_supported(count)
+                    && hdr_features
+                        .flags
+                        .contains(wgpu::TextureFormatFeatureFlags::MULTISAMPLE_RESOLVE)
+                    && depth_features.flags.sample_count_supported(count)
+            })
+            .unwrap_or(1)
+    }
+
+    /// Returns whether depth attachments with the specified number of samples
+    /// per pixel can also be bound as textures. The OpenGL backend of WGPU
+    /// cannot sample multisampled textures.
+    fn depth_texture_bindable(gpu: &Gpu, sample_count: u32) -> bool {
+        sample_count == 1 || gpu.adapter.get_info().backend != wgpu::Backend::Gl
+    }
+
+    /// Creates the depth attachment with the size of the specified GPU's
+    /// surface and the specified number of samples per pixel.
+    fn create_depth_texture(gpu: &Gpu, sample_count: u32) -> wgpu::Texture {
+        let usage = if Self::depth_texture_bindable(gpu, sample_count) {
+            wgpu::TextureUsages::RENDER_ATTACHMENT.union(wgpu::TextureUsages::TEXTURE_BINDING)
+        } else {
+            wgpu::TextureUsages::RENDER_ATTACHMENT
+        };
+
+        gpu.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Depth texture"),
+            size: wgpu::Extent3d {
+                width: gpu.size.0,
+                height: gpu.size.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::DEPTH_FORMAT,
+            usage,
+            view_formats: &[],
+        })
+    }
+
+    /// Creates the multisampled color attachment with the size of the
+    /// specified GPU's surface and the HDR format, or returns `None` if
+    /// `sample_count` is `1`.
+    fn create_multisampled_texture(gpu: &Gpu, sample_count: u32) -> Option<wgpu::Texture> {
+        (sample_count > 1).then(|| {
+            gpu.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Multisampled texture"),
+                size: wgpu::Extent3d {
+                    width: gpu.size.0,
+                    height: gpu.size.1,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count,
+                dimension: wgpu::TextureDimension::D2,
+                format: Self::HDR_FORMAT,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            })
+        })
+    }
+
+    /// Creates the HDR texture with the size of the specified GPU's surface.
+    fn create_hdr_texture(gpu: &Gpu) -> wgpu::Texture {
+        gpu.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("HDR texture"),
+            size: wgpu::Extent3d {
+                width: gpu.size.0,
+                height: gpu.size.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::HDR_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                .union(wgpu::TextureUsages::TEXTURE_BINDING),
+            view_formats: &[],
+        })
+    }
+
+    /// Creates the pipeline and bind groups of the output pass, which reads
+    /// the specified HDR texture and writes into textures of the surface
+    /// format.
+    fn create_output_pass(gpu: &Gpu, hdr_texture: &wgpu::Texture) -> OutputPass {
+        let input_bind_group_layout =
+            gpu.device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("Output input bind group layout"),
+                    entries: &full_screen_pass::input_layout_entries(),
+                });
+
+        let uniform_bind_group_layout =
+            gpu.device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("Output uniform bind group layout"),
+                    entries: &[wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    }],
+                });
+
+        let pipeline = full_screen_pass::create_pipeline(
+            &gpu.device,
+            Some("Output pipeline"),
+            &[&input_bind_group_layout, &uniform_bind_group_layout],
+            include_str!("shaders/output.wgsl"),
+            gpu.surface_configuration.format,
+        );
+
+        let sampler = full_screen_pass::create_sampler(&gpu.device, "Output sampler");
+
+        let input_bind_group = Self::create_output_input_bind_group(
+            gpu,
+            &input_bind_group_layout,
+            &sampler,
+            hdr_texture,
+        );
+
+        let uniform_buffer = gpu.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Output uniform buffer"),
+            size: size_of::<OutputUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM.union(wgpu::BufferUsages::COPY_DST),
+            mapped_at_creation: false,
+        });
+
+        let uniform_bind_group = gpu.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &uniform_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: uniform_buffer.as_entire_binding(),
+            }],
+        });
+
+        OutputPass {
             pipeline,
+            input_bind_group_layout,
+            sampler,
+            input_bind_group,
+            uniform_buffer,
+            uniform_bind_group,
+        }
+    }
+
+    /// Creates the bind group that binds the specified HDR texture as the
+    /// input of the output pass.
+    fn create_output_input_bind_group(
+        gpu: &Gpu,
+        layout: &wgpu::BindGroupLayout,
+        sampler: &wgpu::Sampler,
+        hdr_texture: &wgpu::Texture,
+    ) -> wgpu::BindGroup {
+        gpu.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(
+                        &hdr_texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                    ),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        })
+    }
+
+    /// Creates the render pipeline and uniform buffer of the background pass,
+    /// which draws a triangle that covers the screen behind the scene.
+    fn create_background_pass(&self) -> BackgroundPass {
+        let texture_entry = |binding, view_dimension| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension,
+                multisampled: false,
+            },
+            count: None,
+        };
+
+        let sampler_entry = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+            count: None,
+        };
+
+        let bind_group_layout =
+            self.gpu
+                .device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("Background bind group layout"),
+                    entries: &[
+                        wgpu::BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: wgpu::ShaderStages::FRAGMENT,
+                            ty: wgpu::BindingType::Buffer {
+                                ty: wgpu::BufferBindingType::Uniform,
+                                has_dynamic_offset: false,
+                                min_binding_size: None,
+                            },
+                            count: None,
+                        },
+                        // Equirectangular texture
+                        texture_entry(1, wgpu::TextureViewDimension::D2),
+                        sampler_entry(2),
+                        // Cube texture
+                        texture_entry(3, wgpu::TextureViewDimension::Cube),
+                        sampler_entry(4),
+                    ],
+                });
+
+        let pipeline_layout =
+            self.gpu
+                .device
+                .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                    label: Some("Background pipeline layout"),
+                    bind_group_layouts: &[&bind_group_layout],
+                    push_constant_ranges: &[],
+                });
+
+        let module = self
+            .gpu
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("Background shader"),
+                source: wgpu::ShaderSource::Wgsl(include_str!("shaders/background.wgsl").into()),
+            });
+
+        let pipeline = self
+            .gpu
+            .device
+            .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("Background pipeline"),
+                layout: Some(&pipeline_layout),
+                vertex: wgpu::VertexState {
+                    module: &module,
+                    entry_point: "vertexMain",
+                    compilation_options: Default::default(),
+                    buffers: &[],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &module,
+                    entry_point: "fragmentMain",
+                    compilation_options: Default::default(),
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format: Self::HDR_FORMAT,
+                        blend: None,
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState {
+                    count: self.sample_count,
+                    ..Default::default()
+                },
+                multiview: None,
+                cache: None,
+            });
+
+        let uniform_buffer = self.gpu.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Background uniform buffer"),
+            size: size_of::<BackgroundUniform>() as u64,
+            usage: wgpu::BufferUsages::UNIFORM.union(wgpu::BufferUsages::COPY_DST),
+            mapped_at_creation: false,
+        });
+
+        let bind_group = self.create_background_bind_group(
+            &bind_group_layout,
+            &uniform_buffer,
+            &self.default_texture,
+            &self.default_cube_texture,
+        );
+
+        BackgroundPass {
+            pipeline,
+            bind_group_layout,
+            uniform_buffer,
+            bind_group,
+            textures: (
+                Rc::downgrade(&self.default_texture),
+                Rc::downgrade(&self.default_cube_texture),
+            ),
+        }
+    }
+
+    /// Creates the bind group of the background pass with the specified
+    /// equirectangular texture and cube texture, uploading them to the GPU if
+    /// they have not been uploaded yet.
+    fn create_background_bind_group(
+        &self,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        uniform_buffer: &wgpu::Buffer,
+        texture: &Rc<Texture>,
+        cube_texture: &Rc<CubeTexture>,
+    ) -> wgpu::BindGroup {
+        let mut texture_cache = self.textures.borrow_mut();
+        let mut cube_texture_cache = self.cube_textures.borrow_mut();
+
+        let texture_resources =
+            &*texture_cache.get_or_insert_with(texture, || self.create_texture_resources(texture));
+        let cube_texture_resources = &*cube_texture_cache.get_or_insert_with(cube_texture, || {
+            self.create_cube_texture_resources(cube_texture)
+        });
+
+        self.gpu
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout: bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: uniform_buffer.as_entire_binding(),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::TextureView(&texture_resources.view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::Sampler(&texture_resources.sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 3,
+                        resource: wgpu::BindingResource::TextureView(&cube_texture_resources.view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 4,
+                        resource: wgpu::BindingResource::Sampler(&cube_texture_resources.sampler),
+                    },
+                ],
+            })
+    }
+
+    /// Returns the bind group layout entries of the specified material: the
+    /// uniform buffer, followed by a texture and a sampler for each of the
+    /// material's textures.
+    fn material_bind_group_layout_entries(
+        material: &dyn Material,
+    ) -> Vec<wgpu::BindGroupLayoutEntry> {
+        let mut entries = vec![
+            // Material uniform
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: NonZero::new(Self::uniform_size(material)),
+                },
+                count: None,
+            },
+        ];
+
+        for i in 0..material.textures().len() as u32 {
+            // Texture
+            entries.push(wgpu::BindGroupLayoutEntry {
+                binding: 1 + 2 * i,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    multisampled: false,
+                },
+                count: None,
+            });
+
+            // Sampler
+            entries.push(wgpu::BindGroupLayoutEntry {
+                binding: 2 + 2 * i,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            });
+        }
+
+        entries
+    }
+
+    /// Returns the visible meshes in the specified scene, in drawing order.
+    /// Descendants of invisible objects are not visible.
+    fn visible_meshes(scene: &Rc<Object3d>) -> Vec<Rc<Object3d>> {
+        let mut meshes = Vec::new();
+        let mut stack = vec![Rc::clone(scene)];
+
+        while let Some(object) = stack.pop() {
+            if !*object.visible.borrow() {
+                continue;
+            }
+
+            if Self::geometry_and_material(&object).is_some() {
+                meshes.push(Rc::clone(&object));
+            }
+
+            for child in object.children.borrow().iter() {
+                stack.push(Rc::clone(child));
+            }
+        }
+
+        meshes
+    }
+
+    /// Returns the geometry, material, and primitive of the specified object
+    /// if it is a mesh, an instanced mesh, a line, line segments, or points.
+    fn geometry_and_material(
+        object: &Object3d,
+    ) -> Option<(&Rc<BufferGeometry>, &Rc<dyn Material>, Primitive)> {
+        match &object.kind {
+            Object3dKind::Mesh(mesh) => Some((
+                &mesh.geometry,
+                &mesh.material,
+                Self::mesh_primitive(mesh.material.as_ref()),
+            )),
+            Object3dKind::InstancedMesh(instanced_mesh) => Some((
+                &instanced_mesh.geometry,
+                &instanced_mesh.material,
+                Self::mesh_primitive(instanced_mesh.material.as_ref()),
+            )),
+            Object3dKind::Line(line) => {
+                Some((&line.geometry, &line.material, Primitive::LineStrip))
+            }
+            Object3dKind::LineSegments(line_segments) => Some((
+                &line_segments.geometry,
+                &line_segments.material,
+                Primitive::Lines,
+            )),
+            Object3dKind::Points(points) => {
+                Some((&points.geometry, &points.material, Primitive::Points))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns how the triangles of meshes with the specified material are
+    /// drawn.
+    fn mesh_primitive(material: &dyn Material) -> Primitive {
+        if material.settings().wireframe {
+            Primitive::Wireframe
+        } else {
+            Primitive::Triangles
+        }
+    }
+
+    /// Returns the bounding sphere of the specified object's geometry in its
+    /// local space, which contains all instances of an instanced mesh.
+    fn bounding_sphere(object: &Object3d) -> Option<Sphere> {
+        match &object.kind {
+            Object3dKind::InstancedMesh(instanced_mesh) => {
+                Some(instanced_mesh.compute_bounding_sphere())
+            }
+            _ => Self::geometry_and_material(object)
+                .map(|(geometry, _, _)| geometry.compute_bounding_sphere()),
+        }
+    }
+
+    /// Returns whether the bounding sphere of the specified mesh is at least
+    /// partially inside the specified frustum.
+    fn is_in_frustum(object: &Object3d, frustum: &Frustum) -> bool {
+        match Self::bounding_sphere(object) {
+            Some(bounding_sphere) => frustum
+                .intersects_sphere(&bounding_sphere.apply_matrix4(&object.world_matrix.borrow())),
+            None => true,
+        }
+    }
+
+    /// Writes the per-mesh uniforms of the specified meshes into their slots
+    /// in the object arena, allocating slots for new meshes. The pick ID of
+    /// each mesh is its index in `meshes` plus one.
+    fn update_object_uniforms(
+        &self,
+        meshes: &[Rc<Object3d>],
+        view_matrix: &Matrix4,
+        first_pick_id: u32,
+    ) {
+        let mut objects = self.objects.borrow_mut();
+        let mut object_arena = self.object_arena.borrow_mut();
+
+        for (index, object) in meshes.iter().enumerate() {
+            let slot = *objects.get_or_insert_with(object, || {
+                let (slot, grown) = object_arena.allocate(
+                    &self.gpu.device,
+                    &self.gpu.queue,
+                    size_of::<ObjectUniform>() as u64,
+                );
+
+                if grown {
+                    *self.object_bind_group.borrow_mut() = None;
+                }
+
+                slot
+            });
+
+            let world_matrix = object.world_matrix.borrow();
+            let point_size = match Self::geometry_and_material(object) {
+                Some((_, material, Primitive::Points)) => material.point_size(),
+                _ => 0.0,
+            };
+
+            self.gpu.queue.write_buffer(
+                object_arena.buffer(),
+                slot.offset,
+                bytemuck::bytes_of(&ObjectUniform {
+                    model_matrix: *world_matrix,
+                    model_view_matrix: *view_matrix * world_matrix.as_ref(),
+                    normal_matrix: Self::normal_matrix(&world_matrix),
+                    pick_id: first_pick_id + index as u32,
+                    receive_shadow: (*object.receive_shadow.borrow()).into(),
+                    point_size: [
+                        point_size / self.gpu.size.0 as f32,
+                        point_size / self.gpu.size.1 as f32,
+                    ],
+                }),
+            );
+        }
+
+        let mut object_bind_group = self.object_bind_group.borrow_mut();
+
+        if object_bind_group.is_none() {
+            *object_bind_group = Some(self.create_object_bind_group(object_arena.buffer()));
+        }
+    }
+
+    /// Creates the bind group of the object and camera uniforms, where the
+    /// object uniform is read from the specified buffer.
+    fn create_object_bind_group(&self, object_buffer: &wgpu::Buffer) -> wgpu::BindGroup {
+        self.gpu
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("Object bind group"),
+                layout: &self.bind_group_layout,
+                entries: &[
+                    // Object uniform
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                            buffer: object_buffer,
+                            offset: 0,
+                            size: NonZero::new(size_of::<ObjectUniform>() as u64),
+                        }),
+                    },
+                    // Projection matrix
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: self.projection_matrix_buffer.as_entire_binding(),
+                    },
+                    // View matrix
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: self.view_matrix_buffer.as_entire_binding(),
+                    },
+                    // Camera position
+                    wgpu::BindGroupEntry {
+                        binding: 3,
+                        resource: self.camera_position_buffer.as_entire_binding(),
+                    },
+                ],
+            })
+    }
+
+    /// Returns the dimension of the view of the point shadow map, which is a
+    /// cube map array unless the GPU does not support them, in which case only
+    /// one point light can cast shadows.
+    fn point_shadow_map_dimension(gpu: &Gpu) -> wgpu::TextureViewDimension {
+        if gpu
+            .adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(wgpu::DownlevelFlags::CUBE_ARRAY_TEXTURES)
+        {
+            wgpu::TextureViewDimension::CubeArray
+        } else {
+            wgpu::TextureViewDimension::Cube
+        }
+    }
+
+    /// Creates the buffers and bind groups for the specified number of lights
+    /// and shadows, and the shadow map with layers of the specified size.
+    fn create_lights_resources(
+        gpu: &Gpu,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        shadow_bind_group_layout: &wgpu::BindGroupLayout,
+        capacity: usize,
+        shadow_maps: ShadowMapCapacity,
+        point_shadow_maps: ShadowMapCapacity,
+    ) -> LightsResources {
+        let buffer = gpu.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Lights buffer"),
+            // The light count is padded to the 16-byte alignment of the lights.
+            size: (16 + capacity * size_of::<LightUniform>()) as u64,
+            usage: wgpu::BufferUsages::STORAGE.union(wgpu::BufferUsages::COPY_DST),
+            mapped_at_creation: false,
+        });
+
+        let shadow_buffer = gpu.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Shadow buffer"),
+            size: ((shadow_maps.count + point_shadow_maps.count) * size_of::<ShadowUniform>())
+                as u64,
+            usage: wgpu::BufferUsages::STORAGE.union(wgpu::BufferUsages::COPY_DST),
+            mapped_at_creation: false,
+        });
+
+        let shadow_map = gpu.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Shadow map"),
+            size: wgpu::Extent3d {
+                width: shadow_maps.size,
+                height: shadow_maps.size,
+                // Textures with a single layer cannot be viewed as arrays on
+                // some backends e.g. OpenGL.
+                depth_or_array_layers: shadow_maps.count.max(2) as u32,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::SHADOW_MAP_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                .union(wgpu::TextureUsages::TEXTURE_BINDING),
+            view_formats: &[],
+        });
+
+        let point_shadow_map_dimension = Self::point_shadow_map_dimension(gpu);
+
+        // Textures with six layers are cube maps rather than cube map arrays
+        // on some backends e.g. OpenGL.
+        let point_shadow_map_count = match point_shadow_map_dimension {
+            wgpu::TextureViewDimension::CubeArray => point_shadow_maps.count.max(2),
+            _ => 1,
+        };
+
+        let point_shadow_map = gpu.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Point shadow map"),
+            size: wgpu::Extent3d {
+                width: point_shadow_maps.size,
+                height: point_shadow_maps.size,
+                depth_or_array_layers: 6 * point_shadow_map_count as u32,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::SHADOW_MAP_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                .union(wgpu::TextureUsages::TEXTURE_BINDING),
+            view_formats: &[],
+        });
+
+        let shadow_sampler = gpu.device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Shadow sampler"),
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            // Fragments at most as far from the light as the shadow casters
+            // are lit.
+            compare: Some(wgpu::CompareFunction::LessEqual),
+            ..Default::default()
+        });
+
+        let alignment = gpu.device.limits().min_uniform_buffer_offset_alignment as u64;
+        let shadow_camera_stride =
+            (size_of::<ShadowCameraUniform>() as u64).div_ceil(alignment) * alignment;
+
+        // Each cube map is drawn by six shadow passes, one per face.
+        let shadow_pass_capacity = shadow_maps.count + 6 * point_shadow_maps.count;
+
+        let shadow_camera_buffer = gpu.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Shadow camera buffer"),
+            size: shadow_pass_capacity as u64 * shadow_camera_stride,
+            usage: wgpu::BufferUsages::UNIFORM.union(wgpu::BufferUsages::COPY_DST),
+            mapped_at_creation: false,
+        });
+
+        let shadow_camera_bind_group = gpu.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: shadow_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                    buffer: &shadow_camera_buffer,
+                    offset: 0,
+                    size: NonZero::new(size_of::<ShadowCameraUniform>() as u64),
+                }),
+            }],
+        });
+
+        let bind_group = gpu.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: shadow_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(&shadow_map.create_view(
+                        &wgpu::TextureViewDescriptor {
+                            dimension: Some(wgpu::TextureViewDimension::D2Array),
+                            ..Default::default()
+                        },
+                    )),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::Sampler(&shadow_sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: wgpu::BindingResource::TextureView(&point_shadow_map.create_view(
+                        &wgpu::TextureViewDescriptor {
+                            dimension: Some(point_shadow_map_dimension),
+                            ..Default::default()
+                        },
+                    )),
+                },
+            ],
+        });
+
+        LightsResources {
+            buffer,
+            capacity,
+            shadow_buffer,
+            shadow_maps,
+            shadow_map,
+            point_shadow_maps,
+            point_shadow_map,
+            shadow_camera_buffer,
+            shadow_camera_stride,
+            shadow_camera_bind_group,
+            bind_group,
+        }
+    }
+
+    /// Collects the visible lights in the specified scene and writes them and
+    /// their shadows to the lights buffers, growing them if needed. Cascaded
+    /// shadows are fitted to the frustum of the specified camera.
+    ///
+    /// # Returns
+    ///
+    /// The shadow passes that draw the shadow maps, in the order of their
+    /// cameras in the shadow camera buffer.
+    fn update_lights(&self, scene: &Rc<Object3d>, camera: &Camera) -> Vec<ShadowPass> {
+        let mut light_uniforms: Vec<LightUniform> = Vec::new();
+        let mut shadows: Vec<ShadowView> = Vec::new();
+        let mut stack = vec![Rc::clone(scene)];
+        let max_point_shadows = match Self::point_shadow_map_dimension(&self.gpu) {
+            wgpu::TextureViewDimension::CubeArray => usize::MAX,
+            _ => 1,
+        };
+        let mut point_shadow_count = 0;
+
+        while let Some(object) = stack.pop() {
+            if !*object.visible.borrow() {
+                continue;
+            }
+
+            if let Object3dKind::Light(light) = &object.kind {
+                let light = light.borrow();
+                let world_matrix = object.world_matrix.borrow();
+                let e = &world_matrix.elements;
+
+                // Lights shine along their local -Z axis.
+                let z_axis = [e[8], e[9], e[10]];
+                let length = z_axis.iter().map(|x| x * x).sum::<f32>().sqrt().max(f32::EPSILON);
+
+                let (kind, range, decay, cos_angle, cos_penumbra) = match light.kind {
+                    LightKind::AmbientLight => (0, 0.0, 0.0, 0.0, 0.0),
+                    LightKind::DirectionalLight => (1, 0.0, 0.0, 0.0, 0.0),
+                    LightKind::PointLight { range, decay } => (2, range, decay, 0.0, 0.0),
+                    LightKind::SpotLight {
+                        range,
+                        angle,
+                        penumbra,
+                        decay,
+                    } => (
+                        3,
+                        range,
+                        decay,
+                        angle.cos(),
+                        (angle * (1.0 - penumbra.clamp(0.0, 1.0))).cos(),
+                    ),
+                };
+
+                let mut shadow_views = if light.cast_shadow {
+                    Self::shadow_views(&light, &world_matrix, camera)
+                } else {
+                    Vec::new()
+                };
+
+                // Without cube map arrays, only the first point light that
+                // casts shadows has a cube map.
+                if point_shadow_count == max_point_shadows {
+                    shadow_views.retain(|shadow| shadow.point.is_none());
+                }
+
+                point_shadow_count += shadow_views
+                    .iter()
+                    .filter(|shadow| shadow.point.is_some())
+                    .count();
+
+                let shadow = if shadow_views.is_empty() {
+                    -1
+                } else {
+                    shadows.len() as i32
+                };
+
+                let shadow_count = shadow_views.len() as u32;
+
+                shadows.extend(shadow_views);
+
+                light_uniforms.push(LightUniform {
+                    color: to_linear(&light.color).map(|c| c * light.intensity),
+                    kind,
+                    position: [e[12], e[13], e[14]],
+                    range,
+                    direction: z_axis.map(|x| -x / length),
+                    decay,
+                    cos_angle,
+                    cos_penumbra,
+                    shadow,
+                    shadow_count,
+                });
+            }
+
+            for child in object.children.borrow().iter() {
+                stack.push(Rc::clone(child));
+            }
+        }
+
+        let mut lights = self.lights.borrow_mut();
+
+        // All shadow maps share the layers of a texture array, which are as
+        // large as the largest shadow map. Point lights have their own array
+        // of cube maps, which all have the size of the largest one.
+        let capacity = |point: bool| {
+            let shadows = shadows
+                .iter()
+                .filter(|shadow| shadow.point.is_some() == point);
+
+            ShadowMapCapacity {
+                count: shadows.clone().count(),
+                size: shadows.map(|shadow| shadow.map_size).max().unwrap_or(1),
+            }
+        };
+
+        let (shadow_maps, point_shadow_maps) = (capacity(false), capacity(true));
+
+        if light_uniforms.len() > lights.capacity
+            || shadow_maps.count > lights.shadow_maps.count
+            || shadow_maps.size != lights.shadow_maps.size
+            || point_shadow_maps.count > lights.point_shadow_maps.count
+            || point_shadow_maps.size != lights.point_shadow_maps.size
+        {
+            let grow =
+                |required: ShadowMapCapacity, current: ShadowMapCapacity| ShadowMapCapacity {
+                    count: required.count.max(current.count).next_power_of_two(),
+                    size: required.size,
+                };
+
+            *lights = Self::create_lights_resources(
+                &self.gpu,
+                &self.lights_bind_group_layout,
+                &self.shadow_bind_group_layout,
+                light_uniforms
+                    .len()
+                    .max(lights.capacity)
+                    .next_power_of_two(),
+                grow(shadow_maps, lights.shadow_maps),
+                grow(point_shadow_maps, lights.point_shadow_maps),
+            );
+        }
+
+        self.gpu.queue.write_buffer(
+            &lights.buffer,
+            0,
+            bytemuck::cast_slice(&[light_uniforms.len() as u32, 0, 0, 0]),
+        );
+
+        if !light_uniforms.is_empty() {
+            self.gpu
+                .queue
+                .write_buffer(&lights.buffer, 16, bytemuck::cast_slice(&light_uniforms));
+        }
+
+        let mut shadow_uniforms: Vec<ShadowUniform> = Vec::new();
+        let mut shadow_passes: Vec<ShadowPass> = Vec::new();
+        let mut layers = [0, 0];
+
+        for shadow in &shadows {
+            let point = shadow.point.is_some();
+            let layer = layers[point as usize];
+            let map_size = if point {
+                point_shadow_maps.size
+            } else {
+                shadow_maps.size
+            };
+
+            layers[point as usize] += 1;
+
+            let (position, range) = shadow.point.unwrap_or_default();
+
+            shadow_uniforms.push(ShadowUniform {
+                view_projection_matrix: shadow.view_projection_matrices[0],
+                layer,
+                bias: shadow.bias,
+                scale: shadow.map_size as f32 / map_size as f32,
+                texel_size: 1.0 / map_size as f32,
+                far: shadow.far,
+                blend_start: shadow.blend_start,
+                range,
+                _padding: 0.0,
+            });
+
+            for (face, view_projection_matrix) in shadow.view_projection_matrices.iter().enumerate()
+            {
+                self.gpu.queue.write_buffer(
+                    &lights.shadow_camera_buffer,
+                    shadow_passes.len() as u64 * lights.shadow_camera_stride,
+                    bytemuck::bytes_of(&ShadowCameraUniform {
+                        view_projection_matrix: *view_projection_matrix,
+                        position,
+                        range,
+                    }),
+                );
+
+                // Each cube map takes six consecutive layers, one per face.
+                shadow_passes.push(ShadowPass {
+                    point,
+                    layer: if point {
+                        6 * layer + face as u32
+                    } else {
+                        layer
+                    },
+                    map_size: if point { map_size } else { shadow.map_size },
+                });
+            }
+        }
+
+        if !shadow_uniforms.is_empty() {
+            self.gpu.queue.write_buffer(
+                &lights.shadow_buffer,
+                0,
+                bytemuck::cast_slice(&shadow_uniforms),
+            );
+        }
+
+        shadow_passes
+    }
+
+    /// Returns the views from which the shadow casters of the specified light
+    /// are drawn into the shadow maps, which are the cascades of a cascaded
+    /// shadow, the cube map of a point light, or a single view through the
+    /// light's [shadow camera](Self::shadow_camera).
+    fn shadow_views(light: &Light, world_matrix: &Matrix4, camera: &Camera) -> Vec<ShadowView> {
+        let shadow = &light.shadow;
+
+        match light.kind {
+            LightKind::DirectionalLight if shadow.cascades > 1 => {
+                return Self::shadow_cascades(light, world_matrix, camera);
+            }
+            LightKind::PointLight { range, .. } => {
+                return vec![Self::point_shadow_view(light, world_matrix, range)];
+            }
+            _ => {}
+        }
+
+        Self::shadow_camera(light)
+            .map(|camera_kind| ShadowView {
+                view_projection_matrices: vec![
+                    Camera::compute_projection_matrix(&camera_kind, 1.0) * world_matrix.inverse(),
+                ],
+                bias: shadow.bias,
+                map_size: shadow.map_size.max(1),
+                far: f32::MAX,
+                blend_start: f32::MAX,
+                point: None,
+            })
+            .into_iter()
+            .collect()
+    }
+
+    /// Returns the view of the cube map of the specified point light's
+    /// shadow, which is drawn from the light's position through each face of
+    /// a cube around it.
+    fn point_shadow_view(light: &Light, world_matrix: &Matrix4, range: f32) -> ShadowView {
+        let shadow = &light.shadow;
+        let e = &world_matrix.elements;
+        let position = [e[12], e[13], e[14]];
+        let far = if range > 0.0 { range } else { shadow.far };
+
+        let projection_matrix = Camera::compute_projection_matrix(
+            &CameraKind::PerspectiveCamera {
+                vfov_radians: std::f32::consts::FRAC_PI_2,
+                aspect: 1.0,
+                near: shadow.near,
+                far,
+            },
+            1.0,
+        );
+
+        // The right, up, and forward directions of the view of each face, in
+        // the order of the layers of a cube map. Cube maps are sampled in a
+        // left-handed coordinate system, so the views are mirrored.
+        let faces = [
+            ([0.0, 0.0, -1.0], [0.0, 1.0, 0.0], [1.0, 0.0, 0.0]),
+            ([0.0, 0.0, 1.0], [0.0, 1.0, 0.0], [-1.0, 0.0, 0.0]),
+            ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]),
+            ([1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, -1.0, 0.0]),
+            ([1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]),
+            ([-1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, -1.0]),
+        ];
+
+        let view_projection_matrices = faces
+            .iter()
+            .map(|&(right, up, forward)| {
+                let view_matrix = Matrix4 {
+                    elements: [
+                        right[0],
+                        up[0],
+                        -forward[0],
+                        0.0,
+                        right[1],
+                        up[1],
+                        -forward[1],
+                        0.0,
+                        right[2],
+                        up[2],
+                        -forward[2],
+                        0.0,
+                        -dot(right, position),
+                        -dot(up, position),
+                        dot(forward, position),
+                        1.0,
+                    ],
+                };
+
+                projection_matrix * view_matrix
+            })
+            .collect();
+
+        ShadowView {
+            view_projection_matrices,
+            bias: shadow.bias,
+            map_size: shadow.map_size.max(1),
+            far: f32::MAX,
+            blend_start: f32::MAX,
+            point: Some((position, far)),
+        }
+    }
+
+    /// Splits the frustum of the specified camera into the cascades of the
+    /// specified directional light's shadow, and returns an orthographic view
+    /// of the light fitted around each cascade.
+    fn shadow_cascades(light: &Light, world_matrix: &Matrix4, camera: &Camera) -> Vec<ShadowView> {
+        let shadow = &light.shadow;
+        let map_size = shadow.map_size.max(1);
+        let count = shadow.cascades as usize;
+
+        let (near, far) = camera.near_far();
+        let far = far.min(shadow.cascade_distance).max(near);
+        let lambda = shadow.cascade_split_lambda.clamp(0.0, 1.0);
+
+        // The practical split scheme mixes uniform splits with logarithmic
+        // ones, which need a positive near plane.
+        let splits: Vec<f32> = (0..=count)
+            .map(|i| {
+                let fraction = i as f32 / count as f32;
+                let uniform = near + (far - near) * fraction;
+                let logarithmic = if near > 0.0 {
+                    near * (far / near).powf(fraction)
+                } else {
+                    uniform
+                };
+
+                uniform + lambda * (logarithmic - uniform)
+            })
+            .collect();
+
+        let light_view_matrix = world_matrix.inverse();
+        let mut start = near;
+
+        splits
+            .windows(2)
+            .map(|split| {
+                let (cascade_near, cascade_far) = (split[0], split[1]);
+                let blend_start = cascade_far
+                    - (cascade_far - cascade_near) * shadow.cascade_blend.clamp(0.0, 1.0);
+
+                let corners = camera
+                    .frustum_corners(start, cascade_far)
+                    .map(|corner| transform_point(&light_view_matrix, to_array(&corner)));
+
+                // The next cascade also covers the part of this one that is
+                // blended with it.
+                start = blend_start;
+
+                // Fitting a sphere rather than a box keeps the area covered by
+                // the shadow map the same size as the camera rotates, and
+                // moving it in whole texels keeps the texels in place as the
+                // camera moves, which stops the edges of shadows from
+                // shimmering.
+                let center = [0, 1, 2].map(|i| corners.iter().map(|c| c[i]).sum::<f32>() / 8.0);
+                let radius = corners
+                    .iter()
+                    .map(|&corner| length(sub(corner, center)))
+                    .fold(0.0, f32::max);
+                let radius = (radius * 16.0).ceil() / 16.0;
+
+                let texel_size = 2.0 * radius / map_size as f32;
+                let [x, y] = [center[0], center[1]].map(|v| (v / texel_size).round() * texel_size);
+
+                // The light looks along -Z from in front of the cascade,
+                // regardless of where the light is placed, so that shadow
+                // casters between the light and the cascade are drawn too.
+                let eye = [
+                    center[0],
+                    center[1],
+                    center[2] + radius + shadow.cascade_caster_margin.max(0.0),
+                ];
+                #[rustfmt::skip]
+                let view_matrix = Matrix4 {
+                    elements: [
+                        1.0, 0.0, 0.0, 0.0,
+                        0.0, 1.0, 0.0, 0.0,
+                        0.0, 0.0, 1.0, 0.0,
+                        -eye[0], -eye[1], -eye[2], 1.0,
+                    ],
+                } * light_view_matrix;
+                let projection_matrix = Camera::compute_projection_matrix(
+                    &CameraKind::OrthographicCamera {
+                        left: x - eye[0] - radius,
+                        right: x - eye[0] + radius,
+                        top: y - eye[1] + radius,
+                        bottom: y - eye[1] - radius,
+                        near: 0.0,
+                        far: eye[2] - center[2] + radius,
+                    },
+                    1.0,
+                );
+
+                ShadowView {
+                    view_projection_matrices: vec![projection_matrix * view_matrix],
+                    bias: shadow.bias,
+                    map_size,
+                    far: cascade_far,
+                    blend_start,
+                    point: None,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the kind of camera through which the specified light sees its
+    /// shadow casters, or `None` if its shadow is not drawn through a single
+    /// camera. The camera is placed at the light's position and looks along
+    /// its direction.
+    fn shadow_camera(light: &Light) -> Option<CameraKind> {
+        let shadow = &light.shadow;
+
+        match light.kind {
+            LightKind::DirectionalLight => Some(CameraKind::OrthographicCamera {
+                left: -shadow.extent,
+                right: shadow.extent,
+                top: shadow.extent,
+                bottom: -shadow.extent,
+                near: shadow.near,
+                far: shadow.far,
+            }),
+            LightKind::SpotLight { range, angle, .. } => Some(CameraKind::PerspectiveCamera {
+                vfov_radians: 2.0 * angle,
+                aspect: 1.0,
+                near: shadow.near,
+                far: if range > 0.0 { range } else { shadow.far },
+            }),
+            _ => None,
+        }
+    }
+
+    /// Allocates the vertex and index data of the specified geometry in the
+    /// arenas and uploads it.
+    fn create_geometry_resources(&self, geometry: &BufferGeometry) -> GeometryResources {
+        let mut vertex_arena = self.vertex_arena.borrow_mut();
+        let mut allocate = |length: usize| {
+            vertex_arena
+                .allocate(
+                    &self.gpu.device,
+                    &self.gpu.queue,
+                    (length * size_of::<f32>()) as u64,
+                )
+                .0
+        };
+
+        let position = allocate(geometry.position.borrow().len());
+        let normal = allocate(geometry.normal.borrow().len());
+        let uv = allocate(geometry.uv.borrow().len());
+
+        drop(vertex_arena);
+
+        let indices = geometry.indices.borrow().as_ref().map(|indices| {
+            self.index_arena
+                .borrow_mut()
+                .allocate(
+                    &self.gpu.device,
+                    &self.gpu.queue,
+                    (indices.len() * size_of::<u32>()) as u64,
+                )
+                .0
+        });
+
+        let mut resources = GeometryResources {
+            position,
+            normal,
+            uv,
+            indices,
+            edges: None,
+            count: 0,
+            version: geometry.version(),
+        };
+
+        self.write_geometry_resources(geometry, &mut resources);
+
+        resources
+    }
+
+    /// Uploads the data of the specified geometry into its existing
+    /// allocations, or into new allocations if the sizes of the data have
+    /// changed.
+    fn update_geometry_resources(
+        &self,
+        geometry: &BufferGeometry,
+        resources: &mut GeometryResources,
+    ) {
+        let fits = |allocation: &Allocation, length: usize| {
+            allocation.size == ((length * 4) as u64).max(4)
+        };
+
+        let same_size = fits(&resources.position, geometry.position.borrow().len())
+            && fits(&resources.normal, geometry.normal.borrow().len())
+            && fits(&resources.uv, geometry.uv.borrow().len())
+            && match (&resources.indices, geometry.indices.borrow().as_ref()) {
+                (Some(allocation), Some(indices)) => fits(allocation, indices.len()),
+                (None, None) => true,
+                _ => false,
+            };
+
+        if same_size {
+            self.write_geometry_resources(geometry, resources);
+            resources.version = geometry.version();
+
+            // The edges are generated again the next time they are drawn.
+            if let Some((edges, _)) = resources.edges.take() {
+                self.index_arena.borrow_mut().free(edges);
+            }
+        } else {
+            self.free_geometry_resources(resources);
+            *resources = self.create_geometry_resources(geometry);
+        }
+    }
+
+    /// Writes the data of the specified geometry into the allocations, which
+    /// must have the sizes of the data.
+    fn write_geometry_resources(
+        &self,
+        geometry: &BufferGeometry,
+        resources: &mut GeometryResources,
+    ) {
+        let vertex_arena = self.vertex_arena.borrow();
+        let position = geometry.position.borrow();
+
+        for (allocation, data) in [
+            (&resources.position, &*position),
+            (&resources.normal, &*geometry.normal.borrow()),
+            (&resources.uv, &*geometry.uv.borrow()),
+        ] {
+            self.gpu.queue.write_buffer(
+                vertex_arena.buffer(),
+                allocation.offset,
+                bytemuck::cast_slice(data),
+            );
+        }
+
+        resources.count = (position.len() / 3) as u32;
+
+        if let (Some(allocation), Some(indices)) =
+            (&resources.indices, geometry.indices.borrow().as_ref())
+        {
+            self.gpu.queue.write_buffer(
+                self.index_arena.borrow().buffer(),
+                allocation.offset,
+                bytemuck::cast_slice(indices),
+            );
+
+            resources.count = indices.len() as u32;
+        }
+    }
+
+    /// Returns the allocations of a geometry to the arenas.
+    fn free_geometry_resources(&self, resources: &GeometryResources) {
+        let mut vertex_arena = self.vertex_arena.borrow_mut();
+
+        vertex_arena.free(resources.position);
+        vertex_arena.free(resources.normal);
+        vertex_arena.free(resources.uv);
+
+        if let Some(indices) = resources.indices {
+            self.index_arena.borrow_mut().free(indices);
+        }
+
+        if let Some((edges, _)) = resources.edges {
+            self.index_arena.borrow_mut().free(edges);
+        }
+    }
+
+    /// Returns the size of the uniform buffer of the specified material.
+    fn uniform_size(material: &dyn Material) -> u64 {
+        // Uniform buffer bindings must not be empty.
+        (material.uniform().len() as u64).max(16)
+    }
+
+    /// Creates the uniform buffer and bind group of the specified material.
+    fn create_material_resources(
+        &self,
+        material: &dyn Material,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        textures: &[Rc<Texture>],
+    ) -> MaterialResources {
+        let uniform = material.uniform();
+
+        let uniform_buffer = self.gpu.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Material uniform buffer"),
+            size: Self::uniform_size(material),
+            usage: wgpu::BufferUsages::UNIFORM.union(wgpu::BufferUsages::COPY_DST),
+            mapped_at_creation: false,
+        });
+
+        self.gpu.queue.write_buffer(&uniform_buffer, 0, &uniform);
+
+        let bind_group =
+            self.create_material_bind_group(bind_group_layout, &uniform_buffer, textures);
+
+        MaterialResources {
+            uniform_buffer,
+            bind_group,
+            uniform,
+            textures: textures.iter().map(Rc::downgrade).collect(),
+        }
+    }
+
+    /// Creates the bind group of a material with the specified uniform buffer
+    /// and textures, uploading the textures to the GPU if they have not been
+    /// uploaded yet.
+    fn create_material_bind_group(
+        &self,
+        bind_group_layout: &wgpu::BindGroupLayout,
+        uniform_buffer: &wgpu::Buffer,
+        textures: &[Rc<Texture>],
+    ) -> wgpu::BindGroup {
+        let mut texture_cache = self.textures.borrow_mut();
+
+        for texture in textures {
+            texture_cache.get_or_insert_with(texture, || self.create_texture_resources(texture));
+        }
+
+        let texture_resources: Vec<&TextureResources> = textures
+            .iter()
+            .map(|texture| texture_cache.get(texture).unwrap())
+            .collect();
+
+        let mut entries = vec![wgpu::BindGroupEntry {
+            binding: 0,
+            resource: uniform_buffer.as_entire_binding(),
+        }];
+
+        for (i, resources) in texture_resources.iter().enumerate() {
+            entries.push(wgpu::BindGroupEntry {
+                binding: 1 + 2 * i as u32,
+                resource: wgpu::BindingResource::TextureView(&resources.view),
+            });
+
+            entries.push(wgpu::BindGroupEntry {
+                binding: 2 + 2 * i as u32,
+                resource: wgpu::BindingResource::Sampler(&resources.sampler),
+            });
+        }
+
+        self.gpu
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout: bind_group_layout,
+                entries: &entries,
+            })
+    }
+
+    /// Uploads the specified texture and its mipmaps to the GPU and creates
+    /// its sampler.
+    fn create_texture_resources(&self, texture: &Texture) -> TextureResources {
+        let gpu_texture = self.upload_texture("Material texture", &[texture]);
+
+        TextureResources {
+            view: gpu_texture.create_view(&wgpu::TextureViewDescriptor::default()),
+            sampler: self.create_texture_sampler(texture),
+        }
+    }
+
+    /// Uploads the faces of the specified cube texture and their mipmaps to
+    /// the GPU and creates the sampler of the cube texture.
+    fn create_cube_texture_resources(&self, cube_texture: &CubeTexture) -> TextureResources {
+        // The +Z and -Z faces are swapped since cube maps are sampled in a
+        // left-handed space, where the Z axis is mirrored.
+        let faces = [0, 1, 2, 3, 5, 4].map(|i| &cube_texture.faces()[i]);
+        let gpu_texture = self.upload_texture("Cube texture", &faces);
+
+        TextureResources {
+            view: gpu_texture.create_view(&wgpu::TextureViewDescriptor {
+                dimension: Some(wgpu::TextureViewDimension::Cube),
+                ..Default::default()
+            }),
+            sampler: self.create_texture_sampler(&cube_texture.faces()[0]),
+        }
+    }
+
+    /// Uploads the specified images and their mipmaps to the GPU, each into a
+    /// layer of the same texture. The size, color space, and number of mipmaps
+    /// of the texture are those of the first image.
+    fn upload_texture(&self, label: &str, images: &[&Texture]) -> wgpu::Texture {
+        let texture = images[0];
+        let size = wgpu::Extent3d {
+            width: texture.width,
+            height: texture.height,
+            depth_or_array_layers: images.len() as u32,
+        };
+
+        let gpu_texture = self.gpu.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size,
+            mip_level_count: texture.mip_level_count(),
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: if texture.srgb {
+                wgpu::TextureFormat::Rgba8UnormSrgb
+            } else {
+                wgpu::TextureFormat::Rgba8Unorm
+            },
+            usage: wgpu::TextureUsages::TEXTURE_BINDING.union(wgpu::TextureUsages::COPY_DST),
+            view_formats: &[],
+        });
+
+        for (layer, image) in images.iter().enumerate() {
+            for (mip_level, data) in image.mip_levels().iter().enumerate() {
+                let mip_size = wgpu::Extent3d {
+                    depth_or_array_layers: 1,
+                    ..size.mip_level_size(mip_level as u32, wgpu::TextureDimension::D2)
+                };
+
+                self.gpu.queue.write_texture(
+                    wgpu::ImageCopyTexture {
+                        texture: &gpu_texture,
+                        mip_level: mip_level as u32,
+                        origin: wgpu::Origin3d {
+                            x: 0,
+                            y: 0,
+                            z: layer as u32,
+                        },
+                        aspect: wgpu::TextureAspect::All,
+                    },
+                    data,
+                    wgpu::ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(4 * mip_size.width),
+                        rows_per_image: Some(mip_size.height),
+                    },
+                    mip_size,
+                );
+            }
+        }
+
+        gpu_texture
+    }
+
+    /// Creates a sampler with the sampling configuration of the specified
+    /// texture.
+    fn create_texture_sampler(&self, texture: &Texture) -> wgpu::Sampler {
+        self.gpu.device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Material texture sampler"),
+            address_mode_u: texture.sampler.wrap_u,
+            address_mode_v: texture.sampler.wrap_v,
+            mag_filter: texture.sampler.mag_filter,
+            min_filter: texture.sampler.min_filter,
+            mipmap_filter: texture.sampler.mipmap_filter,
+            ..Default::default()
+        })
+    }
+
+    /// Reconfigures the renderer to render to the specified size, and
+    /// recreates the depth and multisampled attachments with that size. Note
+    /// that this does not resize the window.
+    pub fn set_size(&mut self, width: u32, height: u32) {
+        self.gpu.set_size(width, height);
+        self.depth_texture = Self::create_depth_texture(&self.gpu, self.sample_count);
+        self.multisampled_texture = Self::create_multisampled_texture(&self.gpu, self.sample_count);
+        self.hdr_texture = Self::create_hdr_texture(&self.gpu);
+        self.output_pass.input_bind_group = Self::create_output_input_bind_group(
+            &self.gpu,
+            &self.output_pass.input_bind_group_layout,
+            &self.output_pass.sampler,
+            &self.hdr_texture,
+        );
+        *self.picking_targets.get_mut() = None;
+    }
+
+    /// Returns statistics about the last rendered frame, such as the number of
+    /// meshes that were culled.
+    pub fn info(&self) -> RenderInfo {
+        *self.info.borrow()
+    }
+
+    /// Returns the depth attachment of the last rendered frame, or `None` if it
+    /// cannot be bound as a texture.
+    pub(super) fn scene_depth_texture(&self) -> Option<&wgpu::Texture> {
+        Self::depth_texture_bindable(&self.gpu, self.sample_count).then_some(&self.depth_texture)
+    }
+
+    /// Returns the number of samples per pixel, which is lower than
+    /// [`ForwardRendererOptions::sample_count`] if the device does not support
+    /// that many.
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Renders the specified scene as viewed from the specified camera to the
+    /// window, or to the offscreen texture if the renderer is headless.
+    pub fn render(
+        &self,
+        scene: Rc<Object3d>,
+        camera: &Camera,
+    ) -> Result<(), wgpu::SurfaceError> {
+        self.draw_to_frame(|texture_view| self.draw(scene, camera, texture_view))
+    }
+
+    /// Calls `draw` with the view of the next frame of the window, and
+    /// presents the frame.
+    pub(super) fn draw_to_frame(
+        &self,
+        draw: impl FnOnce(&wgpu::TextureView),
+    ) -> Result<(), wgpu::SurfaceError> {
+        let frame = self.gpu.frame()?;
+        let texture_view = frame
+            .texture()
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        draw(&texture_view);
+
+        frame.present();
+
+        Ok(())
+    }
+
+    /// Renders the specified scene as viewed from the specified camera and
+    /// returns the pixels of the frame in RGBA8 format, row by row from top to
+    /// bottom.
+    ///
+    /// A headless renderer renders into its offscreen texture. A renderer with
+    /// a window renders into a temporary texture of the same size, leaving the
+    /// window untouched.
+    ///
+    /// This method waits for the GPU to finish rendering, so it cannot be used
+    /// on the Web.
+    pub fn render_to_image(
+        &self,
+        scene: Rc<Object3d>,
+        camera: &Camera,
+    ) -> Result<Vec<u8>, wgpu::BufferAsyncError> {
+        self.draw_to_image(|texture_view| self.draw(scene, camera, texture_view))
+    }
+
+    /// Calls `draw` with the view of a texture of the renderer's size, and
+    /// reads the texture back. See [`render_to_image`](Self::render_to_image).
+    pub(super) fn draw_to_image(
+        &self,
+        draw: impl FnOnce(&wgpu::TextureView),
+    ) -> Result<Vec<u8>, wgpu::BufferAsyncError> {
+        let image_texture;
+
+        let texture = match &self.gpu.texture {
+            Some(texture) => texture,
+            None => {
+                image_texture = self.gpu.device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some("Image texture"),
+                    size: wgpu::Extent3d {
+                        width: self.gpu.size.0,
+                        height: self.gpu.size.1,
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: self.gpu.surface_configuration.format,
+                    usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                        .union(wgpu::TextureUsages::COPY_SRC),
+                    view_formats: &[],
+                });
+
+                &image_texture
+            }
+        };
+
+        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        draw(&texture_view);
+
+        let mut pixels = self.gpu.read_texture(texture)?;
+
+        // Surfaces commonly use a BGRA format, so swap the channels into RGBA.
+        if matches!(
+            texture.format(),
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        ) {
+            for pixel in pixels.chunks_exact_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+
+        Ok(pixels)
+    }
+
+    /// Finds the mesh that is visible at the specified pixel when the
+    /// specified scene is viewed from the specified camera, by rendering the
+    /// pick IDs of the meshes into an integer texture and reading the pixel
+    /// back. Unlike a [`Raycaster`](crate::Raycaster), the cost does not
+    /// depend on the number of triangles of the meshes. Lines and points are
+    /// not picked and do not hide the meshes behind them.
+    ///
+    /// * `x`, `y`: The pixel, from the top-left corner of the renderer.
+    ///
+    /// # Returns
+    ///
+    /// The mesh and the triangle at the pixel, or `None` if there is no mesh
+    /// at the pixel or the pixel is outside of the renderer.
+    ///
+    /// This method waits for the GPU to finish rendering, so it cannot be used
+    /// on the Web.
+    pub fn pick(
+        &self,
+        scene: Rc<Object3d>,
+        camera: &Camera,
+        x: u32,
+        y: u32,
+    ) -> Result<Option<PickResult>, wgpu::BufferAsyncError> {
+        let PickIds { meshes, pixels } = self.render_pick_ids(&scene, camera, (x, y), (1, 1))?;
+
+        Ok(pixels.first().and_then(|&[pick_id, face_index]| {
+            Some(PickResult {
+                object: Rc::clone(meshes.get((pick_id as usize).checked_sub(1)?)?),
+                face_index: (face_index != u32::MAX).then_some(face_index as usize),
+            })
+        }))
+    }
+
+    /// Finds the meshes that are visible inside the specified rectangle when
+    /// the specified scene is viewed from the specified camera. See
+    /// [`pick`](Self::pick).
+    ///
+    /// * `x`, `y`: The top-left corner of the rectangle in pixels, from the
+    ///   top-left corner of the renderer.
+    /// * `width`, `height`: The size of the rectangle in pixels. The rectangle
+    ///   is clipped to the size of the renderer.
+    ///
+    /// # Returns
+    ///
+    /// The distinct meshes that cover at least one pixel of the rectangle, in
+    /// the order in which they are found row by row.
+    pub fn pick_rect(
+        &self,
+        scene: Rc<Object3d>,
+        camera: &Camera,
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    ) -> Result<Vec<Rc<Object3d>>, wgpu::BufferAsyncError> {
+        let PickIds { meshes, pixels } =
+            self.render_pick_ids(&scene, camera, (x, y), (width, height))?;
+        let mut picked = vec![false; meshes.len()];
+        let mut objects = Vec::new();
+
+        for [pick_id, _] in pixels {
+            if let Some(index) = (pick_id as usize).checked_sub(1) {
+                if index < meshes.len() && !picked[index] {
+                    picked[index] = true;
+                    objects.push(Rc::clone(&meshes[index]));
+                }
+            }
+        }
+
+        Ok(objects)
+    }
+
+    /// Renders the picking pass of the specified scene inside the specified
+    /// rectangle and reads the rectangle back.
+    ///
+    /// The rectangle is clipped to the size of the renderer.
+    fn render_pick_ids(
+        &self,
+        scene: &Rc<Object3d>,
+        camera: &Camera,
+        origin: (u32, u32),
+        size: (u32, u32),
+    ) -> Result<PickIds, wgpu::BufferAsyncError> {
+        let width = size.0.min(self.gpu.size.0.saturating_sub(origin.0));
+        let height = size.1.min(self.gpu.size.1.saturating_sub(origin.1));
+
+        if width == 0 || height == 0 {
+            return Ok(PickIds {
+                meshes: Vec::new(),
+                pixels: Vec::new(),
+            });
+        }
+
+        let (meshes, _) = self.prepare_meshes(scene, camera);
+
+        let mut picking_pipeline = self.picking_pipeline.borrow_mut();
+        let picking_pipeline =
+            picking_pipeline.get_or_insert_with(|| self.create_picking_pipeline());
+
+        let mut picking_targets = self.picking_targets.borrow_mut();
+        let picking_targets = picking_targets.get_or_insert_with(|| self.create_picking_targets());
+
+        let mut encoder = self
+            .gpu
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Picking pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &picking_targets
+                    .color
+                    .create_view(&wgpu::TextureViewDescriptor::default()),
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    // The background has the pick ID zero.
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &picking_targets
+                    .depth
+                    .create_view(&wgpu::TextureViewDescriptor::default()),
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Store,
+                }),
+                stencil_ops: None,
+            }),
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+
+        // Only the pixels that are read back need to be rasterized.
+        render_pass.set_scissor_rect(origin.0, origin.1, width, height);
+        render_pass.set_pipeline(picking_pipeline);
+
+        let object_bind_group = self.object_bind_group.borrow();
+        let objects = self.objects.borrow();
+
+        // Only meshes are picked, including the faces of wireframes.
+        for object in &meshes {
+            if let Some((geometry, _, Primitive::Triangles | Primitive::Wireframe)) =
+                Self::geometry_and_material(object)
+            {
+                let slot = objects.get(object).unwrap();
+
+                render_pass.set_bind_group(
+                    0,
+                    object_bind_group.as_ref().unwrap(),
+                    &[slot.offset as u32],
+                );
+
+                let instances = self.set_instance_buffer(&mut render_pass, object);
+
+                self.draw_geometry(&mut render_pass, geometry, Primitive::Triangles, instances);
+            }
         }
-    }
 
-    /// Reconfigures the renderer to render to the specified size. Note that
-    /// this does not resize the window.
-    pub fn set_size(&mut self, width: u32, height: u32) {
-        self.gpu.set_size(width, height);
-        self.depth_texture = self.gpu.device.create_texture(&wgpu::TextureDescriptor {
-            label: None,
-            size: wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Depth24Plus,
-            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
-            view_formats: &[],
-        });
-    }
+        drop(render_pass);
 
-    /// Renders the specified scene as viewed from the specified camera to the
-    /// window.
-    pub fn render(
-        &mut self,
-        scene: Rc<Object3d>,
-        camera: &Camera,
-    ) -> Result<(), wgpu::SurfaceError> {
-        let output = self.gpu.surface.get_current_texture()?;
-        let texture_view = output
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+        self.gpu.queue.submit(std::iter::once(encoder.finish()));
+
+        let data = self
+            .gpu
+            .read_texture_region(&picking_targets.color, origin, (width, height))?;
 
+        let pixels = data
+            .chunks_exact(8)
+            .map(|pixel| [0, 4].map(|i| u32::from_ne_bytes(pixel[i..i + 4].try_into().unwrap())))
+            .collect();
+
+        Ok(PickIds { meshes, pixels })
+    }
+
+    /// Writes the camera uniforms, and the per-mesh uniforms and instances of
+    /// the visible meshes in the specified scene that are inside the camera's
+    /// frustum.
+    ///
+    /// # Returns
+    ///
+    /// The meshes to draw, and the number of visible meshes that were culled.
+    fn prepare_meshes(&self, scene: &Rc<Object3d>, camera: &Camera) -> (Vec<Rc<Object3d>>, usize) {
         self.gpu.queue.write_buffer(
             &self.projection_matrix_buffer,
             0,
             bytemuck::cast_slice(&[camera.projection_matrix]),
         );
 
-        let view_matrix = Matrix4::look_at(
-            &camera.position,
-            &(0.0, 0.0, 0.0).into(),
-            &(0.0, 1.0, 0.0).into(),
-        );
+        let view_matrix = camera.view_matrix;
 
         self.gpu.queue.write_buffer(
             &self.view_matrix_buffer,
@@ -400,61 +3065,248 @@ impl<'window> ForwardRenderer<'window> {
             bytemuck::cast_slice(&[view_matrix]),
         );
 
-        // web_sys::console::log_5(
-        //     &"1: ".into(),
-        //     &camera.view_matrix.elements[0].into(),
-        //     &camera.view_matrix.elements[4].into(),
-        //     &camera.view_matrix.elements[8].into(),
-        //     &camera.view_matrix.elements[12].into(),
-        // );
-        //
-        // web_sys::console::log_5(
-        //     &"2: ".into(),
-        //     &camera.view_matrix.elements[1].into(),
-        //     &camera.view_matrix.elements[5].into(),
-        //     &camera.view_matrix.elements[9].into(),
-        //     &camera.view_matrix.elements[13].into(),
-        // );
-        //
-        // web_sys::console::log_5(
-        //     &"3: ".into(),
-        //     &camera.view_matrix.elements[2].into(),
-        //     &camera.view_matrix.elements[6].into(),
-        //     &camera.view_matrix.elements[10].into(),
-        //     &camera.view_matrix.elements[14].into(),
-        // );
-        //
-        // web_sys::console::log_5(
-        //     &"4: ".into(),
-        //     &camera.view_matrix.elements[3].into(),
-        //     &camera.view_matrix.elements[7].into(),
-        //     &camera.view_matrix.elements[11].into(),
-        //     &camera.view_matrix.elements[15].into(),
-        // );
-
         self.gpu.queue.write_buffer(
             &self.camera_position_buffer,
             0,
             bytemuck::cast_slice(&[camera.position]),
         );
 
+        let frustum = Frustum::from_camera(camera);
+        let (meshes, culled): (Vec<_>, Vec<_>) = Self::visible_meshes(scene)
+            .into_iter()
+            .partition(|object| Self::is_in_frustum(object, &frustum));
+
+        self.update_object_uniforms(&meshes, &view_matrix, 1);
+        self.update_instances(&meshes);
+
+        (meshes, culled.len())
+    }
+
+    /// Returns the visible meshes in the specified scene that cast shadows.
+    /// The per-mesh uniforms and instances of those that are not among the
+    /// specified meshes, which were prepared by
+    /// [`prepare_meshes`](Self::prepare_meshes), are written too, since meshes
+    /// outside of the camera's frustum can cast shadows into it.
+    fn prepare_shadow_casters(
+        &self,
+        scene: &Rc<Object3d>,
+        camera: &Camera,
+        meshes: &[Rc<Object3d>],
+    ) -> Vec<Rc<Object3d>> {
+        let prepared: HashSet<*const Object3d> = meshes.iter().map(Rc::as_ptr).collect();
+        let (mut shadow_casters, culled): (Vec<_>, Vec<_>) = Self::visible_meshes(scene)
+            .into_iter()
+            .filter(|object| Self::casts_shadow(object))
+            .partition(|object| prepared.contains(&Rc::as_ptr(object)));
+
+        // Culled meshes are never picked, so their pick IDs follow those of
+        // the prepared meshes.
+        self.update_object_uniforms(&culled, &camera.view_matrix, meshes.len() as u32 + 1);
+        self.update_instances(&culled);
+
+        shadow_casters.extend(culled);
+
+        shadow_casters
+    }
+
+    /// Returns whether the specified object is a mesh or an instanced mesh
+    /// that casts shadows.
+    fn casts_shadow(object: &Object3d) -> bool {
+        *object.cast_shadow.borrow()
+            && matches!(
+                Self::geometry_and_material(object),
+                Some((_, _, Primitive::Triangles | Primitive::Wireframe))
+            )
+    }
+
+    /// Uploads the instances of the instanced meshes among the specified
+    /// meshes. Only the instances that have been modified since the last
+    /// upload are written, unless the number of instances has changed.
+    fn update_instances(&self, meshes: &[Rc<Object3d>]) {
+        let mut instances = self.instances.borrow_mut();
+
+        for object in meshes {
+            let Object3dKind::InstancedMesh(instanced_mesh) = &object.kind else {
+                continue;
+            };
+
+            let count = instanced_mesh.count() as u32;
+            let mut created = false;
+            let resources = instances.get_or_insert_with(object, || {
+                created = true;
+
+                self.create_instance_resources(count)
+            });
+
+            let updated_range = instanced_mesh.take_updated_range();
+
+            let range = if created {
+                0..count as usize
+            } else if resources.count != count {
+                self.vertex_arena.borrow_mut().free(resources.allocation);
+                *resources = self.create_instance_resources(count);
+
+                0..count as usize
+            } else if let Some(range) = updated_range {
+                range
+            } else {
+                continue;
+            };
+
+            self.gpu.queue.write_buffer(
+                self.vertex_arena.borrow().buffer(),
+                resources.allocation.offset + (range.start * size_of::<Instance>()) as u64,
+                bytemuck::cast_slice(&instanced_mesh.instances()[range]),
+            );
+        }
+    }
+
+    /// Allocates the per-instance data of an instanced mesh with the
+    /// specified number of instances in the vertex arena.
+    fn create_instance_resources(&self, count: u32) -> InstanceResources {
+        let (allocation, _) = self.vertex_arena.borrow_mut().allocate(
+            &self.gpu.device,
+            &self.gpu.queue,
+            count as u64 * size_of::<Instance>() as u64,
+        );
+
+        InstanceResources { allocation, count }
+    }
+
+    /// Returns the specified meshes in drawing order: opaque meshes from front
+    /// to back, so that the depth test discards the hidden fragments early,
+    /// followed by transparent meshes from back to front, so that each is
+    /// blended over the meshes behind it.
+    fn sort_meshes(meshes: Vec<Rc<Object3d>>, view_matrix: &Matrix4) -> Vec<Rc<Object3d>> {
+        let (mut opaque, mut transparent): (Vec<_>, Vec<_>) = meshes
+            .into_iter()
+            .map(|object| (Self::view_depth(&object, view_matrix), object))
+            .partition(|(_, object)| match Self::geometry_and_material(object) {
+                Some((_, material, _)) => !material.settings().transparent,
+                None => true,
+            });
+
+        opaque.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        transparent.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        opaque
+            .into_iter()
+            .chain(transparent)
+            .map(|(_, object)| object)
+            .collect()
+    }
+
+    /// Returns the depth of the specified mesh in view space, which is the
+    /// distance from the camera to the center of the mesh's bounding sphere
+    /// along the camera's view direction.
+    fn view_depth(object: &Object3d, view_matrix: &Matrix4) -> f32 {
+        let center = Self::bounding_sphere(object)
+            .map(|bounding_sphere| bounding_sphere.center)
+            .unwrap_or_default();
+
+        let world_center = transform_point(&object.world_matrix.borrow(), to_array(&center));
+
+        // The camera looks along the -Z axis of the view space.
+        -transform_point(view_matrix, world_center)[2]
+    }
+
+    /// Creates the render pipelines that the materials of the specified meshes
+    /// use, if they do not exist yet.
+    fn create_pipelines(&self, meshes: &[Rc<Object3d>]) {
+        let mut pipelines = self.pipelines.borrow_mut();
+
+        for object in meshes {
+            if let Some((_, material, primitive)) = Self::geometry_and_material(object) {
+                let material = material.as_ref();
+                let material_pipeline = pipelines
+                    .entry(material.material_type_id())
+                    .or_insert_with(|| self.create_pipeline(material));
+                let blending = Self::blending(material);
+
+                if !material_pipeline
+                    .pipelines
+                    .contains_key(&(primitive, blending))
+                {
+                    let pipeline =
+                        self.create_render_pipeline(material_pipeline, primitive, blending);
+
+                    material_pipeline
+                        .pipelines
+                        .insert((primitive, blending), pipeline);
+                }
+            }
+        }
+    }
+
+    /// Records and submits the commands to draw the specified scene as viewed
+    /// from the specified camera into the specified texture view.
+    pub(super) fn draw(
+        &self,
+        scene: Rc<Object3d>,
+        camera: &Camera,
+        texture_view: &wgpu::TextureView,
+    ) {
+        let shadow_passes = self.update_lights(&scene, camera);
+        let (meshes, culled) = self.prepare_meshes(&scene, camera);
+
+        let shadow_casters = if shadow_passes.is_empty() {
+            Vec::new()
+        } else {
+            self.prepare_shadow_casters(&scene, camera, &meshes)
+        };
+
+        let meshes = Self::sort_meshes(meshes, &camera.view_matrix);
+
+        self.create_pipelines(&meshes);
+
+        *self.info.borrow_mut() = RenderInfo {
+            rendered: meshes.len(),
+            culled,
+        };
+
         let mut encoder = self
             .gpu
             .device
             .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
 
+        self.record_shadow_passes(&mut encoder, &shadow_passes, &shadow_casters);
+
+        // With multisampling, the scene is drawn into the multisampled texture,
+        // which is then resolved into the HDR texture.
+        let hdr_view = self
+            .hdr_texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let multisampled_view = self
+            .multisampled_texture
+            .as_ref()
+            .map(|texture| texture.create_view(&wgpu::TextureViewDescriptor::default()));
+
+        let color_view = multisampled_view.as_ref().unwrap_or(&hdr_view);
+
+        let load = if self.record_background_pass(&mut encoder, camera, color_view) {
+            wgpu::LoadOp::Load
+        } else {
+            let (color, alpha) = match &self.background {
+                Background::Color(color) => (color, 1.0),
+                _ => (&self.clear_color, self.clear_alpha),
+            };
+            let color = srgb_to_linear(color);
+
+            wgpu::LoadOp::Clear(wgpu::Color {
+                r: color.r,
+                g: color.g,
+                b: color.b,
+                a: alpha,
+            })
+        };
+
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: None,
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &texture_view,
-                resolve_target: None,
+                view: color_view,
+                resolve_target: multisampled_view.as_ref().map(|_| &hdr_view),
                 ops: wgpu::Operations {
-                    load: wgpu::LoadOp::Clear(wgpu::Color {
-                        r: self.clear_color.r,
-                        g: self.clear_color.g,
-                        b: self.clear_color.b,
-                        a: self.clear_alpha,
-                    }),
+                    load,
                     store: wgpu::StoreOp::Store,
                 },
             })],
@@ -472,167 +3324,550 @@ impl<'window> ForwardRenderer<'window> {
             timestamp_writes: None,
         });
 
-        render_pass.set_vertex_buffer(0, self.position_buffer.slice(..));
-        render_pass.set_vertex_buffer(1, self.normal_buffer.slice(..));
-        render_pass.set_vertex_buffer(2, self.uv_buffer.slice(..));
-        render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(2, &self.lights.borrow().bind_group, &[]);
 
-        let mut stack: Vec<Rc<Object3d>> = Vec::new();
-        let mut mesh_index = 0;
-        let mut index_start = 0;
-        let mut base_vertex = 0;
+        let object_bind_group = self.object_bind_group.borrow();
+        let objects = self.objects.borrow_mut();
+        let pipelines = self.pipelines.borrow();
+        let mut current_pipeline = None;
 
-        stack.push(scene);
+        for object in &meshes {
+            if let Some((geometry, material, primitive)) = Self::geometry_and_material(object) {
+                let material_type_id = material.material_type_id();
+                let material_pipeline = &pipelines[&material_type_id];
+                let blending = Self::blending(material.as_ref());
 
-        while let Some(object) = stack.pop() {
-            if !*object.visible.borrow() {
-                continue;
-            }
+                if current_pipeline != Some((material_type_id, primitive, blending)) {
+                    render_pass.set_pipeline(&material_pipeline.pipelines[&(primitive, blending)]);
+                    current_pipeline = Some((material_type_id, primitive, blending));
+                }
 
-            if let Object3dKind::Mesh(mesh) = &object.kind {
-                let mut mut_bind_group = mesh.as_ref().bind_group.borrow_mut();
-
-                let bind_group = &*mut_bind_group.get_or_insert(self.gpu.device.create_bind_group(
-                    &wgpu::BindGroupDescriptor {
-                        label: None,
-                        layout: &self.bind_group_layout,
-                        entries: &[
-                            // Model matrix
-                            wgpu::BindGroupEntry {
-                                binding: 0,
-                                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                                    buffer: &self.model_matrix_buffer,
-                                    offset: mesh_index * Self::OFFSET,
-                                    size: NonZero::new(size_of::<Matrix4>() as u64),
-                                }),
-                            },
-                            // Model-view matrix
-                            wgpu::BindGroupEntry {
-                                binding: 1,
-                                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                                    buffer: &self.model_view_matrix_buffer,
-                                    offset: mesh_index * Self::OFFSET,
-                                    size: NonZero::new(size_of::<Matrix4>() as u64),
-                                }),
-                            },
-                            // Projection matrix
-                            wgpu::BindGroupEntry {
-                                binding: 2,
-                                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                                    buffer: &self.projection_matrix_buffer,
-                                    offset: 0,
-                                    size: NonZero::new(size_of::<Matrix4>() as u64),
-                                }),
-                            },
-                            // View matrix
-                            wgpu::BindGroupEntry {
-                                binding: 3,
-                                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                                    buffer: &self.view_matrix_buffer,
-                                    offset: 0,
-                                    size: NonZero::new(size_of::<Matrix4>() as u64),
-                                }),
-                            },
-                            // Normal matrix
-                            wgpu::BindGroupEntry {
-                                binding: 4,
-                                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                                    buffer: &self.normal_matrix_buffer,
-                                    offset: mesh_index * Self::OFFSET,
-                                    // WebGPU requires 48 bytes minimum.
-                                    // TODO: investigate the spec.
-                                    size: NonZero::new(size_of::<Matrix4>() as u64),
-                                }),
-                            },
-                            // Camera position
-                            wgpu::BindGroupEntry {
-                                binding: 5,
-                                resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
-                                    buffer: &self.camera_position_buffer,
-                                    offset: 0,
-                                    size: NonZero::new(size_of::<Vector3>() as u64),
-                                }),
-                            },
-                        ],
-                    },
-                ));
+                let textures: Vec<Rc<Texture>> = material
+                    .textures()
+                    .into_iter()
+                    .map(|texture| texture.unwrap_or_else(|| Rc::clone(&self.default_texture)))
+                    .collect();
 
-                self.gpu.queue.write_buffer(
-                    &self.position_buffer,
-                    base_vertex * 12,
-                    bytemuck::cast_slice(mesh.geometry.position.as_ref()),
-                );
+                let mut materials = self.materials.borrow_mut();
+                let material_resources = materials.get_or_insert_with(material, || {
+                    self.create_material_resources(
+                        material.as_ref(),
+                        &material_pipeline.bind_group_layout,
+                        &textures,
+                    )
+                });
 
-                self.gpu.queue.write_buffer(
-                    &self.normal_buffer,
-                    base_vertex * 12,
-                    bytemuck::cast_slice(mesh.geometry.normal.as_ref()),
-                );
+                // Textures are swapped by replacing them, so comparing their
+                // identities is enough to detect changes.
+                let textures_changed = material_resources.textures.len() != textures.len()
+                    || material_resources
+                        .textures
+                        .iter()
+                        .zip(&textures)
+                        .any(|(old, new)| Weak::as_ptr(old) != Rc::as_ptr(new));
 
-                self.gpu.queue.write_buffer(
-                    &self.uv_buffer,
-                    base_vertex * 8,
-                    bytemuck::cast_slice(mesh.geometry.uv.as_ref()),
+                if textures_changed {
+                    material_resources.bind_group = self.create_material_bind_group(
+                        &material_pipeline.bind_group_layout,
+                        &material_resources.uniform_buffer,
+                        &textures,
+                    );
+                    material_resources.textures = textures.iter().map(Rc::downgrade).collect();
+                }
+
+                let uniform = material.uniform();
+
+                if uniform != material_resources.uniform {
+                    self.gpu
+                        .queue
+                        .write_buffer(&material_resources.uniform_buffer, 0, &uniform);
+                    material_resources.uniform = uniform;
+                }
+
+                render_pass.set_bind_group(1, &material_resources.bind_group, &[]);
+
+                // Slots are aligned to the minimum uniform buffer offset
+                // alignment, which is far below 4 GiB.
+                let slot = objects.get(object).unwrap();
+
+                render_pass.set_bind_group(
+                    0,
+                    object_bind_group.as_ref().unwrap(),
+                    &[slot.offset as u32],
                 );
 
-                self.gpu.queue.write_buffer(
-                    &self.model_matrix_buffer,
-                    mesh_index * Self::OFFSET,
-                    bytemuck::cast_slice(&[*object.world_matrix.borrow()]),
+                let instances = self.set_instance_buffer(&mut render_pass, object);
+
+                self.draw_geometry(&mut render_pass, geometry, primitive, instances);
+            }
+        }
+
+        drop(render_pass);
+        drop(objects);
+        drop(pipelines);
+
+        self.record_output_pass(&mut encoder, texture_view);
+
+        self.gpu.queue.submit(std::iter::once(encoder.finish()));
+
+        self.materials.borrow_mut().prune();
+        self.textures.borrow_mut().prune();
+        self.cube_textures.borrow_mut().prune();
+
+        for resources in self.geometries.borrow_mut().prune() {
+            self.free_geometry_resources(&resources);
+        }
+
+        for slot in self.objects.borrow_mut().prune() {
+            self.object_arena.borrow_mut().free(slot);
+        }
+
+        for resources in self.instances.borrow_mut().prune() {
+            self.vertex_arena.borrow_mut().free(resources.allocation);
+        }
+    }
+
+    /// Records the specified shadow passes, which draw the specified shadow
+    /// casters into a layer of the shadow maps as seen from a light.
+    fn record_shadow_passes(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        shadow_passes: &[ShadowPass],
+        shadow_casters: &[Rc<Object3d>],
+    ) {
+        if shadow_passes.is_empty() {
+            return;
+        }
+
+        let mut shadow_pipeline = self.shadow_pipeline.borrow_mut();
+        let mut point_shadow_pipeline = self.point_shadow_pipeline.borrow_mut();
+
+        let lights = self.lights.borrow();
+        let object_bind_group = self.object_bind_group.borrow();
+        let objects = self.objects.borrow();
+
+        for (i, shadow_pass) in shadow_passes.iter().enumerate() {
+            let (shadow_map, pipeline) = if shadow_pass.point {
+                (
+                    &lights.point_shadow_map,
+                    &*point_shadow_pipeline
+                        .get_or_insert_with(|| self.create_shadow_pipeline(true)),
+                )
+            } else {
+                (
+                    &lights.shadow_map,
+                    &*shadow_pipeline.get_or_insert_with(|| self.create_shadow_pipeline(false)),
+                )
+            };
+
+            let view = shadow_map.create_view(&wgpu::TextureViewDescriptor {
+                dimension: Some(wgpu::TextureViewDimension::D2),
+                base_array_layer: shadow_pass.layer,
+                array_layer_count: Some(1),
+                ..Default::default()
+            });
+
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Shadow render pass"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Store,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+
+            // Shadow maps smaller than the layers only cover their top-left
+            // corner.
+            let map_size = shadow_pass.map_size as f32;
+
+            render_pass.set_viewport(0.0, 0.0, map_size, map_size, 0.0, 1.0);
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(
+                1,
+                &lights.shadow_camera_bind_group,
+                &[(i as u64 * lights.shadow_camera_stride) as u32],
+            );
+
+            for object in shadow_casters {
+                let Some((geometry, _, _)) = Self::geometry_and_material(object) else {
+                    continue;
+                };
+
+                render_pass.set_bind_group(
+                    0,
+                    object_bind_group.as_ref().unwrap(),
+                    &[objects.get(object).unwrap().offset as u32],
                 );
 
-                let model_view_matrix = view_matrix * object.world_matrix.borrow().as_ref();
-                let normal_matrix: Matrix3 = Matrix3::normal_matrix(&model_view_matrix);
+                let instances = self.set_instance_buffer(&mut render_pass, object);
 
-                self.gpu.queue.write_buffer(
-                    &self.model_view_matrix_buffer,
-                    mesh_index * Self::OFFSET,
-                    bytemuck::cast_slice(&[model_view_matrix]),
+                self.draw_geometry(&mut render_pass, geometry, Primitive::Triangles, instances);
+            }
+        }
+    }
+
+    /// Records the background pass, which clears the specified color
+    /// attachment and draws the textured or gradient background into it, as
+    /// seen from the specified camera with its translation removed.
+    ///
+    /// # Returns
+    ///
+    /// Whether the pass was recorded. Solid color backgrounds are cleared by
+    /// the render pass of the scene instead.
+    fn record_background_pass(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        camera: &Camera,
+        color_view: &wgpu::TextureView,
+    ) -> bool {
+        let (kind, texture, cube_texture, top, bottom) = match &self.background {
+            Background::ClearColor | Background::Color(_) => return false,
+            Background::Equirectangular(texture) => {
+                (EQUIRECTANGULAR_BACKGROUND, Some(texture), None, None, None)
+            }
+            Background::CubeMap(cube_texture) => {
+                (CUBE_MAP_BACKGROUND, None, Some(cube_texture), None, None)
+            }
+            Background::Gradient { top, bottom } => {
+                (GRADIENT_BACKGROUND, None, None, Some(top), Some(bottom))
+            }
+        };
+
+        let texture = texture.unwrap_or(&self.default_texture);
+        let cube_texture = cube_texture.unwrap_or(&self.default_cube_texture);
+
+        let mut background_pass = self.background_pass.borrow_mut();
+        let background_pass = background_pass.get_or_insert_with(|| self.create_background_pass());
+
+        // Textures are swapped by replacing them, so comparing their identities
+        // is enough to detect changes. Bound textures that have been dropped
+        // are always replaced.
+        if !background_pass
+            .textures
+            .0
+            .upgrade()
+            .is_some_and(|bound| Rc::ptr_eq(&bound, texture))
+            || !background_pass
+                .textures
+                .1
+                .upgrade()
+                .is_some_and(|bound| Rc::ptr_eq(&bound, cube_texture))
+        {
+            background_pass.bind_group = self.create_background_bind_group(
+                &background_pass.bind_group_layout,
+                &background_pass.uniform_buffer,
+                texture,
+                cube_texture,
+            );
+            background_pass.textures = (Rc::downgrade(texture), Rc::downgrade(cube_texture));
+        }
+
+        // Without the translation, the camera is at the origin, so the
+        // background only depends on the direction in which it looks.
+        let mut view_rotation = camera.view_matrix;
+        view_rotation.elements[12..15].fill(0.0);
+
+        let linear_color = |color: Option<&Color>| {
+            color.map_or([0.0; 3], |color| {
+                let color = srgb_to_linear(color);
+
+                [color.r as f32, color.g as f32, color.b as f32]
+            })
+        };
+
+        self.gpu.queue.write_buffer(
+            &background_pass.uniform_buffer,
+            0,
+            bytemuck::bytes_of(&BackgroundUniform {
+                inverse_view_projection_matrix: (camera.projection_matrix * view_rotation)
+                    .inverse()
+                    .elements,
+                top_color: linear_color(top),
+                kind,
+                bottom_color: linear_color(bottom),
+                _padding: 0,
+            }),
+        );
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Background render pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: color_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_pipeline(&background_pass.pipeline);
+        render_pass.set_bind_group(0, &background_pass.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+
+        true
+    }
+
+    /// Records the output pass, which applies the exposure and tone mapping to
+    /// the HDR texture and writes the result into the specified texture view.
+    fn record_output_pass(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        texture_view: &wgpu::TextureView,
+    ) {
+        self.gpu.queue.write_buffer(
+            &self.output_pass.uniform_buffer,
+            0,
+            bytemuck::bytes_of(&OutputUniform {
+                exposure: self.exposure,
+                tone_mapping: self.tone_mapping as u32,
+                encode_srgb: (!self.gpu.surface_configuration.format.is_srgb()).into(),
+                _padding: 0,
+            }),
+        );
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Output render pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: texture_view,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_pipeline(&self.output_pass.pipeline);
+        render_pass.set_bind_group(0, &self.output_pass.input_bind_group, &[]);
+        render_pass.set_bind_group(1, &self.output_pass.uniform_bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+
+    /// Sets the per-instance vertex buffer of the specified mesh, which has a
+    /// single instance with no transformation unless it is an instanced mesh.
+    ///
+    /// # Returns
+    ///
+    /// The range of instances to draw.
+    fn set_instance_buffer(
+        &self,
+        render_pass: &mut wgpu::RenderPass,
+        object: &Rc<Object3d>,
+    ) -> Range<u32> {
+        let vertex_arena = self.vertex_arena.borrow();
+
+        match self.instances.borrow().get(object) {
+            Some(resources) => {
+                render_pass.set_vertex_buffer(3, vertex_arena.slice(&resources.allocation));
+
+                0..resources.count
+            }
+            None => {
+                render_pass.set_vertex_buffer(3, vertex_arena.slice(&self.default_instance));
+
+                0..1
+            }
+        }
+    }
+
+    /// Sets the vertex and index buffers of the specified geometry and draws
+    /// the specified instances of it in a single draw call, uploading the
+    /// geometry first if it is new or has been modified.
+    ///
+    /// Points are drawn as one instance of a quad per vertex instead, without
+    /// the indices.
+    fn draw_geometry(
+        &self,
+        render_pass: &mut wgpu::RenderPass,
+        geometry: &Rc<BufferGeometry>,
+        primitive: Primitive,
+        instances: Range<u32>,
+    ) {
+        let mut geometries = self.geometries.borrow_mut();
+        let resources =
+            geometries.get_or_insert_with(geometry, || self.create_geometry_resources(geometry));
+
+        if resources.version != geometry.version() {
+            self.update_geometry_resources(geometry, resources);
+        }
+
+        if resources.count == 0 || instances.is_empty() {
+            return;
+        }
+
+        let vertex_arena = self.vertex_arena.borrow();
+
+        render_pass.set_vertex_buffer(0, vertex_arena.slice(&resources.position));
+        render_pass.set_vertex_buffer(1, vertex_arena.slice(&resources.normal));
+        render_pass.set_vertex_buffer(2, vertex_arena.slice(&resources.uv));
+
+        if primitive == Primitive::Points {
+            render_pass.draw(0..6, 0..geometry.position.borrow().len() as u32 / 3);
+        } else if primitive == Primitive::Wireframe {
+            let (edges, count) = *resources.edges.get_or_insert_with(|| {
+                let edges = Self::edge_indices(geometry);
+                let (allocation, _) = self.index_arena.borrow_mut().allocate(
+                    &self.gpu.device,
+                    &self.gpu.queue,
+                    (edges.len() * size_of::<u32>()) as u64,
                 );
 
                 self.gpu.queue.write_buffer(
-                    &self.normal_matrix_buffer,
-                    mesh_index * Self::OFFSET,
-                    bytemuck::cast_slice(&[normal_matrix]),
+                    self.index_arena.borrow().buffer(),
+                    allocation.offset,
+                    bytemuck::cast_slice(&edges),
                 );
 
-                render_pass.set_bind_group(0, bind_group, &[]);
+                (allocation, edges.len() as u32)
+            });
 
-                let num_vertices = mesh.geometry.position.len() as u32 / 3;
+            render_pass.set_index_buffer(
+                self.index_arena.borrow().slice(&edges),
+                wgpu::IndexFormat::Uint32,
+            );
+            render_pass.draw_indexed(0..count, 0, instances);
+        } else if let Some(indices) = &resources.indices {
+            render_pass.set_index_buffer(
+                self.index_arena.borrow().slice(indices),
+                wgpu::IndexFormat::Uint32,
+            );
+            render_pass.draw_indexed(0..resources.count, 0, instances);
+        } else {
+            render_pass.draw(0..resources.count, instances);
+        }
+    }
 
-                if let Some(indices) = &mesh.geometry.indices {
-                    self.gpu.queue.write_buffer(
-                        &self.index_buffer,
-                        index_start * size_of::<u32>() as u64,
-                        bytemuck::cast_slice(indices.as_ref()),
-                    );
+    /// Returns the indices of the end points of the edges of the triangles of
+    /// the specified geometry, two per edge. Edges shared by triangles that
+    /// share their vertices are only included once.
+    fn edge_indices(geometry: &BufferGeometry) -> Vec<u32> {
+        let indices = geometry.indices.borrow();
+        let triangles: Vec<[u32; 3]> = match &*indices {
+            Some(indices) => indices
+                .chunks_exact(3)
+                .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+                .collect(),
+            None => (0..geometry.position.borrow().len() as u32 / 9)
+                .map(|i| [3 * i, 3 * i + 1, 3 * i + 2])
+                .collect(),
+        };
 
-                    render_pass.draw_indexed(
-                        index_start as u32..index_start as u32 + indices.len() as u32,
-                        base_vertex as i32,
-                        0..1,
-                    );
+        let mut seen = HashSet::new();
+        let mut edges = Vec::with_capacity(triangles.len() * 6);
 
-                    index_start += indices.len() as u64;
-                } else {
-                    render_pass.draw(base_vertex as u32..base_vertex as u32 + num_vertices, 0..1);
+        for [a, b, c] in triangles {
+            for (start, end) in [(a, b), (b, c), (c, a)] {
+                if seen.insert((start.min(end), start.max(end))) {
+                    edges.extend([start, end]);
                 }
-
-                mesh_index += 1;
-                base_vertex += num_vertices as u64;
             }
+        }
 
-            for child in object.children.borrow().iter() {
-                stack.push(Rc::clone(child));
+        edges
+    }
+
+    /// Computes the normal matrix of the specified world matrix, which is the
+    /// inverse transpose of its upper-left 3x3 part. Transforms normals into
+    /// world space.
+    ///
+    /// ## Returns
+    ///
+    /// The normal matrix in column-major order, with each column padded to 4
+    /// numbers as required by the `mat3x3f` WGSL type.
+    fn normal_matrix(world_matrix: &Matrix4) -> [f32; 12] {
+        let e = &world_matrix.elements;
+        let [a, b, c] = [[e[0], e[1], e[2]], [e[4], e[5], e[6]], [e[8], e[9], e[10]]];
+
+        // The columns of the inverse transpose are the cross products of the
+        // other two columns, divided by the determinant.
+        let cross = |u: [f32; 3], v: [f32; 3]| {
+            [
+                u[1] * v[2] - u[2] * v[1],
+                u[2] * v[0] - u[0] * v[2],
+                u[0] * v[1] - u[1] * v[0],
+            ]
+        };
+
+        let (bc, ca, ab) = (cross(b, c), cross(c, a), cross(a, b));
+        let determinant = a[0] * bc[0] + a[1] * bc[1] + a[2] * bc[2];
+        let inverse_determinant = if determinant != 0.0 {
+            1.0 / determinant
+        } else {
+            0.0
+        };
+
+        let mut normal_matrix = [0.0; 12];
+
+        for (column, vector) in [bc, ca, ab].iter().enumerate() {
+            for (row, value) in vector.iter().enumerate() {
+                normal_matrix[column * 4 + row] = value * inverse_determinant;
             }
         }
 
-        drop(render_pass);
+        normal_matrix
+    }
+}
 
-        self.gpu.queue.submit(std::iter::once(encoder.finish()));
-        output.present();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        Ok(())
+    /// Returns a quad made of two triangles, indexed or not.
+    fn quad(indexed: bool) -> BufferGeometry {
+        let corners = [
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+        let triangles = [0, 1, 2, 0, 2, 3];
+
+        let (position, indices): (Vec<f32>, _) = if indexed {
+            (corners.concat(), Some(triangles.to_vec()))
+        } else {
+            (
+                triangles
+                    .iter()
+                    .flat_map(|&i| corners[i as usize])
+                    .collect(),
+                None,
+            )
+        };
+        let vertex_count = position.len() / 3;
+
+        BufferGeometry::new(
+            position,
+            vec![0.0; 3 * vertex_count],
+            vec![0.0; 2 * vertex_count],
+            indices,
+        )
+    }
+
+    #[test]
+    fn edge_indices_skip_shared_edges() {
+        let edges = ForwardRenderer::edge_indices(&quad(true));
+
+        // The diagonal is shared by both triangles, in opposite directions.
+        assert_eq!(edges, [0, 1, 1, 2, 2, 0, 2, 3, 3, 0]);
+    }
+
+    #[test]
+    fn edge_indices_of_non_indexed_geometry() {
+        let edges = ForwardRenderer::edge_indices(&quad(false));
+
+        assert_eq!(edges, [0, 1, 1, 2, 2, 0, 3, 4, 4, 5, 5, 3]);
     }
 }
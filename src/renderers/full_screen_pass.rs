@@ -0,0 +1,95 @@
+//! Resources shared by passes that draw a triangle covering the whole screen
+//! to process an input texture, such as the output pass of renderers and the
+//! passes of post-processors.
+//!
+//! The vertex stage and the input bindings of these passes are declared in
+//! `shaders/full_screen.wgsl`, which is prepended to their fragment shaders.
+
+/// Returns the bind group layout entries of the input texture and sampler of
+/// a full-screen pass, at bindings 0 and 1.
+pub(crate) fn input_layout_entries() -> [wgpu::BindGroupLayoutEntry; 2] {
+    [
+        wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+                sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                view_dimension: wgpu::TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        },
+        wgpu::BindGroupLayoutEntry {
+            binding: 1,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+            count: None,
+        },
+    ]
+}
+
+/// Creates the sampler of the input texture of full-screen passes, which
+/// filters linearly so that passes can sample between pixels.
+pub(crate) fn create_sampler(device: &wgpu::Device, label: &str) -> wgpu::Sampler {
+    device.create_sampler(&wgpu::SamplerDescriptor {
+        label: Some(label),
+        address_mode_u: wgpu::AddressMode::ClampToEdge,
+        address_mode_v: wgpu::AddressMode::ClampToEdge,
+        mag_filter: wgpu::FilterMode::Linear,
+        min_filter: wgpu::FilterMode::Linear,
+        ..Default::default()
+    })
+}
+
+/// Creates a full-screen render pipeline with the specified fragment stage,
+/// whose `fragmentMain` entry point outputs to the specified color format.
+pub(crate) fn create_pipeline(
+    device: &wgpu::Device,
+    label: Option<&str>,
+    bind_group_layouts: &[&wgpu::BindGroupLayout],
+    fragment_shader: &str,
+    format: wgpu::TextureFormat,
+) -> wgpu::RenderPipeline {
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: None,
+        bind_group_layouts,
+        push_constant_ranges: &[],
+    });
+
+    let source = format!(
+        "{}\n{}",
+        include_str!("shaders/full_screen.wgsl"),
+        fragment_shader
+    );
+
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::Wgsl(source.into()),
+    });
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label,
+        layout: Some(&pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &module,
+            entry_point: "vertexMain",
+            buffers: &[],
+            compilation_options: Default::default(),
+        },
+        primitive: wgpu::PrimitiveState::default(),
+        depth_stencil: None,
+        multisample: wgpu::MultisampleState::default(),
+        fragment: Some(wgpu::FragmentState {
+            module: &module,
+            entry_point: "fragmentMain",
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: None,
+                write_mask: wgpu::ColorWrites::ALL,
+            })],
+            compilation_options: Default::default(),
+        }),
+        multiview: None,
+        cache: None,
+    })
+}
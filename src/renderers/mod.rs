@@ -1,5 +1,14 @@
 //! Contains different kinds of 3D renderers and shaders.
 
+mod background;
+mod buffer_arena;
 mod forward_renderer;
+mod full_screen_pass;
+mod post_processor;
+mod resource_cache;
+mod tone_mapping;
 
+pub use background::*;
 pub use forward_renderer::*;
+pub use post_processor::*;
+pub use tone_mapping::*;
@@ -0,0 +1,483 @@
+use std::{any::TypeId, cell::RefCell, collections::HashMap, rc::Rc};
+
+use super::{full_screen_pass, resource_cache::ResourceCache, ForwardRenderer};
+use crate::{core::Camera, core::Object3d, postprocessing::Pass};
+
+/// Applies screen-space effects to the images of a [`ForwardRenderer`].
+///
+/// The post-processor renders the scene into an offscreen texture, then runs
+/// its [passes](Self::passes) one after the other. Each pass reads the image
+/// produced by the previous one and writes into a second texture, after which
+/// the two textures swap roles. The final image is copied to the window, or
+/// to the offscreen texture if the renderer is headless.
+///
+/// Passes run on the image after the renderer has
+/// [tone mapped](ForwardRenderer::tone_mapping) it, so they see displayable
+/// colors. They can also read the depth of the scene, e.g. for fog or depth of
+/// field effects.
+///
+/// A post-processor must always be used with the renderer it was created for.
+pub struct PostProcessor {
+    /// The passes, in the order in which they are run. Disabled passes are
+    /// skipped.
+    pub passes: RefCell<Vec<Rc<dyn Pass>>>,
+
+    /// The layout of the bind group of the input texture of each pass and of
+    /// the renderer's depth texture.
+    input_bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    /// A 1x1 texture at the far plane, which is bound instead of the
+    /// renderer's depth texture if that cannot be bound.
+    far_depth_texture: wgpu::Texture,
+    /// The pipeline that copies the final image to the output.
+    blit_pipeline: wgpu::RenderPipeline,
+    /// The two textures that passes read from and write to, which are created
+    /// on first use and recreated when the size of the renderer changes.
+    targets: RefCell<Option<[RenderTarget; 2]>>,
+    /// Render pipelines, grouped by pass type.
+    pipelines: RefCell<HashMap<TypeId, PassPipeline>>,
+    pass_resources: RefCell<ResourceCache<dyn Pass, PassResources>>,
+}
+
+/// A texture that is rendered into by one pass and read by the next.
+struct RenderTarget {
+    texture: wgpu::Texture,
+    view: wgpu::TextureView,
+    /// The bind group that binds the texture as the input of a pass, along
+    /// with the renderer's depth texture.
+    bind_group: wgpu::BindGroup,
+    /// The identifier of the depth texture in the bind group. The renderer's
+    /// depth texture is replaced when the renderer is resized.
+    depth_texture: wgpu::Id<wgpu::Texture>,
+}
+
+/// Render pipeline of a pass type.
+struct PassPipeline {
+    /// The layout of the bind group of each pass of the type.
+    bind_group_layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+}
+
+/// GPU resources of a pass.
+struct PassResources {
+    uniform_buffer: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    /// The uniform content that was last written to the uniform buffer.
+    uniform: Vec<u8>,
+}
+
+impl PostProcessor {
+    /// Creates a post-processor with no passes for the specified renderer.
+    pub fn new(renderer: &ForwardRenderer) -> Self {
+        let device = &renderer.gpu.device;
+
+        let [texture_entry, sampler_entry] = full_screen_pass::input_layout_entries();
+
+        let input_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("Post-processing input bind group layout"),
+                entries: &[
+                    texture_entry,
+                    sampler_entry,
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Texture {
+                            // Depth textures are bound as floats, because
+                            // loading from depth textures is not supported on
+                            // WebGL.
+                            sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            multisampled: Self::depth_multisampled(renderer),
+                        },
+                        count: None,
+                    },
+                ],
+            });
+
+        let sampler = full_screen_pass::create_sampler(device, "Post-processing sampler");
+
+        let far_depth_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Post-processing far depth texture"),
+            size: wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R32Float,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING.union(wgpu::TextureUsages::COPY_DST),
+            view_formats: &[],
+        });
+
+        renderer.gpu.queue.write_texture(
+            far_depth_texture.as_image_copy(),
+            bytemuck::bytes_of(&1.0f32),
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4),
+                rows_per_image: Some(1),
+            },
+            far_depth_texture.size(),
+        );
+
+        let blit_pipeline = Self::create_pipeline(
+            renderer,
+            &[&input_bind_group_layout],
+            include_str!("shaders/blit.wgsl"),
+        );
+
+        Self {
+            passes: RefCell::new(Vec::new()),
+            input_bind_group_layout,
+            sampler,
+            far_depth_texture,
+            blit_pipeline,
+            targets: RefCell::new(None),
+            pipelines: RefCell::new(HashMap::new()),
+            pass_resources: RefCell::new(ResourceCache::new()),
+        }
+    }
+
+    /// Adds the specified pass after the existing passes.
+    pub fn add_pass(&self, pass: Rc<dyn Pass>) {
+        self.passes.borrow_mut().push(pass);
+    }
+
+    /// Renders the specified scene as viewed from the specified camera with
+    /// the renderer, applies the passes, and outputs the result to the window,
+    /// or to the offscreen texture if the renderer is headless. See
+    /// [`ForwardRenderer::render`].
+    pub fn render(
+        &self,
+        renderer: &ForwardRenderer,
+        scene: Rc<Object3d>,
+        camera: &Camera,
+    ) -> Result<(), wgpu::SurfaceError> {
+        renderer.draw_to_frame(|texture_view| self.draw(renderer, scene, camera, texture_view))
+    }
+
+    /// Renders the specified scene as viewed from the specified camera with
+    /// the renderer, applies the passes, and returns the pixels of the result
+    /// in RGBA8 format. See [`ForwardRenderer::render_to_image`].
+    pub fn render_to_image(
+        &self,
+        renderer: &ForwardRenderer,
+        scene: Rc<Object3d>,
+        camera: &Camera,
+    ) -> Result<Vec<u8>, wgpu::BufferAsyncError> {
+        renderer.draw_to_image(|texture_view| self.draw(renderer, scene, camera, texture_view))
+    }
+
+    /// Draws the scene into the first target, runs the enabled passes, and
+    /// copies the final image into the specified texture view.
+    fn draw(
+        &self,
+        renderer: &ForwardRenderer,
+        scene: Rc<Object3d>,
+        camera: &Camera,
+        texture_view: &wgpu::TextureView,
+    ) {
+        self.update_targets(renderer);
+
+        let targets = self.targets.borrow();
+        let targets = targets.as_ref().unwrap();
+
+        renderer.draw(scene, camera, &targets[0].view);
+
+        let passes: Vec<Rc<dyn Pass>> = self
+            .passes
+            .borrow()
+            .iter()
+            .filter(|pass| pass.enabled())
+            .map(Rc::clone)
+            .collect();
+
+        let mut pipelines = self.pipelines.borrow_mut();
+        let mut pass_resources = self.pass_resources.borrow_mut();
+
+        for pass in &passes {
+            let pipeline = pipelines
+                .entry(pass.pass_type_id())
+                .or_insert_with(|| self.create_pass_pipeline(renderer, pass.as_ref()));
+
+            let resources = pass_resources.get_or_insert_with(pass, || {
+                Self::create_pass_resources(renderer, pass.as_ref(), &pipeline.bind_group_layout)
+            });
+
+            let uniform = pass.uniform();
+
+            if uniform.len() as u64 > resources.uniform_buffer.size() {
+                *resources = Self::create_pass_resources(
+                    renderer,
+                    pass.as_ref(),
+                    &pipeline.bind_group_layout,
+                );
+            } else if uniform != resources.uniform {
+                renderer
+                    .gpu
+                    .queue
+                    .write_buffer(&resources.uniform_buffer, 0, &uniform);
+                resources.uniform = uniform;
+            }
+        }
+
+        let mut encoder =
+            renderer
+                .gpu
+                .device
+                .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                    label: Some("Post-processing command encoder"),
+                });
+
+        // The index of the target that holds the latest image.
+        let mut input = 0;
+
+        for pass in &passes {
+            let pipeline = &pipelines[&pass.pass_type_id()];
+            let resources = pass_resources.get(pass).unwrap();
+
+            Self::run(
+                &mut encoder,
+                &pipeline.pipeline,
+                &targets[input].bind_group,
+                Some(&resources.bind_group),
+                &targets[1 - input].view,
+            );
+
+            input = 1 - input;
+        }
+
+        Self::run(
+            &mut encoder,
+            &self.blit_pipeline,
+            &targets[input].bind_group,
+            None,
+            texture_view,
+        );
+
+        renderer.gpu.queue.submit(std::iter::once(encoder.finish()));
+
+        pass_resources.prune();
+    }
+
+    /// Records a full-screen render pass that reads the input bound by
+    /// `input_bind_group` and writes into `output`.
+    fn run(
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::RenderPipeline,
+        input_bind_group: &wgpu::BindGroup,
+        pass_bind_group: Option<&wgpu::BindGroup>,
+        output: &wgpu::TextureView,
+    ) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Post-processing render pass"),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: output,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_pipeline(pipeline);
+        render_pass.set_bind_group(0, input_bind_group, &[]);
+
+        if let Some(pass_bind_group) = pass_bind_group {
+            render_pass.set_bind_group(1, pass_bind_group, &[]);
+        }
+
+        render_pass.draw(0..3, 0..1);
+    }
+
+    /// Returns whether passes read the renderer's depth texture as a
+    /// multisampled texture.
+    fn depth_multisampled(renderer: &ForwardRenderer) -> bool {
+        renderer.sample_count() > 1 && renderer.scene_depth_texture().is_some()
+    }
+
+    /// Returns the depth texture that is bound for passes.
+    fn depth_texture<'a>(&'a self, renderer: &'a ForwardRenderer) -> &'a wgpu::Texture {
+        renderer
+            .scene_depth_texture()
+            .unwrap_or(&self.far_depth_texture)
+    }
+
+    /// Creates the targets if they do not exist yet, if their size differs
+    /// from the size of the renderer, or if the renderer's depth texture has
+    /// been replaced.
+    fn update_targets(&self, renderer: &ForwardRenderer) {
+        let (width, height) = renderer.gpu.size;
+        let mut targets = self.targets.borrow_mut();
+
+        if let Some([target, _]) = targets.as_ref() {
+            if target.texture.width() == width
+                && target.texture.height() == height
+                && target.depth_texture == self.depth_texture(renderer).global_id()
+            {
+                return;
+            }
+        }
+
+        *targets = Some([0, 1].map(|_| self.create_target(renderer)));
+    }
+
+    /// Creates a target of the size and color format of the renderer.
+    fn create_target(&self, renderer: &ForwardRenderer) -> RenderTarget {
+        let (width, height) = renderer.gpu.size;
+
+        let texture = renderer
+            .gpu
+            .device
+            .create_texture(&wgpu::TextureDescriptor {
+                label: Some("Post-processing target"),
+                size: wgpu::Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: renderer.gpu.surface_configuration.format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                    .union(wgpu::TextureUsages::TEXTURE_BINDING),
+                view_formats: &[],
+            });
+
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let depth_texture = self.depth_texture(renderer);
+        let depth_view = depth_texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let bind_group = renderer
+            .gpu
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout: &self.input_bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(&view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(&self.sampler),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 2,
+                        resource: wgpu::BindingResource::TextureView(&depth_view),
+                    },
+                ],
+            });
+
+        RenderTarget {
+            texture,
+            view,
+            bind_group,
+            depth_texture: depth_texture.global_id(),
+        }
+    }
+
+    /// Creates the bind group layout and render pipeline of a pass type.
+    fn create_pass_pipeline(&self, renderer: &ForwardRenderer, pass: &dyn Pass) -> PassPipeline {
+        let bind_group_layout =
+            renderer
+                .gpu
+                .device
+                .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                    label: Some("Pass bind group layout"),
+                    entries: &[wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::FRAGMENT,
+                        ty: wgpu::BindingType::Buffer {
+                            ty: wgpu::BufferBindingType::Uniform,
+                            has_dynamic_offset: false,
+                            min_binding_size: None,
+                        },
+                        count: None,
+                    }],
+                });
+
+        let pipeline = Self::create_pipeline(
+            renderer,
+            &[&self.input_bind_group_layout, &bind_group_layout],
+            pass.fragment_shader(),
+        );
+
+        PassPipeline {
+            bind_group_layout,
+            pipeline,
+        }
+    }
+
+    /// Creates a full-screen render pipeline with the specified fragment
+    /// stage, which can read the depth of the scene and outputs to the color
+    /// format of the renderer.
+    fn create_pipeline(
+        renderer: &ForwardRenderer,
+        bind_group_layouts: &[&wgpu::BindGroupLayout],
+        fragment_shader: &str,
+    ) -> wgpu::RenderPipeline {
+        let scene_depth = if Self::depth_multisampled(renderer) {
+            include_str!("shaders/scene_depth_multisampled.wgsl")
+        } else {
+            include_str!("shaders/scene_depth.wgsl")
+        };
+
+        full_screen_pass::create_pipeline(
+            &renderer.gpu.device,
+            None,
+            bind_group_layouts,
+            &format!("{}\n{}", scene_depth, fragment_shader),
+            renderer.gpu.surface_configuration.format,
+        )
+    }
+
+    /// Creates the uniform buffer and bind group of the specified pass.
+    fn create_pass_resources(
+        renderer: &ForwardRenderer,
+        pass: &dyn Pass,
+        bind_group_layout: &wgpu::BindGroupLayout,
+    ) -> PassResources {
+        let uniform = pass.uniform();
+
+        let uniform_buffer = renderer.gpu.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Pass uniform buffer"),
+            // Uniform buffer bindings must not be empty.
+            size: (uniform.len() as u64).max(16),
+            usage: wgpu::BufferUsages::UNIFORM.union(wgpu::BufferUsages::COPY_DST),
+            mapped_at_creation: false,
+        });
+
+        renderer
+            .gpu
+            .queue
+            .write_buffer(&uniform_buffer, 0, &uniform);
+
+        let bind_group = renderer
+            .gpu
+            .device
+            .create_bind_group(&wgpu::BindGroupDescriptor {
+                label: None,
+                layout: bind_group_layout,
+                entries: &[wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                }],
+            });
+
+        PassResources {
+            uniform_buffer,
+            bind_group,
+            uniform,
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::{
+    collections::HashMap,
+    rc::{Rc, Weak},
+};
+
+/// Cache of GPU resources that belong to reference-counted values, such as
+/// the uniform buffer of a material. Entries are keyed by the identity of the
+/// value, so values shared through clones of the same [`Rc`] share their
+/// resources.
+///
+/// The cache only holds weak references to the values. Resources of dropped
+/// values are freed by [`prune`](Self::prune).
+pub(crate) struct ResourceCache<T: ?Sized, R> {
+    entries: HashMap<*const (), (Weak<T>, R)>,
+}
+
+impl<T: ?Sized, R> ResourceCache<T, R> {
+    /// Creates an empty cache.
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns the resources of the specified value, creating them with
+    /// `create` if the value has no resources in the cache yet.
+    pub(crate) fn get_or_insert_with(
+        &mut self,
+        value: &Rc<T>,
+        create: impl FnOnce() -> R,
+    ) -> &mut R {
+        // The weak reference keeps the allocation alive, so the address of a
+        // cached value cannot be reused by another value.
+        &mut self
+            .entries
+            .entry(Rc::as_ptr(value) as *const ())
+            .or_insert_with(|| (Rc::downgrade(value), create()))
+            .1
+    }
+
+    /// Returns the resources of the specified value, or `None` if the value has
+    /// no resources in the cache.
+    pub(crate) fn get(&self, value: &Rc<T>) -> Option<&R> {
+        self.entries
+            .get(&(Rc::as_ptr(value) as *const ()))
+            .map(|(_, resources)| resources)
+    }
+
+    /// Removes the resources of all values that have been dropped.
+    ///
+    /// # Returns
+    ///
+    /// The removed resources, for resources that must be released explicitly
+    /// e.g. allocations in a [`BufferArena`](super::buffer_arena::BufferArena).
+    pub(crate) fn prune(&mut self) -> Vec<R> {
+        let dropped: Vec<*const ()> = self
+            .entries
+            .iter()
+            .filter(|(_, (value, _))| value.strong_count() == 0)
+            .map(|(key, _)| *key)
+            .collect();
+
+        dropped
+            .into_iter()
+            .filter_map(|key| self.entries.remove(&key))
+            .map(|(_, resources)| resources)
+            .collect()
+    }
+}
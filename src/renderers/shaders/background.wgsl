@@ -0,0 +1,95 @@
+const EQUIRECTANGULAR_BACKGROUND: u32 = 0;
+const CUBE_MAP_BACKGROUND: u32 = 1;
+const GRADIENT_BACKGROUND: u32 = 2;
+
+const PI: f32 = 3.141592653589793;
+
+struct Background {
+  // The inverse of the camera's view projection matrix without the
+  // translation of the camera, which maps points on the screen to directions.
+  inverse_view_projection_matrix: mat4x4f,
+  // The linear colors of the gradient.
+  top_color: vec3f,
+  kind: u32,
+  bottom_color: vec3f,
+}
+
+@group(0) @binding(0) var<uniform> background: Background;
+@group(0) @binding(1) var equirectangular_map: texture_2d<f32>;
+@group(0) @binding(2) var equirectangular_sampler: sampler;
+@group(0) @binding(3) var cube_map: texture_cube<f32>;
+@group(0) @binding(4) var cube_sampler: sampler;
+
+struct BackgroundVertexOutput {
+  @builtin(position) position: vec4f,
+  @location(0) ndc: vec2f,
+}
+
+// Outputs a triangle that covers the whole screen, with the normalized device
+// coordinates of its corners.
+@vertex
+fn vertexMain(@builtin(vertex_index) vertex_index: u32) -> BackgroundVertexOutput {
+  let uv = vec2f(f32((vertex_index << 1) & 2), f32(vertex_index & 2));
+  let ndc = vec2f(uv.x * 2 - 1, 1 - uv.y * 2);
+
+  var output: BackgroundVertexOutput;
+
+  output.position = vec4f(ndc, 0, 1);
+  output.ndc = ndc;
+
+  return output;
+}
+
+// Returns the world space direction seen at the specified point on the screen.
+// The point halfway through the depth range is finite even if the far plane
+// is at infinity, and the difference also works for orthographic cameras.
+fn view_direction_at(ndc: vec2f) -> vec3f {
+  let near = background.inverse_view_projection_matrix * vec4f(ndc, 0, 1);
+  let middle = background.inverse_view_projection_matrix * vec4f(ndc, 0.5, 1);
+
+  return normalize(middle.xyz / middle.w - near.xyz / near.w);
+}
+
+@fragment
+fn fragmentMain(input: BackgroundVertexOutput) -> @location(0) vec4f {
+  let direction = view_direction_at(input.ndc);
+
+  if background.kind == EQUIRECTANGULAR_BACKGROUND {
+    let u = atan2(direction.x, -direction.z) / (2 * PI) + 0.5;
+    let v = asin(clamp(direction.y, -1.0, 1.0)) / PI + 0.5;
+
+    // U wraps around behind the camera, where its derivatives would select the
+    // smallest mipmap, so they are taken from a copy that wraps elsewhere.
+    let wrapped_u = fract(u + 0.5);
+    let du = vec2f(dpdx(u), dpdy(u));
+    let wrapped_du = vec2f(dpdx(wrapped_u), dpdy(wrapped_u));
+    let dv = vec2f(dpdx(v), dpdy(v));
+    let grad_u = select(du, wrapped_du, dot(wrapped_du, wrapped_du) < dot(du, du));
+
+    let color = textureSampleGrad(
+      equirectangular_map,
+      equirectangular_sampler,
+      vec2f(u, v),
+      vec2f(grad_u.x, dv.x),
+      vec2f(grad_u.y, dv.y),
+    );
+
+    return vec4f(color.rgb, 1);
+  }
+
+  if background.kind == CUBE_MAP_BACKGROUND {
+    // Cube maps are sampled in a left-handed space, and the renderer swaps
+    // their +Z and -Z faces to match.
+    let color = textureSample(
+      cube_map,
+      cube_sampler,
+      vec3f(direction.xy, -direction.z),
+    );
+
+    return vec4f(color.rgb, 1);
+  }
+
+  let t = direction.y * 0.5 + 0.5;
+
+  return vec4f(mix(background.bottom_color, background.top_color, t), 1);
+}
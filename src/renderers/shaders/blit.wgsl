@@ -0,0 +1,4 @@
+@fragment
+fn fragmentMain(input: VertexOutput) -> @location(0) vec4f {
+  return textureSample(input_texture, input_sampler, input.uv);
+}
@@ -1,33 +1,108 @@
-@group(0) @binding(0) var<uniform> model_matrix: mat4x4f;
-@group(0) @binding(1) var<uniform> model_view_matrix: mat4x4f;
-@group(0) @binding(2) var<uniform> projection_matrix: mat4x4f;
-@group(0) @binding(3) var<uniform> view_matrix: mat4x4f;
-@group(0) @binding(4) var<uniform> normal_matrix: mat3x3f;
-@group(0) @binding(5) var<uniform> camera_position: vec3f;
+struct Object {
+  model_matrix: mat4x4f,
+  model_view_matrix: mat4x4f,
+  normal_matrix: mat3x3f,
+  pick_id: u32,
+  receive_shadow: u32,
+  // Half of the width and height of points in normalized device coordinates.
+  point_size: vec2f,
+}
+
+@group(0) @binding(0) var<uniform> object: Object;
+@group(0) @binding(1) var<uniform> projection_matrix: mat4x4f;
+@group(0) @binding(2) var<uniform> view_matrix: mat4x4f;
+@group(0) @binding(3) var<uniform> camera_position: vec3f;
 
 struct VertexInput {
   @location(1) position: vec3f,
   @location(2) normal: vec3f,
   @location(3) uv: vec2f,
+  @location(4) instance_matrix_0: vec4f,
+  @location(5) instance_matrix_1: vec4f,
+  @location(6) instance_matrix_2: vec4f,
+  @location(7) instance_matrix_3: vec4f,
+  @location(8) instance_color: vec4f,
+}
+
+struct PointInput {
+  @location(1) position: vec3f,
+  @location(2) normal: vec3f,
+  @location(3) uv: vec2f,
 }
 
 struct VertexOutput {
   @builtin(position) position: vec4f,
-  @location (2) normal: vec3f,
+  @location(1) world_position: vec3f,
+  @location(2) normal: vec3f,
   @location(3) uv: vec2f,
+  @location(4) world_normal: vec3f,
+  @location(5) color: vec4f,
+}
+
+// Returns the direction from the specified world position towards the camera.
+// An orthographic camera views all positions along the same direction.
+fn view_direction(world_position: vec3f) -> vec3f {
+  if projection_matrix[3][3] == 1.0 {
+    // The camera's +Z axis in world space is the third row of the view matrix.
+    return normalize(vec3f(view_matrix[0][2], view_matrix[1][2], view_matrix[2][2]));
+  }
+
+  return normalize(camera_position - world_position);
+}
+
+// Returns the matrix that transforms normals by the specified matrix, which is
+// the inverse transpose of its upper-left 3x3 matrix up to a positive scale.
+fn normal_matrix(matrix: mat4x4f) -> mat3x3f {
+  let a = matrix[0].xyz;
+  let b = matrix[1].xyz;
+  let c = matrix[2].xyz;
+
+  return mat3x3f(cross(b, c), cross(c, a), cross(a, b)) * sign(dot(a, cross(b, c)));
 }
 
 @vertex
 fn vertexMain(input: VertexInput) -> VertexOutput {
+  // Meshes that are not instanced have a single instance with no
+  // transformation.
+  let instance_matrix = mat4x4f(
+    input.instance_matrix_0,
+    input.instance_matrix_1,
+    input.instance_matrix_2,
+    input.instance_matrix_3,
+  );
+  let position = instance_matrix * vec4f(input.position, 1);
+
   var output: VertexOutput;
-  output.position = projection_matrix * model_view_matrix * vec4f(input.position, 1);
+  output.position = projection_matrix * object.model_view_matrix * position;
+  output.world_position = (object.model_matrix * position).xyz;
   output.normal = input.normal;
   output.uv = input.uv;
+  output.world_normal = object.normal_matrix * normal_matrix(instance_matrix) * input.normal;
+  output.color = input.instance_color;
 
   return output;
 }
 
-@fragment
-fn fragmentMain(input: VertexOutput) -> @location(0) vec4f {
-  return vec4f((input.normal + 1) / 2, 1);
+// Draws each point as a quad that faces the camera. The vertex buffers are read
+// per instance, so instance `i` is the point of vertex `i`, and the 6 vertices
+// of each instance are the corners of the quad's two triangles.
+@vertex
+fn pointsVertexMain(@builtin(vertex_index) vertex_index: u32, input: PointInput) -> VertexOutput {
+  var corners = array(
+    vec2f(-1, -1), vec2f(1, -1), vec2f(1, 1),
+    vec2f(-1, -1), vec2f(1, 1), vec2f(-1, 1),
+  );
+  let corner = corners[vertex_index];
+
+  var output: VertexOutput;
+  output.position = projection_matrix * object.model_view_matrix * vec4f(input.position, 1);
+  // Offsets are multiplied by w to be undone by the perspective division.
+  output.position += vec4f(corner * object.point_size * output.position.w, 0, 0);
+  output.world_position = (object.model_matrix * vec4f(input.position, 1)).xyz;
+  output.normal = input.normal;
+  output.uv = vec2f(0.5 + 0.5 * corner.x, 0.5 - 0.5 * corner.y);
+  output.world_normal = object.normal_matrix * input.normal;
+  output.color = vec4f(1);
+
+  return output;
 }
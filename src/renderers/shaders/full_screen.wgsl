@@ -0,0 +1,21 @@
+@group(0) @binding(0) var input_texture: texture_2d<f32>;
+@group(0) @binding(1) var input_sampler: sampler;
+
+struct VertexOutput {
+  @builtin(position) position: vec4f,
+  @location(0) uv: vec2f,
+}
+
+// Outputs a triangle that covers the whole screen, with the UV coordinates of
+// the image at its corners.
+@vertex
+fn vertexMain(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
+  let uv = vec2f(f32((vertex_index << 1) & 2), f32(vertex_index & 2));
+
+  var output: VertexOutput;
+
+  output.position = vec4f(uv.x * 2 - 1, 1 - uv.y * 2, 0, 1);
+  output.uv = uv;
+
+  return output;
+}
@@ -0,0 +1,238 @@
+const AMBIENT_LIGHT: u32 = 0;
+const DIRECTIONAL_LIGHT: u32 = 1;
+const POINT_LIGHT: u32 = 2;
+const SPOT_LIGHT: u32 = 3;
+
+struct Light {
+  // The color of the light multiplied by its intensity.
+  color: vec3f,
+  kind: u32,
+  position: vec3f,
+  range: f32,
+  direction: vec3f,
+  decay: f32,
+  // The cosine of the angle of the spot light's cone.
+  cos_angle: f32,
+  // The cosine of the angle where the spot light starts fading out.
+  cos_penumbra: f32,
+  // The index of the light's shadow in `shadows`, or -1 if the light does not
+  // cast shadows.
+  shadow: i32,
+  // The number of cascades of the light's shadow, which follow each other in
+  // `shadows`.
+  shadow_count: u32,
+}
+
+struct Lights {
+  count: u32,
+  lights: array<Light>,
+}
+
+struct Shadow {
+  // Transforms world positions into the clip space of the light's shadow
+  // camera.
+  view_projection_matrix: mat4x4f,
+  // The layer of the shadow map in `shadow_maps`, or the cube map of the point
+  // shadow map for point lights.
+  layer: u32,
+  bias: f32,
+  // The fraction of the width and height of the layer covered by the shadow
+  // map, which may be smaller than the layer.
+  scale: f32,
+  // The size of a texel of the layer in UV coordinates.
+  texel_size: f32,
+  // The view-space depth up to which the shadow map is used, and from which
+  // it is blended with the next cascade.
+  far: f32,
+  blend_start: f32,
+  // The distance by which the distances in a point light's cube map are
+  // divided.
+  range: f32,
+}
+
+@group(2) @binding(0) var<storage, read> lights: Lights;
+@group(2) @binding(1) var<storage, read> shadows: array<Shadow>;
+@group(2) @binding(2) var shadow_maps: texture_depth_2d_array;
+@group(2) @binding(3) var shadow_sampler: sampler_comparison;
+
+// Light arriving at a surface point from a direct light source.
+struct IncidentLight {
+  // The direction from the surface point towards the light.
+  direction: vec3f,
+  color: vec3f,
+}
+
+// Returns the sum of all ambient lights.
+fn ambient_light() -> vec3f {
+  var color = vec3f(0.0);
+
+  for (var i = 0u; i < lights.count; i++) {
+    if lights.lights[i].kind == AMBIENT_LIGHT {
+      color += lights.lights[i].color;
+    }
+  }
+
+  return color;
+}
+
+fn distance_attenuation(distance: f32, range: f32, decay: f32) -> f32 {
+  var attenuation = 1.0 / max(pow(distance, decay), 0.01);
+
+  if range > 0.0 {
+    // Smoothly fade out the light towards the end of its range.
+    attenuation *= pow(saturate(1.0 - pow(distance / range, 4.0)), 2.0);
+  }
+
+  return attenuation;
+}
+
+// Returns the fraction of the light of the specified shadow that reaches the
+// specified world position, from 0 (in shadow) to 1 (lit). The shadow map is
+// sampled 3x3 times with bilinear comparisons (percentage-closer filtering),
+// which softens the edges of shadows. Positions outside of the shadow camera's
+// view are lit.
+fn shadow_factor(shadow: Shadow, world_position: vec3f) -> f32 {
+  let clip_position = shadow.view_projection_matrix * vec4f(world_position, 1.0);
+
+  if clip_position.w <= 0.0 {
+    return 1.0;
+  }
+
+  let ndc = clip_position.xyz / clip_position.w;
+  let uv = vec2f(0.5 + 0.5 * ndc.x, 0.5 - 0.5 * ndc.y);
+
+  if any(uv < vec2f(0.0)) || any(uv > vec2f(1.0)) || ndc.z > 1.0 {
+    return 1.0;
+  }
+
+  let depth = ndc.z - shadow.bias;
+  var lit = 0.0;
+
+  for (var y = -1; y <= 1; y++) {
+    for (var x = -1; x <= 1; x++) {
+      let offset = vec2f(f32(x), f32(y)) * shadow.texel_size;
+
+      lit += textureSampleCompareLevel(shadow_maps, shadow_sampler, uv * shadow.scale + offset, shadow.layer, depth);
+    }
+  }
+
+  return lit / 9.0;
+}
+
+// Returns the fraction of the light of the specified point light shadow that
+// reaches the specified world position, from the specified position of the
+// light. The cube map stores the distance of the shadow casters from the light
+// divided by the range, and is sampled 3x3 times around the direction from the
+// light to the position. Positions out of range are lit.
+fn point_shadow_factor(shadow: Shadow, light_position: vec3f, world_position: vec3f) -> f32 {
+  let offset = world_position - light_position;
+  let distance = length(offset);
+
+  if distance >= shadow.range {
+    return 1.0;
+  }
+
+  let direction = offset / distance;
+  let depth = distance / shadow.range - shadow.bias;
+
+  // Two axes perpendicular to the direction, scaled to the size of a texel,
+  // since the faces of a cube map span 2 units at a distance of 1.
+  var up = vec3f(0.0, 1.0, 0.0);
+
+  if abs(direction.y) > 0.99 {
+    up = vec3f(1.0, 0.0, 0.0);
+  }
+
+  let tangent = normalize(cross(direction, up)) * 2.0 * shadow.texel_size;
+  let bitangent = normalize(cross(direction, tangent)) * 2.0 * shadow.texel_size;
+  var lit = 0.0;
+
+  for (var y = -1; y <= 1; y++) {
+    for (var x = -1; x <= 1; x++) {
+      let sample_direction = direction + f32(x) * tangent + f32(y) * bitangent;
+
+      lit += sample_point_shadow_map(sample_direction, shadow.layer, depth);
+    }
+  }
+
+  return lit / 9.0;
+}
+
+// Returns the fraction of the light of the specified shadow that reaches the
+// specified world position, using the cascade that contains the position in
+// the camera's view. Within the blending range at the far side of a cascade,
+// the next cascade is mixed in, or shadows fade out after the last one.
+// Shadows that are not cascaded have a single cascade of infinite depth.
+fn cascaded_shadow_factor(first: u32, count: u32, world_position: vec3f) -> f32 {
+  let depth = -(view_matrix * vec4f(world_position, 1.0)).z;
+
+  for (var i = 0u; i < count; i++) {
+    let cascade = shadows[first + i];
+
+    if depth > cascade.far {
+      continue;
+    }
+
+    let factor = shadow_factor(cascade, world_position);
+
+    if depth <= cascade.blend_start {
+      return factor;
+    }
+
+    var next = 1.0;
+
+    if i + 1u < count {
+      next = shadow_factor(shadows[first + i + 1u], world_position);
+    }
+
+    return mix(factor, next, (depth - cascade.blend_start) / (cascade.far - cascade.blend_start));
+  }
+
+  return 1.0;
+}
+
+// Returns the light arriving at the specified world position from the
+// specified light. Ambient lights do not arrive from a direction and return
+// black; use `ambient_light` for them instead.
+//
+// Shadows are applied if the object receives them, so this must be called
+// with positions on the object being drawn.
+fn incident_light(light: Light, world_position: vec3f) -> IncidentLight {
+  var incident: IncidentLight;
+
+  switch light.kind {
+    case DIRECTIONAL_LIGHT: {
+      incident.direction = -light.direction;
+      incident.color = light.color;
+    }
+    case POINT_LIGHT, SPOT_LIGHT: {
+      let to_light = light.position - world_position;
+      let distance = length(to_light);
+
+      incident.direction = to_light / distance;
+      incident.color = light.color * distance_attenuation(distance, light.range, light.decay);
+
+      if light.kind == SPOT_LIGHT {
+        let cos_theta = dot(-incident.direction, light.direction);
+        // Without a penumbra, both edges are equal, for which smoothstep is
+        // undefined, so the cone ends after a tiny fade.
+        let cos_penumbra = max(light.cos_penumbra, light.cos_angle + 1e-4);
+        incident.color *= smoothstep(light.cos_angle, cos_penumbra, cos_theta);
+      }
+    }
+    default: {
+      incident.direction = vec3f(0.0, 0.0, 1.0);
+      incident.color = vec3f(0.0);
+    }
+  }
+
+  if light.shadow >= 0 && object.receive_shadow != 0u {
+    if light.kind == POINT_LIGHT {
+      incident.color *= point_shadow_factor(shadows[light.shadow], light.position, world_position);
+    } else {
+      incident.color *= cascaded_shadow_factor(u32(light.shadow), light.shadow_count, world_position);
+    }
+  }
+
+  return incident;
+}
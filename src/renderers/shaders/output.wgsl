@@ -0,0 +1,112 @@
+// The discriminants of `ToneMapping`.
+const NO_TONE_MAPPING: u32 = 0;
+const REINHARD_TONE_MAPPING: u32 = 1;
+const ACES_FILMIC_TONE_MAPPING: u32 = 2;
+const AGX_TONE_MAPPING: u32 = 3;
+
+struct OutputSettings {
+  exposure: f32,
+  tone_mapping: u32,
+  // Whether the output format is not sRGB, so colors must be encoded here.
+  encode_srgb: u32,
+}
+
+@group(1) @binding(0) var<uniform> settings: OutputSettings;
+
+// Stephen Hill's fit of the ACES reference rendering transform (RRT) and
+// output device transform (ODT).
+fn aces_filmic(color: vec3f) -> vec3f {
+  let input_matrix = mat3x3f(
+    0.59719, 0.07600, 0.02840,
+    0.35458, 0.90834, 0.13383,
+    0.04823, 0.01566, 0.83777,
+  );
+  let output_matrix = mat3x3f(
+    1.60475, -0.10208, -0.00327,
+    -0.53108, 1.10813, -0.07276,
+    -0.07367, -0.00605, 1.07602,
+  );
+
+  // The fit expects colors exposed 0.6 times darker than the input.
+  let v = input_matrix * (color / 0.6);
+  let a = v * (v + 0.0245786) - 0.000090537;
+  let b = v * (0.983729 * v + 0.4329510) + 0.238081;
+
+  return saturate(output_matrix * (a / b));
+}
+
+// The AgX view transform of Blender, with its default look. See
+// https://github.com/EaryChow/AgX_LUT_Gen.
+fn agx(color: vec3f) -> vec3f {
+  let linear_srgb_to_linear_rec2020 = mat3x3f(
+    0.6274, 0.0691, 0.0164,
+    0.3293, 0.9195, 0.0880,
+    0.0433, 0.0113, 0.8956,
+  );
+  let linear_rec2020_to_linear_srgb = mat3x3f(
+    1.6605, -0.1246, -0.0182,
+    -0.5876, 1.1329, -0.1006,
+    -0.0728, -0.0083, 1.1187,
+  );
+  let inset_matrix = mat3x3f(
+    0.856627153315983, 0.137318972929847, 0.11189821299995,
+    0.0951212405381588, 0.761241990602591, 0.0767994186031903,
+    0.0482516061458583, 0.101439036467562, 0.811302368396859,
+  );
+  let outset_matrix = mat3x3f(
+    1.1271005818144368, -0.1413297634984383, -0.14132976349843826,
+    -0.11060664309660323, 1.157823702216272, -0.11060664309660294,
+    -0.016493938717834573, -0.016493938717834257, 1.2519364065950405,
+  );
+  let min_ev = -12.47393;
+  let max_ev = 4.026069;
+
+  var x = inset_matrix * (linear_srgb_to_linear_rec2020 * color);
+
+  // Log2 encoding, normalized to the range of exposure values.
+  x = saturate((log2(max(x, vec3f(1e-10))) - min_ev) / (max_ev - min_ev));
+
+  // Polynomial approximation of the default contrast curve.
+  let x2 = x * x;
+  let x4 = x2 * x2;
+  x = 15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x + 0.4298 * x2 + 0.1191 * x - 0.00232;
+
+  x = pow(max(outset_matrix * x, vec3f(0)), vec3f(2.2));
+
+  return saturate(linear_rec2020_to_linear_srgb * x);
+}
+
+fn srgb_encode(color: vec3f) -> vec3f {
+  return select(
+    1.055 * pow(color, vec3f(1 / 2.4)) - 0.055,
+    12.92 * color,
+    color <= vec3f(0.0031308),
+  );
+}
+
+@fragment
+fn fragmentMain(input: VertexOutput) -> @location(0) vec4f {
+  let hdr = textureSample(input_texture, input_sampler, input.uv);
+  var color = hdr.rgb * settings.exposure;
+
+  switch settings.tone_mapping {
+    case REINHARD_TONE_MAPPING: {
+      color = color / (1 + color);
+    }
+    case ACES_FILMIC_TONE_MAPPING: {
+      color = aces_filmic(color);
+    }
+    case AGX_TONE_MAPPING: {
+      color = agx(color);
+    }
+    case NO_TONE_MAPPING, default: {
+      color = saturate(color);
+    }
+  }
+
+  if settings.encode_srgb != 0 {
+    color = srgb_encode(color);
+  }
+
+  return vec4f(color, hdr.a);
+}
@@ -0,0 +1,8 @@
+// Fragment stage of the picking pass, which writes the pick ID of the object
+// into an integer render target. The triangle is unknown because the device
+// does not support the primitive index.
+
+@fragment
+fn fragmentMain(input: VertexOutput) -> @location(0) vec2u {
+  return vec2u(object.pick_id, 0xffffffffu);
+}
@@ -0,0 +1,10 @@
+// Fragment stage of the picking pass, which writes the pick ID of the object
+// and the index of the triangle into an integer render target.
+
+@fragment
+fn fragmentMain(
+  input: VertexOutput,
+  @builtin(primitive_index) primitive_index: u32,
+) -> @location(0) vec2u {
+  return vec2u(object.pick_id, primitive_index);
+}
@@ -0,0 +1,9 @@
+// Used if the GPU does not support cube map arrays, in which case only one
+// point light casts shadows.
+@group(2) @binding(4) var point_shadow_map: texture_depth_cube;
+
+// Compares the specified depth with the point shadow map in the specified
+// direction. There is only one cube map, so the layer is ignored.
+fn sample_point_shadow_map(direction: vec3f, layer: u32, depth: f32) -> f32 {
+  return textureSampleCompareLevel(point_shadow_map, shadow_sampler, direction, depth);
+}
@@ -0,0 +1,7 @@
+@group(2) @binding(4) var point_shadow_maps: texture_depth_cube_array;
+
+// Compares the specified depth with the specified cube map of the point shadow
+// map in the specified direction.
+fn sample_point_shadow_map(direction: vec3f, layer: u32, depth: f32) -> f32 {
+  return textureSampleCompareLevel(point_shadow_maps, shadow_sampler, direction, layer, depth);
+}
@@ -0,0 +1,11 @@
+@group(0) @binding(2) var depth_texture: texture_multisampled_2d<f32>;
+
+// Returns the depth of the scene at the specified UV coordinates, from 0 at the
+// near plane to 1 at the far plane of the camera. Only the first sample of
+// each pixel is read.
+fn scene_depth(uv: vec2f) -> f32 {
+  let size = textureDimensions(depth_texture);
+  let coordinates = min(vec2u(uv * vec2f(size)), size - 1);
+
+  return textureLoad(depth_texture, coordinates, 0).r;
+}
@@ -0,0 +1,51 @@
+struct ShadowCamera {
+  view_projection_matrix: mat4x4f,
+  // The position of a point light, and the distance by which the distances
+  // from it are divided.
+  position: vec3f,
+  range: f32,
+}
+
+// The camera of the drawn shadow map.
+@group(1) @binding(0) var<uniform> shadow_camera: ShadowCamera;
+
+struct ShadowVertexOutput {
+  @builtin(position) position: vec4f,
+  @location(0) world_position: vec3f,
+}
+
+// Transforms the vertices of shadow casters into the clip space of the shadow
+// camera. The shadow pass has no fragment stage, since it only writes depth,
+// except for point lights.
+@vertex
+fn shadowVertexMain(input: VertexInput) -> ShadowVertexOutput {
+  let instance_matrix = mat4x4f(
+    input.instance_matrix_0,
+    input.instance_matrix_1,
+    input.instance_matrix_2,
+    input.instance_matrix_3,
+  );
+
+  let world_position = object.model_matrix * instance_matrix * vec4f(input.position, 1);
+
+  var output: ShadowVertexOutput;
+
+  output.position = shadow_camera.view_projection_matrix * world_position;
+  output.world_position = world_position.xyz;
+
+  return output;
+}
+
+// Writes the distance of shadow casters from a point light as their depth.
+// Unlike the depth of a perspective projection, it is linear, so the shadow
+// bias is the same at all distances.
+//
+// The depth bias of the pipeline does not apply to written depths, so surfaces
+// at a grazing angle to the light, whose distance changes quickly between
+// texels, are pushed back by the same slope-scaled amount here.
+@fragment
+fn pointShadowFragmentMain(input: ShadowVertexOutput) -> @builtin(frag_depth) f32 {
+  let distance = length(input.world_position - shadow_camera.position);
+
+  return saturate((distance + 2.0 * fwidth(distance)) / shadow_camera.range);
+}
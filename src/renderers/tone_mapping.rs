@@ -0,0 +1,21 @@
+/// How a renderer maps the linear HDR colors of the scene, which can exceed
+/// `1.0`, into the displayable range. See
+/// [`ForwardRenderer::tone_mapping`](super::ForwardRenderer::tone_mapping).
+///
+/// The discriminants are the values of the tone mapping constants of the
+/// output shader.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash, Debug)]
+pub enum ToneMapping {
+    /// Clamps colors to the displayable range, which clips highlights.
+    #[default]
+    None = 0,
+    /// Maps each channel `c` to `c / (1 + c)`, which is simple and keeps
+    /// dark colors, but makes bright colors look washed out.
+    Reinhard = 1,
+    /// Approximates the filmic curve of the Academy Color Encoding System
+    /// (ACES), with high contrast and saturated highlights.
+    AcesFilmic = 2,
+    /// Blender's AgX view transform, which desaturates very bright colors
+    /// towards white as film does, rather than skewing their hue.
+    Agx = 3,
+}
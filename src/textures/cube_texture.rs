@@ -0,0 +1,63 @@
+use super::Texture;
+
+/// Six square images that form the faces of a cube around the origin, e.g. the
+/// surroundings of a scene as a [background](crate::Background::CubeMap).
+///
+/// Like [`Texture`], a cube texture only holds its data on the CPU. Renderers
+/// upload it to the GPU the first time it is used.
+pub struct CubeTexture {
+    /// The faces, which are private so that they keep the same square size.
+    faces: [Texture; 6],
+}
+
+impl CubeTexture {
+    /// Creates a cube texture from the specified faces, in the order +X, -X,
+    /// +Y, -Y, +Z, -Z. The faces are uploaded with the top row of their images
+    /// at the top, so their [`flip_y`](Texture::flip_y) is set to `false`, and
+    /// the other faces take the color space, mipmaps and sampler of the first
+    /// face.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a face is not square, or if the faces do not all have the
+    /// same size.
+    pub fn new(mut faces: [Texture; 6]) -> Self {
+        let size = faces[0].width;
+
+        assert!(
+            faces
+                .iter()
+                .all(|face| face.width == size && face.height == size),
+            "cube texture faces must be square and have the same size"
+        );
+
+        let (srgb, generate_mipmaps, sampler) =
+            (faces[0].srgb, faces[0].generate_mipmaps, faces[0].sampler);
+
+        for face in &mut faces {
+            face.flip_y = false;
+            face.srgb = srgb;
+            face.generate_mipmaps = generate_mipmaps;
+            face.sampler = sampler;
+        }
+
+        Self { faces }
+    }
+
+    /// Returns the faces in the order +X, -X, +Y, -Y, +Z, -Z, as seen from the
+    /// inside of the cube when looking along each axis. The top of the side
+    /// faces is towards +Y, and the top of the +Y and -Y faces is towards +Z
+    /// and -Z respectively, as when tilting up or down a camera that looks
+    /// along -Z.
+    ///
+    /// The [`srgb`](Texture::srgb), [`generate_mipmaps`](Texture::generate_mipmaps)
+    /// and [`sampler`](Texture::sampler) of the first face apply to all faces.
+    pub fn faces(&self) -> &[Texture; 6] {
+        &self.faces
+    }
+
+    /// Returns the width and height of the faces in pixels.
+    pub fn size(&self) -> u32 {
+        self.faces[0].width
+    }
+}
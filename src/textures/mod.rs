@@ -0,0 +1,10 @@
+//! Contains textures, which are images that materials sample from, and their
+//! sampling configuration.
+
+mod cube_texture;
+mod sampler;
+mod texture;
+
+pub use cube_texture::*;
+pub use sampler::*;
+pub use texture::*;
@@ -0,0 +1,33 @@
+/// Configures how a [`Texture`](super::Texture) is sampled.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Sampler {
+    /// How texture coordinates outside of `0.0..=1.0` along the U axis are
+    /// handled. Default is `Repeat`.
+    pub wrap_u: wgpu::AddressMode,
+    /// How texture coordinates outside of `0.0..=1.0` along the V axis are
+    /// handled. Default is `Repeat`.
+    pub wrap_v: wgpu::AddressMode,
+    /// The filter used when the texture is magnified, i.e. when a texel covers
+    /// more than one pixel. Default is `Linear`.
+    pub mag_filter: wgpu::FilterMode,
+    /// The filter used when the texture is minified, i.e. when a pixel covers
+    /// more than one texel. Default is `Linear`.
+    pub min_filter: wgpu::FilterMode,
+    /// The filter used between mipmap levels. Only has an effect if the
+    /// texture has mipmaps. Default is `Linear`.
+    pub mipmap_filter: wgpu::FilterMode,
+}
+
+impl Default for Sampler {
+    /// Returns the default sampler, which repeats the texture and filters
+    /// linearly.
+    fn default() -> Self {
+        Self {
+            wrap_u: wgpu::AddressMode::Repeat,
+            wrap_v: wgpu::AddressMode::Repeat,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use super::Sampler;
+
+/// 2D image that [materials](crate::Material) can sample from, with RGBA8
+/// pixel data.
+///
+/// A texture only holds its data on the CPU. Renderers upload it to the GPU the
+/// first time a material that uses it is rendered.
+pub struct Texture {
+    /// The width of the texture in pixels.
+    pub width: u32,
+    /// The height of the texture in pixels.
+    pub height: u32,
+    /// Pixel data with 4 bytes (red, green, blue, alpha) per pixel, row by row
+    /// from top to bottom.
+    pub data: Vec<u8>,
+    /// Whether the color channels are sRGB encoded, which is the case for
+    /// textures with colors e.g. photos. Textures with non-color data e.g.
+    /// roughness maps should set this to `false`. Default is `true`.
+    pub srgb: bool,
+    /// Whether to flip the image vertically when it is uploaded to the GPU, so
+    /// that the UV origin is at the bottom left corner of the image as in
+    /// [`BoxGeometry`](crate::BoxGeometry) and OBJ files. Should be `false` for
+    /// glTF files, where the UV origin is at the top left. Default is `true`.
+    pub flip_y: bool,
+    /// Whether to generate mipmaps for the texture, which reduces aliasing
+    /// when the texture is minified. Default is `true`.
+    pub generate_mipmaps: bool,
+    /// How the texture is sampled.
+    pub sampler: Sampler,
+}
+
+impl Texture {
+    /// Creates a texture from raw RGBA8 pixel data.
+    ///
+    /// * `width`: The width of the image in pixels.
+    /// * `height`: The height of the image in pixels.
+    /// * `data`: 4 bytes (red, green, blue, alpha) per pixel, row by row from
+    ///   top to bottom.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `data` is not `4 * width * height`, or if the
+    /// texture is empty.
+    pub fn from_rgba(width: u32, height: u32, data: Vec<u8>) -> Self {
+        assert!(width > 0 && height > 0, "texture must not be empty");
+        assert_eq!(
+            data.len(),
+            4 * width as usize * height as usize,
+            "texture data must have 4 bytes per pixel"
+        );
+
+        Self {
+            width,
+            height,
+            data,
+            srgb: true,
+            flip_y: true,
+            generate_mipmaps: true,
+            sampler: Sampler::default(),
+        }
+    }
+
+    /// Creates a texture by decoding the content of a PNG or JPEG file. Can be
+    /// included using e.g. the [`include_bytes!`] macro.
+    ///
+    /// # Returns
+    ///
+    /// [`Result`] that contains the texture, or an
+    /// [`ImageError`](image::ImageError) if the image format is not supported or
+    /// the data could not be decoded.
+    pub fn from_image_bytes(bytes: &[u8]) -> Result<Self, image::ImageError> {
+        let image = image::load_from_memory(bytes)?.into_rgba8();
+        let (width, height) = image.dimensions();
+
+        Ok(Self::from_rgba(width, height, image.into_raw()))
+    }
+
+    /// Returns the number of mipmap levels the texture has on the GPU,
+    /// including the full-size image.
+    pub fn mip_level_count(&self) -> u32 {
+        if self.generate_mipmaps {
+            32 - self.width.max(self.height).leading_zeros()
+        } else {
+            1
+        }
+    }
+
+    /// Returns the pixel data of every mipmap level, starting with the
+    /// full-size image, flipped vertically if [`flip_y`](Self::flip_y) is set.
+    ///
+    /// Each level is half the size of the previous one (rounded down, but at
+    /// least 1 pixel), and each of its pixels is the average of the 2x2
+    /// corresponding pixels in the previous level. sRGB colors are averaged in
+    /// linear space.
+    pub(crate) fn mip_levels(&self) -> Vec<Vec<u8>> {
+        let mut base = self.data.clone();
+
+        if self.flip_y {
+            let row_length = 4 * self.width as usize;
+
+            base = base
+                .chunks(row_length)
+                .rev()
+                .flatten()
+                .copied()
+                .collect();
+        }
+
+        let mut levels = vec![base];
+        let (mut width, mut height) = (self.width as usize, self.height as usize);
+
+        for _ in 1..self.mip_level_count() {
+            let previous = levels.last().unwrap();
+            let (next_width, next_height) = ((width / 2).max(1), (height / 2).max(1));
+            let mut level = Vec::with_capacity(4 * next_width * next_height);
+
+            for y in 0..next_height {
+                for x in 0..next_width {
+                    for channel in 0..4 {
+                        // Odd sizes skip the last row or column, and sizes of
+                        // 1 reuse the same row or column.
+                        let mut sum = 0.0;
+
+                        for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                            let sx = (2 * x + dx).min(width - 1);
+                            let sy = (2 * y + dy).min(height - 1);
+                            let value = previous[4 * (sy * width + sx) + channel];
+
+                            sum += self.decode(value, channel);
+                        }
+
+                        level.push(self.encode(sum / 4.0, channel));
+                    }
+                }
+            }
+
+            levels.push(level);
+            width = next_width;
+            height = next_height;
+        }
+
+        levels
+    }
+
+    /// Converts a channel value to a linear number between `0.0` and `1.0`.
+    fn decode(&self, value: u8, channel: usize) -> f32 {
+        let value = value as f32 / 255.0;
+
+        if self.srgb && channel < 3 {
+            if value <= 0.04045 {
+                value / 12.92
+            } else {
+                ((value + 0.055) / 1.055).powf(2.4)
+            }
+        } else {
+            value
+        }
+    }
+
+    /// Converts a linear number between `0.0` and `1.0` to a channel value.
+    fn encode(&self, value: f32, channel: usize) -> u8 {
+        let value = if self.srgb && channel < 3 {
+            if value <= 0.0031308 {
+                value * 12.92
+            } else {
+                1.055 * value.powf(1.0 / 2.4) - 0.055
+            }
+        } else {
+            value
+        };
+
+        (value * 255.0).round().clamp(0.0, 255.0) as u8
+    }
+}
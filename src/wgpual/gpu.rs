@@ -1,15 +1,23 @@
-use std::error::Error;
+use std::{error::Error, sync::mpsc};
 
 /// Container for several GPU objects used by renderers.
 pub struct Gpu<'window> {
-    /// The WGPU surface.
-    pub surface: wgpu::Surface<'window>,
+    /// The WGPU surface, or `None` if this GPU renders offscreen into
+    /// [`texture`](Self::texture).
+    pub surface: Option<wgpu::Surface<'window>>,
+    /// The WGPU adapter that the device was requested from.
+    pub adapter: wgpu::Adapter,
     /// The WGPU device.
     pub device: wgpu::Device,
     /// The WGPU queue.
     pub queue: wgpu::Queue,
-    /// The WGPU surface configuration.
+    /// The WGPU surface configuration. For an offscreen GPU, this describes
+    /// the format and size of [`texture`](Self::texture) and is never used to
+    /// configure a surface.
     pub surface_configuration: wgpu::SurfaceConfiguration,
+    /// The texture that an offscreen GPU renders into, or `None` if this GPU
+    /// renders to [`surface`](Self::surface).
+    pub texture: Option<wgpu::Texture>,
     /// The size of the surface in (logical) pixels.
     pub size: (u32, u32),
 }
@@ -20,6 +28,47 @@ pub struct GpuOptions {
     /// use an integrated GPU, while `HighPower` means to use a dedicated GPU.
     /// Default is `None` (provides no hint).
     pub power_preference: wgpu::PowerPreference,
+    /// If `true`, only the fallback adapter (usually a software rasterizer)
+    /// will be requested. Useful to render on machines without a GPU, such as
+    /// CI servers. Default is `false`.
+    pub force_fallback_adapter: bool,
+}
+
+impl Default for GpuOptions {
+    /// Returns the default GPU options.
+    fn default() -> Self {
+        Self {
+            power_preference: wgpu::PowerPreference::None,
+            force_fallback_adapter: false,
+        }
+    }
+}
+
+/// The texture that a frame is rendered into, obtained from [`Gpu::frame`].
+pub enum GpuFrame<'gpu> {
+    /// The next texture of the surface, which must be presented after
+    /// rendering.
+    Surface(wgpu::SurfaceTexture),
+    /// The offscreen texture of the GPU.
+    Texture(&'gpu wgpu::Texture),
+}
+
+impl GpuFrame<'_> {
+    /// Returns the texture to render into.
+    pub fn texture(&self) -> &wgpu::Texture {
+        match self {
+            GpuFrame::Surface(surface_texture) => &surface_texture.texture,
+            GpuFrame::Texture(texture) => texture,
+        }
+    }
+
+    /// Schedules the frame to be presented on the surface. Does nothing for
+    /// offscreen frames.
+    pub fn present(self) {
+        if let GpuFrame::Surface(surface_texture) = self {
+            surface_texture.present();
+        }
+    }
 }
 
 impl<'window> Gpu<'window> {
@@ -37,33 +86,20 @@ impl<'window> Gpu<'window> {
         window: impl Into<wgpu::SurfaceTarget<'window>>,
         options: GpuOptions,
     ) -> Result<Self, Box<dyn Error>> {
-        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
-            ..Default::default()
-        });
+        let instance = Self::create_instance();
 
         let surface = instance.create_surface(window)?;
 
         let adapter = instance
             .request_adapter(&wgpu::RequestAdapterOptions {
                 power_preference: options.power_preference,
-                force_fallback_adapter: false,
+                force_fallback_adapter: options.force_fallback_adapter,
                 compatible_surface: Some(&surface),
             })
             .await
-            .unwrap();
+            .ok_or("No suitable GPU adapter found")?;
 
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    label: None,
-                    required_features: wgpu::Features::empty(),
-                    required_limits: wgpu::Limits::default(),
-                    memory_hints: Default::default(),
-                },
-                None,
-            )
-            .await?;
+        let (device, queue) = Self::request_device(&adapter).await?;
 
         let surface_capabilities = surface.get_capabilities(&adapter);
 
@@ -90,25 +126,298 @@ impl<'window> Gpu<'window> {
         surface.configure(&device, &surface_configuration);
 
         Ok(Gpu {
-            surface,
+            surface: Some(surface),
+            adapter,
+            device,
+            queue,
+            surface_configuration,
+            texture: None,
+            size,
+        })
+    }
+
+    /// Creates a new GPU object that renders offscreen into an owned texture
+    /// instead of a window surface.
+    ///
+    /// * `width`: Width of the offscreen texture in pixels.
+    /// * `height`: Height of the offscreen texture in pixels.
+    /// * `options`: Configuration for the new renderer. Set
+    ///   [`force_fallback_adapter`](GpuOptions::force_fallback_adapter) to
+    ///   render on machines without a GPU.
+    ///
+    /// # Returns
+    ///
+    /// A [`Future`](std::future::Future) where the new state is returned, or
+    /// an error if no adapter was found or a
+    /// [`RequestDeviceError`](wgpu::RequestDeviceError).
+    pub async fn new_headless(
+        width: u32,
+        height: u32,
+        options: GpuOptions,
+    ) -> Result<Self, Box<dyn Error>> {
+        let instance = Self::create_instance();
+
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: options.power_preference,
+                force_fallback_adapter: options.force_fallback_adapter,
+                compatible_surface: None,
+            })
+            .await
+            .ok_or("No suitable GPU adapter found")?;
+
+        let (device, queue) = Self::request_device(&adapter).await?;
+
+        let size = (width.max(1), height.max(1));
+
+        let surface_configuration = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            width: size.0,
+            height: size.1,
+            present_mode: wgpu::PresentMode::Fifo,
+            desired_maximum_frame_latency: 2,
+            alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+            view_formats: vec![],
+        };
+
+        let texture = Self::create_target_texture(&device, &surface_configuration);
+
+        Ok(Gpu {
+            surface: None,
+            adapter,
             device,
             queue,
             surface_configuration,
+            texture: Some(texture),
             size,
         })
     }
 
+    fn create_instance() -> wgpu::Instance {
+        wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::all(),
+            ..Default::default()
+        })
+    }
+
+    /// Requests a device from the adapter, with the optional features that
+    /// renderers use if the adapter supports them:
+    ///
+    /// * [`SHADER_PRIMITIVE_INDEX`](wgpu::Features::SHADER_PRIMITIVE_INDEX),
+    ///   to pick the triangles of meshes.
+    /// * [`TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`](wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES),
+    ///   to use the sample counts that the adapter supports beyond `4`.
+    async fn request_device(
+        adapter: &wgpu::Adapter,
+    ) -> Result<(wgpu::Device, wgpu::Queue), wgpu::RequestDeviceError> {
+        let optional_features = wgpu::Features::SHADER_PRIMITIVE_INDEX
+            .union(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES);
+
+        adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: None,
+                    required_features: adapter.features() & optional_features,
+                    required_limits: wgpu::Limits::default(),
+                    memory_hints: Default::default(),
+                },
+                None,
+            )
+            .await
+    }
+
+    /// Creates a texture that can be rendered into and copied from, with the
+    /// format and size of the given configuration.
+    fn create_target_texture(
+        device: &wgpu::Device,
+        configuration: &wgpu::SurfaceConfiguration,
+    ) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Offscreen target texture"),
+            size: wgpu::Extent3d {
+                width: configuration.width,
+                height: configuration.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: configuration.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                .union(wgpu::TextureUsages::COPY_SRC)
+                .union(wgpu::TextureUsages::TEXTURE_BINDING),
+            view_formats: &[],
+        })
+    }
+
+    /// Returns the features of the specified texture format that the device
+    /// allows. These are the adapter's features if the device has
+    /// [`TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES`](wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES)
+    /// or does not support all WebGPU formats, and the features that WebGPU
+    /// guarantees otherwise.
+    pub fn texture_format_features(
+        &self,
+        format: wgpu::TextureFormat,
+    ) -> wgpu::TextureFormatFeatures {
+        let adapter_specific = self
+            .device
+            .features()
+            .contains(wgpu::Features::TEXTURE_ADAPTER_SPECIFIC_FORMAT_FEATURES);
+        let downlevel = !self
+            .adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(wgpu::DownlevelFlags::WEBGPU_TEXTURE_FORMAT_SUPPORT);
+
+        if adapter_specific || downlevel {
+            self.adapter.get_texture_format_features(format)
+        } else {
+            format.guaranteed_format_features(self.device.features())
+        }
+    }
+
     /// Reconfigures the surface to the specified width and height. Note that
     /// this does not resize the surface, and should be called after the surface
     /// has been resized.
+    ///
+    /// For an offscreen GPU, the offscreen texture is recreated with the new
+    /// size.
     pub fn set_size(&mut self, width: u32, height: u32) {
         if width > 0 && height > 0 {
             self.size.0 = width;
             self.size.1 = height;
             self.surface_configuration.width = width;
             self.surface_configuration.height = height;
-            self.surface
-                .configure(&self.device, &self.surface_configuration);
+
+            if let Some(surface) = &self.surface {
+                surface.configure(&self.device, &self.surface_configuration);
+            }
+
+            if self.texture.is_some() {
+                self.texture = Some(Self::create_target_texture(
+                    &self.device,
+                    &self.surface_configuration,
+                ));
+            }
         }
     }
+
+    /// Returns the texture to render the next frame into: the next surface
+    /// texture, or the offscreen texture if this GPU has no surface.
+    pub fn frame(&self) -> Result<GpuFrame<'_>, wgpu::SurfaceError> {
+        match (&self.surface, &self.texture) {
+            (Some(surface), _) => Ok(GpuFrame::Surface(surface.get_current_texture()?)),
+            (None, Some(texture)) => Ok(GpuFrame::Texture(texture)),
+            (None, None) => Err(wgpu::SurfaceError::Lost),
+        }
+    }
+
+    /// Copies the given texture from the GPU and returns its pixels row by
+    /// row, from top to bottom, without any row padding.
+    ///
+    /// The texture must have been created with
+    /// [`COPY_SRC`](wgpu::TextureUsages::COPY_SRC) usage and must have a
+    /// format with a fixed block size e.g. not a depth-stencil format.
+    ///
+    /// This method blocks until the GPU has finished all submitted work, so it
+    /// cannot be used on the Web, where the main thread must not block.
+    pub fn read_texture(
+        &self,
+        texture: &wgpu::Texture,
+    ) -> Result<Vec<u8>, wgpu::BufferAsyncError> {
+        self.read_texture_region(texture, (0, 0), (texture.width(), texture.height()))
+    }
+
+    /// Copies a rectangular region of the given texture from the GPU and
+    /// returns its pixels row by row, from top to bottom, without any row
+    /// padding. See [`read_texture`](Self::read_texture).
+    ///
+    /// * `origin`: The top-left corner of the region in pixels.
+    /// * `size`: The width and height of the region in pixels, which must fit
+    ///   into the texture.
+    pub fn read_texture_region(
+        &self,
+        texture: &wgpu::Texture,
+        origin: (u32, u32),
+        size: (u32, u32),
+    ) -> Result<Vec<u8>, wgpu::BufferAsyncError> {
+        let bytes_per_pixel = texture
+            .format()
+            .block_copy_size(Some(wgpu::TextureAspect::All))
+            .unwrap_or(4);
+
+        let (width, height) = size;
+        let unpadded_bytes_per_row = width * bytes_per_pixel;
+        let padded_bytes_per_row = unpadded_bytes_per_row
+            .div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Readback buffer"),
+            size: (padded_bytes_per_row * height) as u64,
+            usage: wgpu::BufferUsages::MAP_READ.union(wgpu::BufferUsages::COPY_DST),
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: origin.0,
+                    y: origin.1,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = mpsc::channel();
+
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            // The receiver outlives the callback, so sending cannot fail.
+            let _ = sender.send(result);
+        });
+
+        self.device.poll(wgpu::Maintain::Wait);
+
+        receiver
+            .recv()
+            .unwrap_or(Err(wgpu::BufferAsyncError))?;
+
+        let mut pixels = Vec::with_capacity((unpadded_bytes_per_row * height) as usize);
+
+        {
+            let data = slice.get_mapped_range();
+
+            for row in data.chunks(padded_bytes_per_row as usize) {
+                pixels.extend_from_slice(&row[..unpadded_bytes_per_row as usize]);
+            }
+        }
+
+        buffer.unmap();
+
+        Ok(pixels)
+    }
 }
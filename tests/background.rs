@@ -0,0 +1,94 @@
+mod common;
+
+use std::rc::Rc;
+
+use common::*;
+use poli_gon::{Background, CubeTexture, Texture};
+
+/// Returns a texture of 2 x 1 pixels of the specified color.
+fn solid(color: [u8; 4]) -> Texture {
+    Texture::from_rgba(2, 1, color.repeat(2))
+}
+
+#[test]
+fn equirectangular_background_is_replaced() {
+    let Some(mut renderer) = renderer() else {
+        return;
+    };
+    let scene = scene();
+
+    renderer.background = Background::Equirectangular(Rc::new(solid([255, 0, 0, 255])));
+
+    assert_pixel(
+        center(&render(&renderer, &scene, &camera())),
+        [255, 0, 0, 255],
+        0,
+    );
+
+    // The first texture is dropped before the second one is created.
+    renderer.background = Background::ClearColor;
+    renderer.background = Background::Equirectangular(Rc::new(solid([0, 255, 0, 255])));
+
+    assert_pixel(
+        center(&render(&renderer, &scene, &camera())),
+        [0, 255, 0, 255],
+        0,
+    );
+}
+
+#[test]
+fn cube_map_background_shows_the_face_in_view() {
+    let Some(mut renderer) = renderer() else {
+        return;
+    };
+    let scene = scene();
+    let colors = [
+        [255, 0, 0, 255],
+        [0, 255, 0, 255],
+        [0, 0, 255, 255],
+        [255, 255, 0, 255],
+        [0, 255, 255, 255],
+        [255, 0, 255, 255],
+    ];
+    let cube_texture =
+        CubeTexture::new(colors.map(|color| Texture::from_rgba(1, 1, color.to_vec())));
+
+    assert_eq!(cube_texture.size(), 1);
+    assert!(cube_texture.faces().iter().all(|face| !face.flip_y));
+
+    renderer.background = Background::CubeMap(Rc::new(cube_texture));
+
+    // The camera looks along -Z.
+    assert_pixel(center(&render(&renderer, &scene, &camera())), colors[5], 0);
+}
+
+#[test]
+#[should_panic(expected = "cube texture faces must be square and have the same size")]
+fn cube_texture_faces_must_have_the_same_size() {
+    CubeTexture::new(std::array::from_fn(|i| {
+        let size = if i == 3 { 2 } else { 1 };
+
+        Texture::from_rgba(size, size, vec![0; 4 * (size * size) as usize])
+    }));
+}
+
+#[test]
+fn gradient_background_goes_from_bottom_to_top() {
+    let Some(mut renderer) = renderer() else {
+        return;
+    };
+    let scene = scene();
+
+    renderer.background = Background::Gradient {
+        top: WHITE,
+        bottom: BLACK,
+    };
+
+    let image = render(&renderer, &scene, &camera());
+    let [top, middle, bottom] = [0, HEIGHT / 2, HEIGHT - 1].map(|y| pixel(&image, WIDTH / 2, y));
+
+    // Looking straight ahead is halfway between the colors, which is 188 in
+    // sRGB.
+    assert_pixel(middle, [188, 188, 188, 255], 2);
+    assert!(top[0] > middle[0] && middle[0] > bottom[0]);
+}
@@ -0,0 +1,159 @@
+//! Helpers shared by the integration tests, which render scenes offscreen on
+//! the fallback adapter and check the pixels of the result.
+
+#![allow(dead_code)]
+
+use std::rc::Rc;
+
+use poli_gon::{
+    BasicMaterial, BoxGeometry, BufferGeometry, Camera, CameraKind, ForwardRenderer,
+    ForwardRendererOptions, Gpu, GpuOptions, Material, Mesh, Object3d, Object3dKind,
+};
+use poli_math::Color;
+
+pub const WIDTH: u32 = 64;
+pub const HEIGHT: u32 = 48;
+
+pub const RED: Color = Color {
+    r: 1.0,
+    g: 0.0,
+    b: 0.0,
+};
+pub const GREEN: Color = Color {
+    r: 0.0,
+    g: 1.0,
+    b: 0.0,
+};
+pub const BLUE: Color = Color {
+    r: 0.0,
+    g: 0.0,
+    b: 1.0,
+};
+pub const WHITE: Color = Color {
+    r: 1.0,
+    g: 1.0,
+    b: 1.0,
+};
+pub const BLACK: Color = Color {
+    r: 0.0,
+    g: 0.0,
+    b: 0.0,
+};
+
+/// Creates a headless renderer of [`WIDTH`] x [`HEIGHT`] pixels on the
+/// fallback adapter, with an opaque black clear color. Returns `None` if the
+/// machine has no fallback adapter, in which case the test should be skipped.
+pub fn renderer() -> Option<ForwardRenderer<'static>> {
+    renderer_with_options(ForwardRendererOptions::default())
+}
+
+/// Like [`renderer`], with the specified options. The fallback adapter is
+/// always forced.
+pub fn renderer_with_options(options: ForwardRendererOptions) -> Option<ForwardRenderer<'static>> {
+    let adapter_found = pollster::block_on(Gpu::new_headless(
+        1,
+        1,
+        GpuOptions {
+            force_fallback_adapter: true,
+            ..Default::default()
+        },
+    ))
+    .is_ok();
+
+    if !adapter_found {
+        eprintln!("skipping test: no fallback adapter found");
+        return None;
+    }
+
+    let mut renderer = pollster::block_on(ForwardRenderer::new_headless(
+        WIDTH,
+        HEIGHT,
+        ForwardRendererOptions {
+            force_fallback_adapter: true,
+            ..options
+        },
+    ));
+
+    renderer.clear_color = BLACK;
+    renderer.clear_alpha = 1.0;
+
+    Some(renderer)
+}
+
+/// Creates a perspective camera at `(0, 0, 5)` that looks along -Z, with the
+/// aspect ratio of the renderers.
+pub fn camera() -> Rc<Object3d> {
+    let camera = Rc::new(Object3d::from(Camera::new(CameraKind::PerspectiveCamera {
+        vfov_radians: 1.0,
+        aspect: WIDTH as f32 / HEIGHT as f32,
+        near: 0.1,
+        far: 100.0,
+    })));
+
+    Object3d::translate(&camera, &(0.0, 0.0, 5.0).into());
+
+    camera
+}
+
+/// Creates an empty scene.
+pub fn scene() -> Rc<Object3d> {
+    Rc::new(Object3d::new(Object3dKind::Group))
+}
+
+/// Adds a unit box with the specified material to the scene, centered at the
+/// specified position.
+pub fn add_box(
+    scene: &Rc<Object3d>,
+    material: Rc<dyn Material>,
+    position: (f32, f32, f32),
+) -> Rc<Object3d> {
+    let geometry = Rc::new(BufferGeometry::from_geometry(&BoxGeometry::default()));
+    let mesh = Rc::new(Object3d::from(Mesh::new(geometry, material)));
+
+    Object3d::add(scene, &mesh);
+    Object3d::translate(&mesh, &position.into());
+
+    mesh
+}
+
+/// Returns an unlit material of the specified color.
+pub fn basic(color: Color) -> Rc<dyn Material> {
+    Rc::new(BasicMaterial::new(color))
+}
+
+/// Renders the scene as seen from the specified camera object and returns the
+/// RGBA8 pixels.
+pub fn render(renderer: &ForwardRenderer, scene: &Rc<Object3d>, camera: &Rc<Object3d>) -> Vec<u8> {
+    let Object3dKind::Camera(camera) = &camera.kind else {
+        panic!("not a camera");
+    };
+
+    renderer
+        .render_to_image(Rc::clone(scene), &camera.borrow())
+        .unwrap()
+}
+
+/// Returns the RGBA8 pixel at the specified position of an image of
+/// [`WIDTH`] x [`HEIGHT`] pixels.
+pub fn pixel(image: &[u8], x: u32, y: u32) -> [u8; 4] {
+    let i = 4 * (y * WIDTH + x) as usize;
+
+    [image[i], image[i + 1], image[i + 2], image[i + 3]]
+}
+
+/// Returns the pixel at the center of an image.
+pub fn center(image: &[u8]) -> [u8; 4] {
+    pixel(image, WIDTH / 2, HEIGHT / 2)
+}
+
+/// Asserts that each channel of `actual` is within `tolerance` of `expected`.
+#[track_caller]
+pub fn assert_pixel(actual: [u8; 4], expected: [u8; 4], tolerance: u8) {
+    assert!(
+        actual
+            .iter()
+            .zip(expected)
+            .all(|(&a, e)| a.abs_diff(e) <= tolerance),
+        "pixel {actual:?} is not within {tolerance} of {expected:?}"
+    );
+}
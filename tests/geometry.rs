@@ -0,0 +1,73 @@
+mod common;
+
+use std::rc::Rc;
+
+use common::*;
+use poli_gon::{BufferGeometry, Mesh, Object3d, Object3dKind};
+
+/// Returns the geometry of the specified mesh.
+fn geometry(mesh: &Object3d) -> Rc<BufferGeometry> {
+    let Object3dKind::Mesh(mesh) = &mesh.kind else {
+        panic!("not a mesh");
+    };
+
+    Rc::clone(&mesh.geometry)
+}
+
+#[test]
+fn geometry_is_uploaded_again_after_needs_update() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+    let camera = camera();
+    let geometry = geometry(&add_box(&scene, basic(RED), (0.0, 0.0, 0.0)));
+
+    assert_pixel(
+        center(&render(&renderer, &scene, &camera)),
+        [255, 0, 0, 255],
+        0,
+    );
+
+    for x in geometry.position.borrow_mut().iter_mut().step_by(3) {
+        *x += 3.0;
+    }
+
+    // The cached buffers are kept until the geometry is marked as changed.
+    assert_pixel(
+        center(&render(&renderer, &scene, &camera)),
+        [255, 0, 0, 255],
+        0,
+    );
+
+    geometry.needs_update();
+
+    assert_pixel(
+        center(&render(&renderer, &scene, &camera)),
+        [0, 0, 0, 255],
+        0,
+    );
+}
+
+#[test]
+fn shared_geometry_is_drawn_by_every_mesh() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+    let camera = camera();
+    let left = add_box(&scene, basic(RED), (-1.5, 0.0, 0.0));
+    let right = Rc::new(Object3d::from(Mesh::new(geometry(&left), basic(GREEN))));
+
+    Object3d::add(&scene, &right);
+    Object3d::translate(&right, &(1.5, 0.0, 0.0).into());
+
+    let image = render(&renderer, &scene, &camera);
+
+    assert_pixel(
+        pixel(&image, WIDTH / 2 - 12, HEIGHT / 2),
+        [255, 0, 0, 255],
+        0,
+    );
+    assert_pixel(
+        pixel(&image, WIDTH / 2 + 12, HEIGHT / 2),
+        [0, 255, 0, 255],
+        0,
+    );
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use std::rc::Rc;
+
+use common::*;
+use poli_gon::Object3dKind;
+
+#[test]
+fn renders_offscreen_to_rgba8_pixels() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+    let camera = camera();
+
+    add_box(&scene, basic(RED), (0.0, 0.0, 0.0));
+
+    let image = render(&renderer, &scene, &camera);
+
+    assert_eq!(image.len(), (4 * WIDTH * HEIGHT) as usize);
+    assert_pixel(center(&image), [255, 0, 0, 255], 0);
+    assert_pixel(pixel(&image, 0, 0), [0, 0, 0, 255], 0);
+}
+
+#[test]
+fn clear_color_fills_empty_scene() {
+    let Some(mut renderer) = renderer() else {
+        return;
+    };
+
+    renderer.clear_color = BLUE;
+
+    let image = render(&renderer, &scene(), &camera());
+
+    for (x, y) in [(0, 0), (WIDTH - 1, HEIGHT - 1), (WIDTH / 2, HEIGHT / 2)] {
+        assert_pixel(pixel(&image, x, y), [0, 0, 255, 255], 0);
+    }
+}
+
+#[test]
+fn image_follows_renderer_size() {
+    let Some(mut renderer) = renderer() else {
+        return;
+    };
+    let scene = scene();
+    let camera = camera();
+
+    add_box(&scene, basic(GREEN), (0.0, 0.0, 0.0));
+    renderer.set_size(WIDTH / 2, HEIGHT / 2);
+
+    let Object3dKind::Camera(camera) = &camera.kind else {
+        unreachable!()
+    };
+    let image = renderer
+        .render_to_image(Rc::clone(&scene), &camera.borrow())
+        .unwrap();
+
+    assert_eq!(image.len(), (WIDTH * HEIGHT) as usize);
+
+    let i = 4 * ((HEIGHT / 4) * (WIDTH / 2) + WIDTH / 4) as usize;
+    assert_pixel(
+        [image[i], image[i + 1], image[i + 2], image[i + 3]],
+        [0, 255, 0, 255],
+        0,
+    );
+}
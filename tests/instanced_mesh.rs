@@ -0,0 +1,90 @@
+mod common;
+
+use std::rc::Rc;
+
+use common::*;
+use poli_gon::{BoxGeometry, BufferGeometry, InstancedMesh, Object3d, Object3dKind};
+use poli_math::Matrix4;
+
+/// Returns a matrix that translates by the specified offset.
+fn translation(x: f32, y: f32) -> Matrix4 {
+    let mut matrix = Matrix4::identity();
+
+    matrix.translate(&(x, y, 0.0).into());
+    matrix
+}
+
+#[test]
+fn instances_have_their_own_matrix_and_color() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+    let geometry = Rc::new(BufferGeometry::from_geometry(&BoxGeometry::default()));
+    let instanced_mesh = Rc::new(Object3d::from(InstancedMesh::new(
+        geometry,
+        basic(WHITE),
+        3,
+    )));
+
+    Object3d::add(&scene, &instanced_mesh);
+
+    let Object3dKind::InstancedMesh(instances) = &instanced_mesh.kind else {
+        unreachable!();
+    };
+
+    for (index, (x, color)) in [(-1.5, RED), (0.0, GREEN), (1.5, BLUE)]
+        .into_iter()
+        .enumerate()
+    {
+        instances.set_matrix_at(index, &translation(x, 0.0));
+        instances.set_color_at(index, &color);
+    }
+
+    let (left, right, top) = (
+        (WIDTH / 2 - 15, HEIGHT / 2),
+        (WIDTH / 2 + 15, HEIGHT / 2),
+        (WIDTH / 2, HEIGHT / 2 - 15),
+    );
+
+    let image = render(&renderer, &scene, &camera());
+
+    assert_pixel(pixel(&image, left.0, left.1), [255, 0, 0, 255], 0);
+    assert_pixel(center(&image), [0, 255, 0, 255], 0);
+    assert_pixel(pixel(&image, right.0, right.1), [0, 0, 255, 255], 0);
+    assert_pixel(pixel(&image, top.0, top.1), [0, 0, 0, 255], 0);
+
+    // Only the middle instance is uploaded again.
+    instances.set_matrix_at(1, &translation(0.0, 1.5));
+    instances.set_color_at(1, &WHITE);
+
+    let image = render(&renderer, &scene, &camera());
+
+    assert_pixel(pixel(&image, left.0, left.1), [255, 0, 0, 255], 0);
+    assert_pixel(center(&image), [0, 0, 0, 255], 0);
+    assert_pixel(pixel(&image, right.0, right.1), [0, 0, 255, 255], 0);
+    assert_pixel(pixel(&image, top.0, top.1), [255, 255, 255, 255], 0);
+}
+
+#[test]
+fn bounding_box_follows_instance_matrices() {
+    let geometry = Rc::new(BufferGeometry::from_geometry(&BoxGeometry::default()));
+    let instanced_mesh = InstancedMesh::new(geometry, basic(WHITE), 2);
+
+    instanced_mesh.set_matrix_at(1, &translation(3.0, 0.0));
+
+    let bounding_box = instanced_mesh.compute_bounding_box();
+
+    assert_eq!(bounding_box.max.x, 3.5);
+    assert_eq!(instanced_mesh.compute_bounding_box().max.x, 3.5);
+
+    // Setting a matrix discards the cached bounds.
+    instanced_mesh.set_matrix_at(1, &translation(-3.0, 0.0));
+
+    assert_eq!(instanced_mesh.compute_bounding_box().max.x, 0.5);
+    assert_eq!(instanced_mesh.compute_bounding_box().min.x, -3.5);
+
+    let bounding_sphere = instanced_mesh.compute_bounding_sphere();
+
+    // The corners of both boxes are 1.5 + √0.75 from the center.
+    assert_eq!(bounding_sphere.center.x, -1.5);
+    assert!((bounding_sphere.radius - (1.5 + 0.75f32.sqrt())).abs() < 1e-5);
+}
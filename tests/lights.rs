@@ -0,0 +1,68 @@
+mod common;
+
+use std::rc::Rc;
+
+use common::*;
+use poli_gon::{LambertMaterial, Light, LightKind, Object3d};
+
+/// Adds a white wall facing the camera at `z = -1`, which is lit by lights.
+fn add_wall(scene: &Rc<Object3d>) {
+    let wall = add_box(
+        scene,
+        Rc::new(LambertMaterial::new(WHITE)),
+        (0.0, 0.0, -1.0),
+    );
+
+    Object3d::scale(&wall, &(20.0, 20.0, 0.1).into());
+}
+
+/// Adds a white spot light at the camera's position, pointing at the wall.
+fn add_spot_light(scene: &Rc<Object3d>, penumbra: f32, intensity: f32) {
+    let light = Rc::new(Object3d::from(Light::new(
+        LightKind::SpotLight {
+            range: 0.0,
+            angle: 0.15,
+            penumbra,
+            decay: 0.0,
+        },
+        WHITE,
+        intensity,
+    )));
+
+    Object3d::add(scene, &light);
+    Object3d::translate(&light, &(0.0, 0.0, 5.0).into());
+}
+
+#[test]
+fn spot_light_without_penumbra_has_a_hard_edge() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+
+    add_wall(&scene);
+    add_spot_light(&scene, 0.0, 10.0);
+
+    let image = render(&renderer, &scene, &camera());
+    let lit = center(&image);
+
+    assert!(lit[0] > 200, "center of the cone is not lit: {lit:?}");
+    assert_pixel(pixel(&image, WIDTH / 2 + 12, HEIGHT / 2), [0, 0, 0, 255], 0);
+    assert_pixel(pixel(&image, 0, 0), [0, 0, 0, 255], 0);
+}
+
+#[test]
+fn spot_light_penumbra_fades_towards_the_edge() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+
+    add_wall(&scene);
+    add_spot_light(&scene, 1.0, 0.5);
+
+    let image = render(&renderer, &scene, &camera());
+    let inner = center(&image)[0];
+    let outer = pixel(&image, WIDTH / 2 + 4, HEIGHT / 2)[0];
+
+    assert!(
+        inner > outer && outer > 0,
+        "no fade from {inner} to {outer}"
+    );
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::*;
+use poli_gon::ForwardRendererOptions;
+
+/// Returns the number of pixels in the middle row whose red channel is
+/// neither black nor fully red, i.e. that are partially covered by a red box.
+fn count_partially_covered(sample_count: u32) -> Option<(u32, usize)> {
+    let renderer = renderer_with_options(ForwardRendererOptions {
+        sample_count,
+        ..Default::default()
+    })?;
+    let scene = scene();
+
+    // The edges of the box do not fall on pixel boundaries.
+    add_box(&scene, basic(RED), (0.05, 0.0, 0.0));
+
+    let image = render(&renderer, &scene, &camera());
+    let partially_covered = (0..WIDTH)
+        .filter(|&x| (20..235).contains(&pixel(&image, x, HEIGHT / 2)[0]))
+        .count();
+
+    Some((renderer.sample_count(), partially_covered))
+}
+
+#[test]
+fn multisampling_smooths_edges() {
+    let Some((1, aliased)) = count_partially_covered(1) else {
+        return;
+    };
+    let Some((4, smoothed)) = count_partially_covered(4) else {
+        panic!("4 samples are supported by all devices");
+    };
+
+    assert_eq!(aliased, 0);
+    assert!(smoothed > 0);
+}
+
+#[test]
+fn unsupported_sample_counts_are_lowered() {
+    for requested in [3, 16, 64] {
+        let Some((sample_count, _)) = count_partially_covered(requested) else {
+            return;
+        };
+
+        assert!(sample_count.is_power_of_two());
+        assert!((1..=requested).contains(&sample_count));
+    }
+
+    let Some((sample_count, _)) = count_partially_covered(0) else {
+        return;
+    };
+
+    assert_eq!(sample_count, 1);
+}
+
+#[test]
+fn multisampling_keeps_depth_testing() {
+    let Some(renderer) = renderer_with_options(ForwardRendererOptions {
+        sample_count: 4,
+        ..Default::default()
+    }) else {
+        return;
+    };
+    let scene = scene();
+
+    // The box behind is added last, so it is drawn over the front one without
+    // depth testing.
+    add_box(&scene, basic(RED), (0.0, 0.0, 0.0));
+    add_box(&scene, basic(BLUE), (0.0, 0.0, -2.0));
+
+    let image = render(&renderer, &scene, &camera());
+
+    assert_pixel(center(&image), [255, 0, 0, 255], 0);
+}
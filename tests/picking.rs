@@ -0,0 +1,86 @@
+mod common;
+
+use std::rc::Rc;
+
+use common::*;
+use poli_gon::{Object3d, Object3dKind, Raycaster};
+
+/// Adds a red box on the left and a blue box on the right of the view.
+fn two_boxes(scene: &Rc<Object3d>) -> (Rc<Object3d>, Rc<Object3d>) {
+    let left = add_box(scene, basic(RED), (-1.5, 0.0, 0.0));
+    let right = add_box(scene, basic(BLUE), (1.5, 0.0, 0.0));
+
+    (left, right)
+}
+
+#[test]
+fn pick_returns_mesh_and_triangle_at_pixel() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+    let camera_object = camera();
+    let (left, right) = two_boxes(&scene);
+    let Object3dKind::Camera(camera) = &camera_object.kind else {
+        unreachable!()
+    };
+
+    for (object, x) in [(&left, WIDTH / 2 - 15), (&right, WIDTH / 2 + 15)] {
+        let y = HEIGHT / 2 + 3;
+        let picked = renderer
+            .pick(Rc::clone(&scene), &camera.borrow(), x, y)
+            .unwrap()
+            .expect("a mesh at the pixel");
+
+        assert!(Rc::ptr_eq(&picked.object, object));
+
+        // The triangle is the one a ray through the center of the pixel hits.
+        if let Some(face_index) = picked.face_index {
+            let ndc_x = 2.0 * (x as f32 + 0.5) / WIDTH as f32 - 1.0;
+            let ndc_y = 1.0 - 2.0 * (y as f32 + 0.5) / HEIGHT as f32;
+            let intersections =
+                Raycaster::from_camera(ndc_x, ndc_y, &camera.borrow()).intersect_object(&scene);
+
+            assert!(Rc::ptr_eq(&intersections[0].object, object));
+            assert_eq!(face_index, intersections[0].face_index);
+        }
+    }
+
+    let background = renderer
+        .pick(Rc::clone(&scene), &camera.borrow(), WIDTH / 2, 0)
+        .unwrap();
+
+    assert!(background.is_none());
+}
+
+#[test]
+fn pick_rect_returns_every_mesh_in_rectangle() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+    let camera_object = camera();
+    let (left, right) = two_boxes(&scene);
+    let Object3dKind::Camera(camera) = &camera_object.kind else {
+        unreachable!()
+    };
+
+    let picked = renderer
+        .pick_rect(Rc::clone(&scene), &camera.borrow(), 0, 0, WIDTH, HEIGHT)
+        .unwrap();
+
+    assert_eq!(picked.len(), 2);
+    assert!(picked.iter().any(|object| Rc::ptr_eq(object, &left)));
+    assert!(picked.iter().any(|object| Rc::ptr_eq(object, &right)));
+
+    // Only the right box is inside the right half.
+    let picked = renderer
+        .pick_rect(
+            Rc::clone(&scene),
+            &camera.borrow(),
+            WIDTH / 2,
+            0,
+            WIDTH / 2,
+            HEIGHT,
+        )
+        .unwrap();
+
+    assert_eq!(picked.len(), 1);
+    assert!(Rc::ptr_eq(&picked[0], &right));
+}
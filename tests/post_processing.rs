@@ -0,0 +1,166 @@
+mod common;
+
+use std::{cell::RefCell, rc::Rc};
+
+use common::*;
+use poli_gon::{
+    ForwardRenderer, ForwardRendererOptions, Object3d, Object3dKind, Pass, PostProcessor,
+    VignettePass,
+};
+
+/// Pass that replaces the image with a color, followed by a configurable
+/// number of unused bytes.
+struct FillPass {
+    color: RefCell<[f32; 4]>,
+    padding: RefCell<usize>,
+}
+
+impl Pass for FillPass {
+    fn fragment_shader(&self) -> &'static str {
+        "
+        struct FillPass {
+          color: vec4f,
+        }
+
+        @group(1) @binding(0) var<uniform> fill: FillPass;
+
+        @fragment
+        fn fragmentMain(input: VertexOutput) -> @location(0) vec4f {
+          return fill.color;
+        }
+        "
+    }
+
+    fn uniform(&self) -> Vec<u8> {
+        let mut uniform = bytemuck::bytes_of(&*self.color.borrow()).to_vec();
+
+        uniform.resize(uniform.len() + *self.padding.borrow(), 0);
+        uniform
+    }
+}
+
+/// Pass that outputs white where the scene was drawn and black elsewhere,
+/// according to the depth of the scene.
+struct DepthPass;
+
+impl Pass for DepthPass {
+    fn fragment_shader(&self) -> &'static str {
+        "
+        @fragment
+        fn fragmentMain(input: VertexOutput) -> @location(0) vec4f {
+          return vec4f(vec3f(select(0.0, 1.0, scene_depth(input.uv) < 1)), 1);
+        }
+        "
+    }
+}
+
+fn post_process(
+    post_processor: &PostProcessor,
+    renderer: &ForwardRenderer,
+    scene: &Rc<Object3d>,
+    camera: &Rc<Object3d>,
+) -> Vec<u8> {
+    let Object3dKind::Camera(camera) = &camera.kind else {
+        panic!("not a camera");
+    };
+
+    post_processor
+        .render_to_image(renderer, Rc::clone(scene), &camera.borrow())
+        .unwrap()
+}
+
+#[test]
+fn passes_are_applied_in_order() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+    let camera = camera();
+    let post_processor = PostProcessor::new(&renderer);
+
+    add_box(&scene, basic(WHITE), (0.0, 0.0, 0.0));
+
+    let image = post_process(&post_processor, &renderer, &scene, &camera);
+
+    assert_eq!(image, render(&renderer, &scene, &camera));
+
+    post_processor.add_pass(Rc::new(VignettePass::default()));
+
+    let image = post_process(&post_processor, &renderer, &scene, &camera);
+
+    assert_pixel(center(&image), [255, 255, 255, 255], 2);
+    assert!(pixel(&image, 0, 0)[0] == 0);
+
+    post_processor.add_pass(Rc::new(FillPass {
+        color: RefCell::new([0.0, 0.0, 1.0, 1.0]),
+        padding: RefCell::new(0),
+    }));
+
+    let image = post_process(&post_processor, &renderer, &scene, &camera);
+
+    assert_pixel(center(&image), [0, 0, 255, 255], 0);
+}
+
+#[test]
+fn pass_uniform_can_grow() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+    let camera = camera();
+    let post_processor = PostProcessor::new(&renderer);
+    let pass = Rc::new(FillPass {
+        color: RefCell::new([1.0, 0.0, 0.0, 1.0]),
+        padding: RefCell::new(0),
+    });
+
+    post_processor.add_pass(Rc::clone(&pass) as _);
+
+    let image = post_process(&post_processor, &renderer, &scene, &camera);
+
+    assert_pixel(center(&image), [255, 0, 0, 255], 0);
+
+    *pass.color.borrow_mut() = [0.0, 1.0, 0.0, 1.0];
+    *pass.padding.borrow_mut() = 240;
+
+    let image = post_process(&post_processor, &renderer, &scene, &camera);
+
+    assert_pixel(center(&image), [0, 255, 0, 255], 0);
+}
+
+#[test]
+fn passes_read_scene_depth() {
+    for sample_count in [1, 4] {
+        let Some(mut renderer) = renderer_with_options(ForwardRendererOptions {
+            sample_count,
+            ..Default::default()
+        }) else {
+            return;
+        };
+        let scene = scene();
+        let camera = camera();
+        let post_processor = PostProcessor::new(&renderer);
+
+        // The OpenGL backend cannot read multisampled depth, so nothing is
+        // drawn according to it.
+        let drawn = if renderer.sample_count() > 1
+            && renderer.gpu.adapter.get_info().backend == wgpu::Backend::Gl
+        {
+            [0, 0, 0, 255]
+        } else {
+            [255, 255, 255, 255]
+        };
+
+        add_box(&scene, basic(RED), (0.0, 0.0, 0.0));
+        post_processor.add_pass(Rc::new(DepthPass));
+
+        let image = post_process(&post_processor, &renderer, &scene, &camera);
+
+        assert_pixel(center(&image), drawn, 0);
+        assert_pixel(pixel(&image, 0, 0), [0, 0, 0, 255], 0);
+
+        // The depth texture is replaced when the renderer is resized.
+        renderer.set_size(WIDTH, HEIGHT);
+
+        let image = post_process(&post_processor, &renderer, &scene, &camera);
+
+        assert_pixel(center(&image), drawn, 0);
+        assert_pixel(pixel(&image, 0, 0), [0, 0, 0, 255], 0);
+    }
+}
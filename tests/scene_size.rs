@@ -0,0 +1,80 @@
+mod common;
+
+use std::rc::Rc;
+
+use common::*;
+use poli_gon::{BufferGeometry, Mesh, Object3d};
+
+#[test]
+fn hidden_mesh_is_drawn_where_it_is_when_shown_again() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+    let camera = camera();
+    let mesh = add_box(&scene, basic(RED), (0.0, 0.0, 0.0));
+
+    assert_pixel(
+        center(&render(&renderer, &scene, &camera)),
+        [255, 0, 0, 255],
+        0,
+    );
+
+    *mesh.visible.borrow_mut() = false;
+    Object3d::translate(&mesh, &(1.5, 0.0, 0.0).into());
+
+    // Enough other meshes to grow the renderer's buffers while the mesh is
+    // hidden. They are too small to cover any pixel.
+    for i in 0..500 {
+        let small = add_box(&scene, basic(GREEN), (-2.0, 1.0, -i as f32 * 0.01));
+
+        Object3d::scale(&small, &(0.001, 0.001, 0.001).into());
+    }
+
+    let image = render(&renderer, &scene, &camera);
+
+    assert_pixel(center(&image), [0, 0, 0, 255], 0);
+    assert_eq!(renderer.info().rendered, 500);
+
+    *mesh.visible.borrow_mut() = true;
+
+    let image = render(&renderer, &scene, &camera);
+
+    assert_pixel(center(&image), [0, 0, 0, 255], 0);
+    assert_pixel(
+        pixel(&image, WIDTH / 2 + 12, HEIGHT / 2),
+        [255, 0, 0, 255],
+        0,
+    );
+}
+
+#[test]
+fn meshes_past_the_old_fixed_capacities_are_drawn_completely() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+    let camera = camera();
+
+    // Degenerate triangles followed by a quad in front of the camera, which is
+    // only drawn if all vertices are uploaded.
+    let vertex_count = (1 << 19) + 6;
+    let mut position = vec![0.0; 3 * (vertex_count - 6)];
+
+    position.extend_from_slice(&[
+        -1.0, -1.0, 0.0, 1.0, -1.0, 0.0, 1.0, 1.0, 0.0, //
+        -1.0, -1.0, 0.0, 1.0, 1.0, 0.0, -1.0, 1.0, 0.0,
+    ]);
+
+    let geometry = BufferGeometry::new(
+        position,
+        vec![0.0; 3 * vertex_count],
+        vec![0.0; 2 * vertex_count],
+        None,
+    );
+    let mesh = Rc::new(Object3d::from(Mesh::new(Rc::new(geometry), basic(RED))));
+
+    Object3d::add(&scene, &mesh);
+
+    assert_pixel(
+        center(&render(&renderer, &scene, &camera)),
+        [255, 0, 0, 255],
+        0,
+    );
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use std::rc::Rc;
+
+use common::*;
+use poli_gon::{LambertMaterial, Light, LightKind, Object3d};
+use poli_math::Color;
+
+/// Adds a white wall facing the camera at `z = -1`, which receives shadows.
+fn add_wall(scene: &Rc<Object3d>) {
+    let wall = add_box(
+        scene,
+        Rc::new(LambertMaterial::new(WHITE)),
+        (0.0, 0.0, -1.0),
+    );
+
+    Object3d::scale(&wall, &(20.0, 20.0, 0.1).into());
+    *wall.receive_shadow.borrow_mut() = true;
+}
+
+#[test]
+fn cascades_draw_casters_behind_the_light() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+
+    add_wall(&scene);
+
+    // The caster is off to the side, so that it does not hide its shadow.
+    let caster = add_box(&scene, basic(WHITE), (1.5, 0.0, 2.0));
+
+    *caster.cast_shadow.borrow_mut() = true;
+
+    // The light looks along -Z from between the caster and the wall.
+    let mut light = Light::new(LightKind::DirectionalLight, WHITE, 1.0);
+
+    light.cast_shadow = true;
+    light.shadow.cascades = 2;
+
+    let light = Rc::new(Object3d::from(light));
+
+    Object3d::add(&scene, &light);
+
+    let image = render(&renderer, &scene, &camera());
+    let lit = pixel(&image, WIDTH / 2 - 11, HEIGHT / 2);
+    let shadowed = pixel(&image, WIDTH / 2 + 11, HEIGHT / 2);
+
+    assert!(lit[0] > 200, "the wall is not lit: {lit:?}");
+    assert_pixel(shadowed, [0, 0, 0, 255], 0);
+}
+
+/// Adds a point light of the specified color that casts shadows, with a box
+/// between it and the wall.
+fn add_point_light_and_caster(scene: &Rc<Object3d>, color: Color, x: f32) {
+    let mut light = Light::new(
+        LightKind::PointLight {
+            range: 0.0,
+            decay: 0.0,
+        },
+        color,
+        1.0,
+    );
+
+    light.cast_shadow = true;
+    light.shadow.map_size = 256;
+
+    let light = Rc::new(Object3d::from(light));
+
+    Object3d::add(scene, &light);
+    Object3d::translate(&light, &(x, 0.0, 2.0).into());
+
+    let caster = add_box(scene, basic(WHITE), (x, 0.0, 0.5));
+
+    *caster.cast_shadow.borrow_mut() = true;
+}
+
+#[test]
+fn point_lights_cast_shadows() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+
+    add_wall(&scene);
+    add_point_light_and_caster(&scene, RED, 2.0);
+    add_point_light_and_caster(&scene, BLUE, -2.0);
+
+    // The parts of the shadows that are not hidden by the boxes, where the
+    // wall is only lit by the other light.
+    let image = render(&renderer, &scene, &camera());
+    let red_shadowed = pixel(&image, WIDTH / 2 + 7, HEIGHT / 2)[0] == 0;
+    let blue_shadowed = pixel(&image, WIDTH / 2 - 7, HEIGHT / 2)[2] == 0;
+
+    if renderer
+        .gpu
+        .adapter
+        .get_downlevel_capabilities()
+        .flags
+        .contains(wgpu::DownlevelFlags::CUBE_ARRAY_TEXTURES)
+    {
+        assert!(red_shadowed && blue_shadowed);
+    } else {
+        // Only one point light casts shadows.
+        assert!(red_shadowed != blue_shadowed);
+    }
+}
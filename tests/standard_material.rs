@@ -0,0 +1,128 @@
+mod common;
+
+use std::{f32::consts::PI, rc::Rc};
+
+use common::*;
+use poli_gon::{Light, LightKind, Object3d, StandardMaterial, Texture};
+use poli_math::Color;
+
+/// Returns a 1x1 texture of the specified color.
+fn texture(rgba: [u8; 4], srgb: bool) -> Option<Rc<Texture>> {
+    Some(Rc::new(Texture {
+        srgb,
+        ..Texture::from_rgba(1, 1, rgba.to_vec())
+    }))
+}
+
+/// Renders a box with the specified material, lit by a white ambient light
+/// whose diffuse reflection is the base color, and returns the center pixel.
+fn render_material(material: StandardMaterial) -> Option<[u8; 4]> {
+    let renderer = renderer()?;
+    let scene = scene();
+    let light = Rc::new(Object3d::from(Light::new(
+        LightKind::AmbientLight,
+        WHITE,
+        PI,
+    )));
+
+    Object3d::add(&scene, &light);
+    add_box(&scene, Rc::new(material), (0.0, 0.0, 0.0));
+
+    Some(center(&render(&renderer, &scene, &camera())))
+}
+
+/// Returns a dielectric material with the specified base color.
+fn dielectric(color: Color) -> StandardMaterial {
+    let material = StandardMaterial::new(color);
+
+    *material.metallic.borrow_mut() = 0.0;
+
+    material
+}
+
+#[test]
+fn map_multiplies_base_color() {
+    let material = dielectric(WHITE);
+
+    *material.map.borrow_mut() = texture([0, 255, 0, 255], true);
+
+    let Some(color) = render_material(material) else {
+        return;
+    };
+
+    assert_pixel(color, [0, 255, 0, 255], 2);
+}
+
+#[test]
+fn metallic_roughness_map_multiplies_metallic() {
+    // Metals have no diffuse reflection, so they are black under ambient light.
+    let Some(metal) = render_material(StandardMaterial::new(WHITE)) else {
+        return;
+    };
+
+    assert_pixel(metal, [0, 0, 0, 255], 0);
+
+    let material = StandardMaterial::new(WHITE);
+
+    *material.metallic_roughness_map.borrow_mut() = texture([0, 255, 0, 255], false);
+
+    let Some(dielectric) = render_material(material) else {
+        return;
+    };
+
+    assert_pixel(dielectric, [255, 255, 255, 255], 2);
+}
+
+#[test]
+fn emissive_map_multiplies_emissive() {
+    let material = dielectric(BLACK);
+
+    *material.emissive.borrow_mut() = WHITE;
+    *material.emissive_map.borrow_mut() = texture([255, 0, 0, 255], true);
+
+    let Some(color) = render_material(material) else {
+        return;
+    };
+
+    assert_pixel(color, [255, 0, 0, 255], 2);
+}
+
+#[test]
+fn occlusion_map_occludes_ambient_light() {
+    let material = dielectric(WHITE);
+
+    *material.occlusion_map.borrow_mut() = texture([0, 255, 255, 255], false);
+
+    let Some(color) = render_material(material) else {
+        return;
+    };
+
+    assert_pixel(color, [0, 0, 0, 255], 0);
+}
+
+#[test]
+fn occlusion_strength_scales_occlusion_map() {
+    // Without a map, the strength has no effect.
+    let material = dielectric(WHITE);
+
+    *material.occlusion.borrow_mut() = 0.5;
+
+    let Some(color) = render_material(material) else {
+        return;
+    };
+
+    assert_pixel(color, [255, 255, 255, 255], 2);
+
+    // Half of the occlusion of a mid-grey map lets 0.75 of the light through,
+    // which is 225 in sRGB.
+    let material = dielectric(WHITE);
+
+    *material.occlusion.borrow_mut() = 0.5;
+    *material.occlusion_map.borrow_mut() = texture([128, 128, 128, 255], false);
+
+    let Some(color) = render_material(material) else {
+        return;
+    };
+
+    assert_pixel(color, [225, 225, 225, 255], 2);
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use common::*;
+use poli_gon::ToneMapping;
+
+/// Returns the color of the center of a white box exposed 4 times brighter,
+/// i.e. with a linear HDR color of `4.0`, with the specified tone mapping.
+fn tone_mapped_white(tone_mapping: ToneMapping) -> Option<[u8; 4]> {
+    let mut renderer = renderer()?;
+    let scene = scene();
+
+    renderer.tone_mapping = tone_mapping;
+    renderer.exposure = 4.0;
+    add_box(&scene, basic(WHITE), (0.0, 0.0, 0.0));
+
+    Some(center(&render(&renderer, &scene, &camera())))
+}
+
+#[test]
+fn tone_mapping_maps_bright_colors() {
+    let Some(none) = tone_mapped_white(ToneMapping::None) else {
+        return;
+    };
+
+    assert_pixel(none, [255, 255, 255, 255], 0);
+
+    // 4 / (1 + 4) = 0.8 in linear space is 231 in sRGB.
+    let reinhard = tone_mapped_white(ToneMapping::Reinhard).unwrap();
+
+    assert_pixel(reinhard, [231, 231, 231, 255], 2);
+
+    let aces_filmic = tone_mapped_white(ToneMapping::AcesFilmic).unwrap();
+    let agx = tone_mapped_white(ToneMapping::Agx).unwrap();
+
+    for color in [aces_filmic, agx] {
+        assert!(color[0] < 255 && color[0] > 128);
+        assert_pixel(color, [color[0], color[0], color[0], 255], 1);
+    }
+
+    assert_ne!(aces_filmic, agx);
+    assert_ne!(aces_filmic, reinhard);
+    assert_ne!(agx, reinhard);
+}
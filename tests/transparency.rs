@@ -0,0 +1,77 @@
+mod common;
+
+use std::rc::Rc;
+
+use common::*;
+use poli_gon::{BasicMaterial, BlendMode, MaterialSettings, Object3d};
+use poli_math::Color;
+
+/// Adds a large box of the specified color and settings that covers the view,
+/// with its front face at `z`.
+fn add_wall(scene: &Rc<Object3d>, color: Color, z: f32, settings: MaterialSettings) {
+    let material = BasicMaterial::new(color);
+
+    *material.settings.borrow_mut() = settings;
+
+    let wall = add_box(scene, Rc::new(material), (0.0, 0.0, z - 0.05));
+
+    Object3d::scale(&wall, &(20.0, 20.0, 0.1).into());
+}
+
+fn transparent(opacity: f32, blend_mode: BlendMode) -> MaterialSettings {
+    MaterialSettings {
+        opacity,
+        transparent: true,
+        blend_mode,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn transparent_material_blends_with_colors_behind() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+
+    // The transparent wall is added first, but drawn after the opaque one.
+    add_wall(&scene, RED, 0.0, transparent(0.5, BlendMode::Normal));
+    add_wall(&scene, BLUE, -1.0, MaterialSettings::default());
+
+    let [r, g, b, _] = center(&render(&renderer, &scene, &camera()));
+
+    // Half of each color in linear space is 188 in sRGB.
+    assert!(r.abs_diff(188) <= 3 && b.abs_diff(188) <= 3 && g == 0);
+}
+
+#[test]
+fn opaque_material_ignores_opacity() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+
+    add_wall(
+        &scene,
+        RED,
+        0.0,
+        MaterialSettings {
+            opacity: 0.5,
+            ..Default::default()
+        },
+    );
+    add_wall(&scene, BLUE, -1.0, MaterialSettings::default());
+
+    let [r, g, b, _] = center(&render(&renderer, &scene, &camera()));
+
+    assert_eq!([r, g, b], [255, 0, 0]);
+}
+
+#[test]
+fn additive_blend_mode_adds_colors() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+
+    add_wall(&scene, GREEN, 0.0, transparent(1.0, BlendMode::Additive));
+    add_wall(&scene, RED, -1.0, MaterialSettings::default());
+
+    let image = render(&renderer, &scene, &camera());
+
+    assert_pixel(center(&image), [255, 255, 0, 255], 1);
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use std::rc::Rc;
+
+use common::*;
+use poli_gon::{BasicMaterial, NormalMaterial};
+
+#[test]
+fn wireframe_draws_only_edges() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+    let material = Rc::new(BasicMaterial::new(RED));
+
+    material.settings.borrow_mut().wireframe = true;
+    add_box(&scene, Rc::clone(&material) as _, (0.0, 0.0, 0.0));
+
+    // Between the left edge and the diagonals of the front face.
+    let (x, y) = (WIDTH / 2 - 3, HEIGHT / 2);
+
+    let image = render(&renderer, &scene, &camera());
+
+    assert_pixel(pixel(&image, x, y), [0, 0, 0, 255], 0);
+    assert_pixel(pixel(&image, x - 2, y), [255, 0, 0, 255], 0);
+
+    material.settings.borrow_mut().wireframe = false;
+
+    let image = render(&renderer, &scene, &camera());
+
+    assert_pixel(pixel(&image, x, y), [255, 0, 0, 255], 0);
+}
+
+#[test]
+fn normal_material_is_drawn_as_wireframe() {
+    let Some(renderer) = renderer() else { return };
+    let scene = scene();
+    let material = NormalMaterial::new();
+
+    material.settings.borrow_mut().wireframe = true;
+    add_box(&scene, Rc::new(material), (0.0, 0.0, 0.0));
+
+    let image = render(&renderer, &scene, &camera());
+
+    assert_pixel(pixel(&image, WIDTH / 2 - 3, HEIGHT / 2), [0, 0, 0, 255], 0);
+    assert_ne!(pixel(&image, WIDTH / 2 - 5, HEIGHT / 2), [0, 0, 0, 255]);
+}